@@ -1,7 +1,19 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::Error;
+
 const MULTICAST_BIT: u8 = 0x01;
 const LOCAL_BIT: u8 = 0x02;
 
-#[derive(Debug, PartialEq)]
+/// Largest value representable in 48 bits
+const MAX_U64: u64 = 0xFFFF_FFFF_FFFF;
+
+/// 48-bit IEEE 802 MAC address
+///
+/// Displays as lowercase colon-separated hex (`02:00:5e:10:00:01`); see
+/// [`MacAddr::notation`] for the other formats accepted by `from_str`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MacAddr(pub u8, pub u8, pub u8, pub u8, pub u8, pub u8);
 
 impl MacAddr {
@@ -36,11 +48,145 @@ impl MacAddr {
     pub fn is_universal(&self) -> bool {
         !self.is_local()
     }
+
+    /// Format the address in the given notation
+    pub fn notation(&self, notation: MacNotation) -> MacAddrDisplay {
+        MacAddrDisplay {
+            octets: self.octets(),
+            notation,
+        }
+    }
+}
+
+/// Textual notation of a MAC address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacNotation {
+    /// `aa:bb:cc:dd:ee:ff`
+    Colon,
+    /// `aa-bb-cc-dd-ee-ff` (IEEE 802 canonical form)
+    Hyphen,
+    /// `aabb.ccdd.eeff` (Cisco)
+    Dot,
+    /// `aabbccddeeff`
+    Bare,
+}
+
+impl MacNotation {
+    /// Separator written after the hex digit at `index` (0-11), if any
+    fn separator_after(&self, index: usize) -> Option<u8> {
+        let (separator, group) = match self {
+            MacNotation::Colon => (b':', 2),
+            MacNotation::Hyphen => (b'-', 2),
+            MacNotation::Dot => (b'.', 4),
+            MacNotation::Bare => return None,
+        };
+        (index < 11 && (index + 1).is_multiple_of(group)).then_some(separator)
+    }
+}
+
+/// Formatter returned by [`MacAddr::notation`]
+#[derive(Debug, Clone, Copy)]
+pub struct MacAddrDisplay {
+    octets: [u8; 6],
+    notation: MacNotation,
+}
+
+impl fmt::Display for MacAddrDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for index in 0..12 {
+            let octet = self.octets[index / 2];
+            let nibble = if index % 2 == 0 {
+                octet >> 4
+            } else {
+                octet & 0x0F
+            };
+            write!(f, "{nibble:x}")?;
+            if let Some(separator) = self.notation.separator_after(index) {
+                write!(f, "{}", separator as char)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.notation(MacNotation::Colon).fmt(f)
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = Error;
+
+    /// Parse any [`MacNotation`], with hex digits in either case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        let notation = match bytes.len() {
+            17 if bytes[2] == b':' => MacNotation::Colon,
+            17 => MacNotation::Hyphen,
+            14 => MacNotation::Dot,
+            12 => MacNotation::Bare,
+            _ => return Err(Error::BadMacAddr),
+        };
+
+        let mut octets = [0u8; 6];
+        let mut chars = bytes.iter();
+        for index in 0..12 {
+            let digit = chars.next().and_then(|&c| (c as char).to_digit(16));
+            let digit = digit.ok_or(Error::BadMacAddr)? as u8;
+            octets[index / 2] = (octets[index / 2] << 4) | digit;
+
+            if let Some(separator) = notation.separator_after(index)
+                && chars.next() != Some(&separator)
+            {
+                return Err(Error::BadMacAddr);
+            }
+        }
+
+        Ok(MacAddr::from(octets))
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        let [o1, o2, o3, o4, o5, o6] = octets;
+        MacAddr(o1, o2, o3, o4, o5, o6)
+    }
+}
+
+impl From<MacAddr> for [u8; 6] {
+    fn from(mac: MacAddr) -> Self {
+        mac.octets()
+    }
+}
+
+impl From<MacAddr> for u64 {
+    fn from(mac: MacAddr) -> Self {
+        let [o1, o2, o3, o4, o5, o6] = mac.octets();
+        u64::from_be_bytes([0, 0, o1, o2, o3, o4, o5, o6])
+    }
+}
+
+impl TryFrom<u64> for MacAddr {
+    type Error = Error;
+
+    /// Convert the low 48 bits, failing if any higher bit is set
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        if value > MAX_U64 {
+            return Err(Error::BadMacAddr);
+        }
+        let bytes = value.to_be_bytes();
+        Ok(MacAddr(
+            bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::collections::BTreeMap;
+    use alloc::string::ToString;
 
     #[test]
     fn test_new() {
@@ -108,4 +254,110 @@ mod tests {
         let mac = MacAddr::new(0x02, 0x02, 0x03, 0x04, 0x05, 0x06);
         assert!(!mac.is_universal());
     }
+
+    #[test]
+    fn test_display() {
+        let mac = MacAddr::new(0x02, 0x00, 0x5E, 0x10, 0x00, 0xAB);
+        let expect = "02:00:5e:10:00:ab";
+        let actual = mac.to_string();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_display_notations() {
+        let mac = MacAddr::new(0x02, 0x00, 0x5E, 0x10, 0x00, 0xAB);
+        assert_eq!(
+            "02-00-5e-10-00-ab",
+            mac.notation(MacNotation::Hyphen).to_string()
+        );
+        assert_eq!("0200.5e10.00ab", mac.notation(MacNotation::Dot).to_string());
+        assert_eq!("02005e1000ab", mac.notation(MacNotation::Bare).to_string());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let expect = MacAddr::new(0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF);
+        for s in [
+            "aa:bb:cc:dd:ee:ff",
+            "AA:BB:CC:DD:EE:FF",
+            "aa-bb-cc-dd-ee-ff",
+            "aabb.ccdd.eeff",
+            "aabbccddeeff",
+            "AaBbCcDdEeFf",
+        ] {
+            assert_eq!(Ok(expect), s.parse(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_from_str_roundtrip() {
+        let mac = MacAddr::new(0x00, 0x1B, 0x2C, 0x3D, 0x4E, 0x5F);
+        for notation in [
+            MacNotation::Colon,
+            MacNotation::Hyphen,
+            MacNotation::Dot,
+            MacNotation::Bare,
+        ] {
+            let actual: MacAddr = mac.notation(notation).to_string().parse().unwrap();
+            assert_eq!(mac, actual);
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        for s in [
+            "",
+            "aa:bb:cc:dd:ee",
+            "aa:bb:cc:dd:ee:ff:00",
+            "aa:bb-cc:dd:ee:ff",
+            "aa:bb:cc:dd:ee:gg",
+            "aa.bb.cc.dd.ee.ff",
+            "aabb:ccdd:eeff",
+            "+abbccddeeff",
+            "a:b:c:d:e:f",
+            "aa:bb:cc:dd:ee:f\u{e9}",
+        ] {
+            assert_eq!(Err(Error::BadMacAddr), s.parse::<MacAddr>(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_octet_array_conversions() {
+        let octets = [1, 2, 3, 4, 5, 6];
+        let mac = MacAddr::from(octets);
+        assert_eq!(MacAddr::new(1, 2, 3, 4, 5, 6), mac);
+        assert_eq!(octets, <[u8; 6]>::from(mac));
+    }
+
+    #[test]
+    fn test_u64_conversions() {
+        let mac = MacAddr::new(0x02, 0x00, 0x5E, 0x10, 0x00, 0xAB);
+        assert_eq!(0x0200_5E10_00AB, u64::from(mac));
+        assert_eq!(Ok(mac), MacAddr::try_from(0x0200_5E10_00AB));
+        assert_eq!(
+            Ok(MacAddr::broadcast()),
+            MacAddr::try_from(0xFFFF_FFFF_FFFF)
+        );
+        assert_eq!(
+            Err(Error::BadMacAddr),
+            MacAddr::try_from(0x1_0000_0000_0000)
+        );
+    }
+
+    #[test]
+    fn test_ordering_matches_u64() {
+        let low = MacAddr::new(0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF);
+        let high = MacAddr::new(0x01, 0x00, 0x00, 0x00, 0x00, 0x00);
+        assert!(low < high);
+        assert!(u64::from(low) < u64::from(high));
+    }
+
+    #[test]
+    fn test_map_key() {
+        let mac = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let mut flows = BTreeMap::new();
+        *flows.entry(mac).or_insert(0) += 1;
+        *flows.entry(mac).or_insert(0) += 1;
+        assert_eq!(Some(&2), flows.get(&mac));
+    }
 }
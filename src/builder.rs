@@ -0,0 +1,888 @@
+use alloc::vec::Vec;
+
+use crate::address::mac_addr::MacAddr;
+use crate::datalink::ethernet::EthernetFrame;
+use crate::datalink::ethernet::ethertype::EtherType;
+use crate::datalink::ethernet::header::EthernetHeader;
+use crate::datalink::ethernet::llc::LlcHeader;
+use crate::datalink::ethernet::vlan::VlanTag;
+use crate::error::{Error, Layer};
+use crate::network::arp::ArpPacket;
+use crate::network::ip_protocol::IpProtocol;
+use crate::network::ipv4::Ipv4Packet;
+use crate::network::ipv4::header::Ipv4Header;
+use crate::network::ipv4::options::{Ipv4Option, Ipv4Options};
+use crate::network::ipv6::Ipv6Packet;
+use crate::network::ipv6::extension::Ipv6ExtensionHeader;
+use crate::network::ipv6::header::Ipv6Header;
+use crate::transport::tcp::TcpPacket;
+use crate::transport::tcp::header::TcpHeader;
+use crate::transport::tcp::options::{TcpOption, TcpOptions};
+use crate::transport::udp::UdpPacket;
+use crate::transport::udp::header::UdpHeader;
+
+/// Layered packet builder
+///
+/// Layers are stacked from the bottom up and serialized by [`PacketBuilder::build`],
+/// which fills in every field that depends on the layers above: the EtherType,
+/// IPv4 protocol, IHL, total length and header checksum, IPv6 next header and
+/// payload length, the TCP data offset and checksum, and the UDP length and
+/// checksum.
+///
+/// ```
+/// use packet_builder::PacketBuilder;
+/// use packet_builder::address::mac_addr::MacAddr;
+///
+/// let bytes = PacketBuilder::new()
+///     .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+///     .ipv4([192, 168, 1, 100], [192, 168, 1, 1])
+///     .tcp(12345, 80)
+///     .tcp_header(|header| header.flags.syn = true)
+///     .payload(b"hello")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(14 + 20 + 20 + 5, bytes.len());
+/// assert_eq!([0x08, 0x00], bytes[12..14]); // EtherType: IPv4
+/// assert_eq!(6, bytes[14 + 9]); // IPv4 protocol: TCP
+/// ```
+pub struct PacketBuilder<'a> {
+    ethernet: Option<EthernetLayer>,
+    llc: Option<LlcHeader>,
+    network: Option<NetworkLayer>,
+    transport: Option<TransportLayer>,
+    payload: &'a [u8],
+    /// First misuse of a modifier, returned by [`PacketBuilder::build`]
+    error: Option<Error>,
+}
+
+struct EthernetLayer {
+    destination: MacAddr,
+    source: MacAddr,
+    vlan_tags: Vec<VlanTag>,
+    /// Explicit EtherType, used only without a network layer or LLC header
+    ethertype: Option<EtherType>,
+}
+
+enum NetworkLayer {
+    Ipv4 {
+        header: Ipv4Header,
+        options: Ipv4Options,
+    },
+    Ipv6 {
+        header: Ipv6Header,
+        extensions: Vec<Ipv6ExtensionHeader>,
+    },
+    Arp(ArpPacket),
+}
+
+/// Addresses covered by the transport checksum pseudo-header
+enum PseudoHeader {
+    Ipv4([u8; 4], [u8; 4]),
+    Ipv6([u8; 16], [u8; 16]),
+}
+
+impl NetworkLayer {
+    /// Pseudo-header addresses, or `None` for layers that cannot carry a transport
+    fn pseudo_header(&self) -> Option<PseudoHeader> {
+        match self {
+            NetworkLayer::Ipv4 { header, .. } => Some(PseudoHeader::Ipv4(
+                header.source_address,
+                header.destination_address,
+            )),
+            NetworkLayer::Ipv6 { header, extensions } => {
+                let destination = extensions
+                    .iter()
+                    .find_map(Ipv6ExtensionHeader::final_destination)
+                    .unwrap_or(header.destination_address);
+                Some(PseudoHeader::Ipv6(header.source_address, destination))
+            }
+            NetworkLayer::Arp(_) => None,
+        }
+    }
+}
+
+enum TransportLayer {
+    Tcp {
+        header: TcpHeader,
+        options: TcpOptions,
+    },
+    Udp {
+        header: UdpHeader,
+    },
+}
+
+impl Default for PacketBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> PacketBuilder<'a> {
+    /// Create a builder with no layers and an empty payload
+    pub fn new() -> Self {
+        PacketBuilder {
+            ethernet: None,
+            llc: None,
+            network: None,
+            transport: None,
+            payload: &[],
+            error: None,
+        }
+    }
+
+    /// Record a modifier called before the layer it modifies was added
+    fn missing_layer(&mut self, layer: Layer) {
+        self.error.get_or_insert(Error::MissingLayer { layer });
+    }
+
+    /// Add an Ethernet header
+    ///
+    /// The frame is Ethernet II, with the EtherType derived from the network
+    /// layer or set explicitly with [`PacketBuilder::ethertype`], unless an LLC
+    /// header makes it IEEE 802.3. With neither a network layer, an EtherType
+    /// nor an LLC header, [`PacketBuilder::build`] fails.
+    pub fn ethernet(mut self, destination: MacAddr, source: MacAddr) -> Self {
+        self.ethernet = Some(EthernetLayer {
+            destination,
+            source,
+            vlan_tags: Vec::new(),
+            ethertype: None,
+        });
+        self
+    }
+
+    /// Set the EtherType used when no network layer is stacked on Ethernet
+    pub fn ethertype(mut self, ethertype: EtherType) -> Self {
+        match self.ethernet.as_mut() {
+            Some(ethernet) => ethernet.ethertype = Some(ethertype),
+            None => self.missing_layer(Layer::Ethernet),
+        }
+        self
+    }
+
+    /// Push a VLAN tag onto the Ethernet header added by [`PacketBuilder::ethernet`]
+    ///
+    /// Tags are serialized in the order they are added, outermost first, so a
+    /// QinQ frame adds the service tag before the customer tag.
+    pub fn vlan_tag(mut self, tag: VlanTag) -> Self {
+        match self.ethernet.as_mut() {
+            Some(ethernet) => ethernet.vlan_tags.push(tag),
+            None => self.missing_layer(Layer::Ethernet),
+        }
+        self
+    }
+
+    /// Add an IEEE 802.2 LLC header, making the frame IEEE 802.3 length-framed
+    ///
+    /// The length field is computed on build. A SNAP extension with OUI
+    /// 00-00-00 gets the network layer's EtherType as its protocol ID (RFC 1042).
+    /// Without an Ethernet header, [`PacketBuilder::build`] fails.
+    pub fn llc(mut self, header: LlcHeader) -> Self {
+        match self.ethernet {
+            Some(_) => self.llc = Some(header),
+            None => self.missing_layer(Layer::Llc),
+        }
+        self
+    }
+
+    /// Add an IPv4 header (RFC 791)
+    ///
+    /// The protocol is set from the transport layer on build. Without one, it
+    /// stays at the IANA reserved value 255 unless set with
+    /// [`PacketBuilder::ipv4_header`].
+    pub fn ipv4(mut self, source: [u8; 4], destination: [u8; 4]) -> Self {
+        self.network = Some(NetworkLayer::Ipv4 {
+            header: Ipv4Header::new(source, destination, IpProtocol::Other(255)),
+            options: Ipv4Options::new(),
+        });
+        self
+    }
+
+    /// Modify the IPv4 header added by [`PacketBuilder::ipv4`]
+    ///
+    /// Fields derived from upper layers (IHL, total length, checksum, and the
+    /// protocol when a transport layer is present) are overwritten on build.
+    pub fn ipv4_header(mut self, f: impl FnOnce(&mut Ipv4Header)) -> Self {
+        match self.network.as_mut() {
+            Some(NetworkLayer::Ipv4 { header, .. }) => f(header),
+            _ => self.missing_layer(Layer::Ipv4),
+        }
+        self
+    }
+
+    /// Append an option to the IPv4 header added by [`PacketBuilder::ipv4`]
+    pub fn ipv4_option(mut self, option: Ipv4Option) -> Self {
+        match self.network.as_mut() {
+            Some(NetworkLayer::Ipv4 { options, .. }) => options.add(option),
+            _ => self.missing_layer(Layer::Ipv4),
+        }
+        self
+    }
+
+    /// Add an IPv6 header (RFC 8200)
+    pub fn ipv6(mut self, source: [u8; 16], destination: [u8; 16]) -> Self {
+        self.network = Some(NetworkLayer::Ipv6 {
+            header: Ipv6Header::new(source, destination, IpProtocol::Ipv6NoNextHeader),
+            extensions: Vec::new(),
+        });
+        self
+    }
+
+    /// Modify the IPv6 header added by [`PacketBuilder::ipv6`]
+    ///
+    /// The payload length, and the next header when a transport layer is
+    /// present, are overwritten on build.
+    pub fn ipv6_header(mut self, f: impl FnOnce(&mut Ipv6Header)) -> Self {
+        match self.network.as_mut() {
+            Some(NetworkLayer::Ipv6 { header, .. }) => f(header),
+            _ => self.missing_layer(Layer::Ipv6),
+        }
+        self
+    }
+
+    /// Append an extension header to the IPv6 header added by [`PacketBuilder::ipv6`]
+    ///
+    /// Next Header fields are chained on build, and transport checksums use the
+    /// final destination of any routing header.
+    pub fn ipv6_extension(mut self, extension: Ipv6ExtensionHeader) -> Self {
+        match self.network.as_mut() {
+            Some(NetworkLayer::Ipv6 { extensions, .. }) => extensions.push(extension),
+            _ => self.missing_layer(Layer::Ipv6),
+        }
+        self
+    }
+
+    /// Add an ARP packet (RFC 826)
+    ///
+    /// ARP carries no upper layers, so any transport layer makes
+    /// [`PacketBuilder::build`] fail and any payload is ignored.
+    pub fn arp(mut self, packet: ArpPacket) -> Self {
+        self.network = Some(NetworkLayer::Arp(packet));
+        self
+    }
+
+    /// Add a TCP header (RFC 9293)
+    pub fn tcp(mut self, source_port: u16, destination_port: u16) -> Self {
+        self.transport = Some(TransportLayer::Tcp {
+            header: TcpHeader::new(source_port, destination_port),
+            options: TcpOptions::new(),
+        });
+        self
+    }
+
+    /// Modify the TCP header added by [`PacketBuilder::tcp`]
+    ///
+    /// The data offset and checksum are overwritten on build.
+    pub fn tcp_header(mut self, f: impl FnOnce(&mut TcpHeader)) -> Self {
+        match self.transport.as_mut() {
+            Some(TransportLayer::Tcp { header, .. }) => f(header),
+            _ => self.missing_layer(Layer::Tcp),
+        }
+        self
+    }
+
+    /// Append an option to the TCP header added by [`PacketBuilder::tcp`]
+    pub fn tcp_option(mut self, option: TcpOption) -> Self {
+        match self.transport.as_mut() {
+            Some(TransportLayer::Tcp { options, .. }) => options.add(option),
+            _ => self.missing_layer(Layer::Tcp),
+        }
+        self
+    }
+
+    /// Add a UDP header (RFC 768)
+    pub fn udp(mut self, source_port: u16, destination_port: u16) -> Self {
+        self.transport = Some(TransportLayer::Udp {
+            header: UdpHeader::new(source_port, destination_port),
+        });
+        self
+    }
+
+    /// Modify the UDP header added by [`PacketBuilder::udp`]
+    ///
+    /// The length and checksum are overwritten on build.
+    pub fn udp_header(mut self, f: impl FnOnce(&mut UdpHeader)) -> Self {
+        match self.transport.as_mut() {
+            Some(TransportLayer::Udp { header }) => f(header),
+            _ => self.missing_layer(Layer::Udp),
+        }
+        self
+    }
+
+    /// Set the payload carried by the topmost layer
+    pub fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Serialize all layers, filling in lengths, protocol numbers and checksums
+    ///
+    /// Fails with [`Error::MissingLayer`] if a modifier was called before the
+    /// layer it modifies was added.
+    pub fn build(self) -> Result<Vec<u8>, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let pseudo_header = self.network.as_ref().and_then(NetworkLayer::pseudo_header);
+        let transport_bytes = match self.transport {
+            Some(TransportLayer::Tcp { header, options }) => {
+                let mut packet = TcpPacket {
+                    header,
+                    options,
+                    payload: self.payload,
+                };
+                let bytes = match pseudo_header {
+                    Some(PseudoHeader::Ipv4(src, dst)) => packet.to_bytes_ipv4(src, dst)?,
+                    Some(PseudoHeader::Ipv6(src, dst)) => packet.to_bytes_ipv6(src, dst)?,
+                    None => return Err(Error::MissingNetworkLayer { layer: Layer::Tcp }),
+                };
+                Some((IpProtocol::Tcp, bytes))
+            }
+            Some(TransportLayer::Udp { header }) => {
+                let mut packet = UdpPacket {
+                    header,
+                    payload: self.payload,
+                };
+                let bytes = match pseudo_header {
+                    Some(PseudoHeader::Ipv4(src, dst)) => packet.to_bytes_ipv4(src, dst)?,
+                    Some(PseudoHeader::Ipv6(src, dst)) => packet.to_bytes_ipv6(src, dst)?,
+                    None => return Err(Error::MissingNetworkLayer { layer: Layer::Udp }),
+                };
+                Some((IpProtocol::Udp, bytes))
+            }
+            None => None,
+        };
+        let transport_protocol = transport_bytes.as_ref().map(|(protocol, _)| *protocol);
+        let upper_payload = transport_bytes
+            .as_ref()
+            .map_or(self.payload, |(_, bytes)| &bytes[..]);
+
+        let network_bytes = match self.network {
+            Some(NetworkLayer::Ipv4 {
+                mut header,
+                options,
+            }) => {
+                if let Some(protocol) = transport_protocol {
+                    header.protocol = protocol;
+                }
+                let mut packet = Ipv4Packet {
+                    header,
+                    options,
+                    payload: upper_payload,
+                };
+                Some((EtherType::Ipv4, packet.to_bytes()?))
+            }
+            Some(NetworkLayer::Ipv6 {
+                mut header,
+                extensions,
+            }) => {
+                if let Some(protocol) = transport_protocol {
+                    header.next_header = protocol;
+                }
+                let mut packet = Ipv6Packet {
+                    header,
+                    extensions,
+                    payload: upper_payload,
+                };
+                Some((EtherType::Ipv6, packet.to_bytes()?))
+            }
+            Some(NetworkLayer::Arp(packet)) => Some((EtherType::Arp, packet.to_bytes())),
+            None => None,
+        };
+
+        let network_ethertype = network_bytes.as_ref().map(|(ethertype, _)| *ethertype);
+        let network_payload = network_bytes
+            .as_ref()
+            .map_or(upper_payload, |(_, bytes)| &bytes[..]);
+
+        // An LLC header switches the frame to IEEE 802.3 length framing
+        let llc_bytes = self.llc.map(|mut llc| {
+            if let (Some(snap), Some(ethertype)) = (llc.snap.as_mut(), network_ethertype)
+                && snap.oui == [0; 3]
+            {
+                snap.protocol_id = ethertype.to_u16();
+            }
+            let mut bytes = llc.to_bytes();
+            bytes.extend_from_slice(network_payload);
+            bytes
+        });
+        let frame_payload = llc_bytes.as_deref().unwrap_or(network_payload);
+
+        match self.ethernet {
+            Some(ethernet) => {
+                // The 802.3 length field is filled in from the payload on write
+                let ethertype = if llc_bytes.is_some() {
+                    EtherType::Length(0)
+                } else {
+                    network_ethertype
+                        .or(ethernet.ethertype)
+                        .ok_or(Error::MissingNetworkLayer {
+                            layer: Layer::Ethernet,
+                        })?
+                };
+                let header = EthernetHeader {
+                    dst: ethernet.destination,
+                    src: ethernet.source,
+                    vlan_tags: ethernet.vlan_tags,
+                    ethertype,
+                };
+                let frame = EthernetFrame {
+                    header,
+                    payload: frame_payload,
+                };
+                frame.to_bytes(false, false)
+            }
+            None => Ok(frame_payload.to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::ipv6::options::Ipv6Options;
+    use crate::network::ipv6::routing::{RoutingHeader, SegmentRoutingHeader};
+
+    #[test]
+    fn test_packet_builder_ethernet() {
+        let expect = EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+                src: MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Ipv4,
+            },
+            payload: &[0x45, 0x00],
+        }
+        .to_bytes(false, false)
+        .unwrap();
+        let actual = PacketBuilder::new()
+            .ethernet(
+                MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+                MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+            )
+            .ethertype(EtherType::Ipv4)
+            .payload(&[0x45, 0x00])
+            .build()
+            .unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_ethernet_ipv4_tcp() {
+        let payload = b"hello";
+        let src_ip = [192, 168, 1, 100];
+        let dst_ip = [192, 168, 1, 1];
+
+        let mut tcp = TcpPacket::new(12345, 80, payload);
+        tcp.header.flags.syn = true;
+        tcp.header.window = 65535;
+        tcp.options.add(TcpOption::MaximumSegmentSize(1460));
+        let tcp_bytes = tcp.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+
+        let mut ipv4 = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Tcp, &tcp_bytes);
+        ipv4.header.time_to_live = 128;
+        let ipv4_bytes = ipv4.to_bytes().unwrap();
+
+        let expect = EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr::broadcast(),
+                src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Ipv4,
+            },
+            payload: &ipv4_bytes,
+        }
+        .to_bytes(false, false)
+        .unwrap();
+
+        let actual = PacketBuilder::new()
+            .ethernet(
+                MacAddr::broadcast(),
+                MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            )
+            .ipv4(src_ip, dst_ip)
+            .ipv4_header(|header| header.time_to_live = 128)
+            .tcp(12345, 80)
+            .tcp_header(|header| {
+                header.flags.syn = true;
+                header.window = 65535;
+            })
+            .tcp_option(TcpOption::MaximumSegmentSize(1460))
+            .payload(payload)
+            .build()
+            .unwrap();
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_fills_derived_fields() {
+        let actual = PacketBuilder::new()
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .ipv4_option(Ipv4Option::NoOperation)
+            .tcp(80, 8080)
+            .tcp_option(TcpOption::NoOperation)
+            .payload(b"data")
+            .build()
+            .unwrap();
+
+        assert_eq!(52, actual.len()); // 24-byte IPv4 + 24-byte TCP + 4-byte payload
+        assert_eq!(0x46, actual[0]); // Version 4, IHL 6
+        assert_eq!(52, u16::from_be_bytes([actual[2], actual[3]])); // Total length
+        assert_eq!(IpProtocol::Tcp.to_u8(), actual[9]);
+        assert_eq!(0x60, actual[24 + 12]); // TCP data offset 6
+
+        let mut parsed = Ipv4Packet::new([0; 4], [0; 4], IpProtocol::Tcp, &actual[24..]);
+        parsed.header = Ipv4Header::from_bytes(&actual).unwrap();
+        parsed.options = Ipv4Options::from_bytes(&actual[20..24]).unwrap();
+        let expect = parsed.calculate_header_checksum().unwrap();
+        assert_eq!(expect, parsed.header.header_checksum);
+    }
+
+    #[test]
+    fn test_packet_builder_ipv4_without_transport() {
+        let actual = PacketBuilder::new()
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .ipv4_header(|header| header.protocol = IpProtocol::Udp)
+            .payload(b"raw")
+            .build()
+            .unwrap();
+
+        assert_eq!(23, actual.len());
+        assert_eq!(17, actual[9]); // Protocol left as configured
+        assert_eq!(b"raw", &actual[20..]);
+    }
+
+    #[test]
+    fn test_packet_builder_ipv4_default_protocol() {
+        let actual = PacketBuilder::new()
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .build()
+            .unwrap();
+
+        assert_eq!(20, actual.len());
+        assert_eq!(255, actual[9]); // Reserved, not Hop-by-Hop (0)
+    }
+
+    #[test]
+    fn test_packet_builder_ipv4_udp() {
+        let src_ip = [192, 168, 1, 1];
+        let dst_ip = [192, 168, 1, 2];
+        let udp_bytes = UdpPacket::new(53, 12345, b"hello")
+            .to_bytes_ipv4(src_ip, dst_ip)
+            .unwrap();
+        let expect = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Udp, &udp_bytes)
+            .to_bytes()
+            .unwrap();
+
+        let actual = PacketBuilder::new()
+            .ipv4(src_ip, dst_ip)
+            .udp(53, 12345)
+            .payload(b"hello")
+            .build()
+            .unwrap();
+
+        assert_eq!(expect, actual);
+        assert_eq!([0x08, 0x40], actual[20 + 6..20 + 8]); // UDP checksum
+    }
+
+    #[test]
+    fn test_packet_builder_ethernet_ipv6_tcp() {
+        let mut src_ip = [0u8; 16];
+        let mut dst_ip = [0u8; 16];
+        src_ip[..2].copy_from_slice(&[0xfe, 0x80]);
+        src_ip[15] = 1;
+        dst_ip[..2].copy_from_slice(&[0xfe, 0x80]);
+        dst_ip[15] = 2;
+
+        let mut tcp = TcpPacket::new(443, 50000, b"data");
+        tcp.header.flags.ack = true;
+        let tcp_bytes = tcp.to_bytes_ipv6(src_ip, dst_ip).unwrap();
+
+        let mut ipv6 = Ipv6Packet::new(src_ip, dst_ip, IpProtocol::Tcp, &tcp_bytes);
+        ipv6.header.hop_limit = 255;
+        let ipv6_bytes = ipv6.to_bytes().unwrap();
+
+        let expect = EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x02),
+                src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Ipv6,
+            },
+            payload: &ipv6_bytes,
+        }
+        .to_bytes(false, false)
+        .unwrap();
+
+        let actual = PacketBuilder::new()
+            .ethernet(
+                MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x02),
+                MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            )
+            .ipv6(src_ip, dst_ip)
+            .ipv6_header(|header| header.hop_limit = 255)
+            .tcp(443, 50000)
+            .tcp_header(|header| header.flags.ack = true)
+            .payload(b"data")
+            .build()
+            .unwrap();
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_ipv6_extensions_udp() {
+        let src_ip = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let segment = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9];
+        let final_ip = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+        let srh = SegmentRoutingHeader::new(alloc::vec![final_ip, segment]);
+
+        let actual = PacketBuilder::new()
+            .ipv6(src_ip, segment)
+            .ipv6_extension(Ipv6ExtensionHeader::HopByHop(Ipv6Options::new()))
+            .ipv6_extension(Ipv6ExtensionHeader::Routing(RoutingHeader::SegmentRouting(
+                srh,
+            )))
+            .udp(53, 12345)
+            .payload(b"hello")
+            .build()
+            .unwrap();
+
+        let parsed = Ipv6Packet::from_bytes(&actual).unwrap();
+        assert_eq!(0, actual[6]); // Next Header: Hop-by-Hop
+        assert_eq!(IpProtocol::Udp, parsed.header.next_header);
+        assert_eq!(2, parsed.extensions.len());
+        assert_eq!(final_ip, parsed.final_destination());
+
+        let udp = UdpPacket::from_bytes(parsed.payload).unwrap();
+        let verification = udp.verify_checksum_ipv6(src_ip, final_ip);
+        assert!(verification.is_valid());
+    }
+
+    #[test]
+    fn test_packet_builder_ipv6_without_transport() {
+        let actual = PacketBuilder::new()
+            .ipv6([0; 16], [0; 16])
+            .payload(b"raw")
+            .build()
+            .unwrap();
+
+        assert_eq!(43, actual.len());
+        assert_eq!(3, u16::from_be_bytes([actual[4], actual[5]])); // Payload length
+        assert_eq!(IpProtocol::Ipv6NoNextHeader.to_u8(), actual[6]);
+    }
+
+    #[test]
+    fn test_packet_builder_ethernet_arp() {
+        let sender_mac = MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01);
+        let sender_ip = [192, 168, 1, 100];
+        let target_ip = [192, 168, 1, 1];
+        let arp_bytes = ArpPacket::request(
+            MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            sender_ip,
+            target_ip,
+        )
+        .to_bytes();
+
+        let expect = EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr::broadcast(),
+                src: MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Arp,
+            },
+            payload: &arp_bytes,
+        }
+        .to_bytes(false, false)
+        .unwrap();
+
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), sender_mac)
+            .arp(ArpPacket::request(
+                MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+                sender_ip,
+                target_ip,
+            ))
+            .build()
+            .unwrap();
+
+        assert_eq!(expect, actual);
+        assert_eq!(42, actual.len());
+    }
+
+    #[test]
+    fn test_packet_builder_qinq_ipv4() {
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .vlan_tag(VlanTag::service(300))
+            .vlan_tag(VlanTag::new(10))
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .payload(b"raw")
+            .build()
+            .unwrap();
+
+        assert_eq!(22 + 20 + 3, actual.len());
+        assert_eq!(
+            [0x88, 0xA8, 0x01, 0x2C, 0x81, 0x00, 0x00, 0x0A, 0x08, 0x00],
+            actual[12..22]
+        );
+
+        let frame = EthernetFrame::from_bytes(&actual).unwrap();
+        assert_eq!(2, frame.header.vlan_tags.len());
+        assert_eq!(0x45, frame.payload[0]);
+    }
+
+    #[test]
+    fn test_packet_builder_802_3_llc() {
+        let bpdu = [0x00, 0x00, 0x00, 0x00];
+        let actual = PacketBuilder::new()
+            .ethernet(
+                MacAddr::new(0x01, 0x80, 0xC2, 0x00, 0x00, 0x00),
+                MacAddr::new(0x02, 0, 0, 0, 0, 1),
+            )
+            .llc(LlcHeader::new(0x42, 0x42))
+            .payload(&bpdu)
+            .build()
+            .unwrap();
+
+        assert_eq!(14 + 3 + 4, actual.len());
+        assert_eq!([0x00, 0x07, 0x42, 0x42, 0x03], actual[12..17]);
+    }
+
+    #[test]
+    fn test_packet_builder_802_3_payload_too_long() {
+        // 3-byte LLC header plus 1600 bytes of data exceeds the 1500-byte length field
+        let data = [0u8; 1600];
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Ethernet,
+            length: 1603,
+        });
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .llc(LlcHeader::new(0x42, 0x42))
+            .payload(&data)
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_snap_ipv4() {
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .llc(LlcHeader::snap([0; 3], 0))
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .build()
+            .unwrap();
+
+        let frame = EthernetFrame::from_bytes(&actual).unwrap();
+        assert_eq!(EtherType::Length(28), frame.header.ethertype);
+
+        let (llc, consumed) = LlcHeader::from_bytes(frame.payload).unwrap();
+        let snap = llc.snap.unwrap();
+        assert_eq!(0x0800, snap.protocol_id);
+        assert_eq!(0x45, frame.payload[consumed]);
+    }
+
+    #[test]
+    fn test_packet_builder_udp_over_arp() {
+        let expect = Err(Error::MissingNetworkLayer { layer: Layer::Udp });
+        let actual = PacketBuilder::new()
+            .arp(ArpPacket::probe(MacAddr::broadcast(), [10, 0, 0, 1]))
+            .udp(53, 53)
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_tcp_without_network() {
+        let expect = Err(Error::MissingNetworkLayer { layer: Layer::Tcp });
+        let actual = PacketBuilder::new().tcp(80, 8080).build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_ethernet_without_ethertype() {
+        let expect = Err(Error::MissingNetworkLayer {
+            layer: Layer::Ethernet,
+        });
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .payload(b"raw")
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_modifier_without_layer() {
+        let expect = Err(Error::MissingLayer { layer: Layer::Tcp });
+        let actual = PacketBuilder::new()
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .tcp_option(TcpOption::NoOperation)
+            .tcp(80, 8080)
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_modifier_wrong_layer() {
+        let expect = Err(Error::MissingLayer { layer: Layer::Ipv4 });
+        let actual = PacketBuilder::new()
+            .ipv6([0; 16], [0; 16])
+            .ipv4_option(Ipv4Option::NoOperation)
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_vlan_id_out_of_range() {
+        let expect = Err(Error::BadVlanTag {
+            pcp: 0,
+            vid: 0x1000,
+        });
+        let actual = PacketBuilder::new()
+            .ethernet(MacAddr::broadcast(), MacAddr::new(0x02, 0, 0, 0, 0, 1))
+            .vlan_tag(VlanTag::new(0x1000))
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_vlan_tag_without_ethernet() {
+        let expect = Err(Error::MissingLayer {
+            layer: Layer::Ethernet,
+        });
+        let actual = PacketBuilder::new()
+            .vlan_tag(VlanTag::new(10))
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_llc_without_ethernet() {
+        let expect = Err(Error::MissingLayer { layer: Layer::Llc });
+        let actual = PacketBuilder::new()
+            .llc(LlcHeader::new(0x42, 0x42))
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .build();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_packet_builder_ipv4_total_length_overflow() {
+        let payload = alloc::vec![0; 65516];
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Ipv4,
+            length: 65536,
+        });
+        let actual = PacketBuilder::new()
+            .ipv4([10, 0, 0, 1], [10, 0, 0, 2])
+            .payload(&payload)
+            .build();
+        assert_eq!(expect, actual);
+    }
+}
@@ -0,0 +1,370 @@
+//! Internet checksum helpers
+//!
+//! Reference: RFC 1071 - Computing the Internet Checksum
+//!
+//! ```
+//! use packet_builder::checksum::{self, Checksum};
+//!
+//! let mut checksum = Checksum::new();
+//! checksum.add_bytes(&[0x00, 0x01, 0xf2, 0x03]);
+//! checksum.add_bytes(&[0xf4, 0xf5, 0xf6, 0xf7]);
+//! let value = checksum.finish();
+//! assert_eq!(0x220d, value); // RFC 1071 Section 3 example
+//!
+//! // Patch the checksum after rewriting the first word from 0x0001 to 0x0002
+//! let patched = checksum::update_u16(value, 0x0001, 0x0002);
+//! assert_eq!(0x220c, patched);
+//! ```
+
+use crate::network::ip_protocol::IpProtocol;
+
+/// Streaming one's complement sum accumulator
+///
+/// Bytes may be added in arbitrarily sized pieces; an odd trailing byte is
+/// held back and paired with the first byte of the next piece, so the result
+/// is the same as summing the concatenation in one go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Checksum {
+    sum: u64,
+    pending: Option<u8>,
+}
+
+impl Checksum {
+    pub fn new() -> Self {
+        Checksum {
+            sum: 0,
+            pending: None,
+        }
+    }
+
+    /// Add bytes in network byte order
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        let mut bytes = bytes;
+
+        if let Some(high) = self.pending.take() {
+            match bytes.split_first() {
+                Some((&low, rest)) => {
+                    self.sum += u16::from_be_bytes([high, low]) as u64;
+                    bytes = rest;
+                }
+                None => {
+                    self.pending = Some(high);
+                    return;
+                }
+            }
+        }
+
+        let mut chunks = bytes.chunks_exact(2);
+        for chunk in &mut chunks {
+            self.sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u64;
+        }
+        if let [last] = chunks.remainder() {
+            self.pending = Some(*last);
+        }
+    }
+
+    /// Add a 16-bit value in network byte order
+    pub fn add_u16(&mut self, value: u16) {
+        self.add_bytes(&value.to_be_bytes());
+    }
+
+    /// Add a 32-bit value in network byte order
+    pub fn add_u32(&mut self, value: u32) {
+        self.add_bytes(&value.to_be_bytes());
+    }
+
+    /// Add the IPv4 pseudo-header used by upper-layer checksums
+    ///
+    /// Reference: RFC 9293 Section 3.1 (TCP), RFC 768 (UDP)
+    pub fn add_ipv4_pseudo_header(
+        &mut self,
+        src_ip: [u8; 4],
+        dst_ip: [u8; 4],
+        protocol: IpProtocol,
+        length: u16,
+    ) {
+        self.add_bytes(&src_ip);
+        self.add_bytes(&dst_ip);
+        self.add_bytes(&[0, protocol.to_u8()]);
+        self.add_u16(length);
+    }
+
+    /// Add the IPv6 pseudo-header used by upper-layer checksums
+    ///
+    /// Reference: RFC 8200 Section 8.1 - Upper-Layer Checksums
+    pub fn add_ipv6_pseudo_header(
+        &mut self,
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+        next_header: IpProtocol,
+        length: u32,
+    ) {
+        self.add_bytes(&src_ip);
+        self.add_bytes(&dst_ip);
+        self.add_u32(length);
+        self.add_bytes(&[0, 0, 0, next_header.to_u8()]);
+    }
+
+    /// Folded one's complement sum of everything added so far (not complemented)
+    ///
+    /// An odd trailing byte is padded with zero (RFC 1071 Section 4.1).
+    pub fn sum(&self) -> u16 {
+        let mut sum = self.sum;
+        if let Some(high) = self.pending {
+            sum += (high as u64) << 8;
+        }
+        fold(sum)
+    }
+
+    /// Finish the computation and return the checksum to store in a header
+    pub fn finish(&self) -> u16 {
+        !self.sum()
+    }
+}
+
+/// Fold a wide one's complement sum into 16 bits
+fn fold(mut sum: u64) -> u16 {
+    while (sum >> 16) != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    sum as u16
+}
+
+/// Incrementally update a checksum after a 16-bit field changed from `old` to `new`
+///
+/// Uses `HC' = ~(~HC + ~m + m')`, which unlike the earlier RFC 1141 form never
+/// produces -0 for a non-zero sum.
+///
+/// Reference: RFC 1624 Section 3, Equation 3
+pub fn update_u16(checksum: u16, old: u16, new: u16) -> u16 {
+    let sum = (!checksum) as u64 + (!old) as u64 + new as u64;
+    !fold(sum)
+}
+
+/// Incrementally update a checksum after a 32-bit field changed from `old` to `new`
+///
+/// Reference: RFC 1624 Section 3
+pub fn update_u32(checksum: u16, old: u32, new: u32) -> u16 {
+    let checksum = update_u16(checksum, (old >> 16) as u16, (new >> 16) as u16);
+    update_u16(checksum, old as u16, new as u16)
+}
+
+/// Incrementally update a checksum after a 16-bit aligned field changed from `old` to `new`
+///
+/// Suitable for addresses, e.g. when rewriting an IPv4 or IPv6 address that
+/// is covered by a header checksum or an upper-layer pseudo-header. Both
+/// slices must start at the same 16-bit alignment within the covered data.
+///
+/// Reference: RFC 1624 Section 3
+pub fn update_bytes(checksum: u16, old: &[u8], new: &[u8]) -> u16 {
+    let mut old_sum = Checksum::new();
+    old_sum.add_bytes(old);
+    let mut new_sum = Checksum::new();
+    new_sum.add_bytes(new);
+    update_u16(checksum, old_sum.sum(), new_sum.sum())
+}
+
+/// Result of verifying a stored Internet checksum
+///
+/// `expected` is the value a sender would have written into the checksum
+/// field; `stored` is the value found in the packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumVerification {
+    pub stored: u16,
+    pub expected: u16,
+}
+
+impl ChecksumVerification {
+    /// Whether the stored checksum is valid
+    ///
+    /// A receiver sums the covered data including the stored checksum and
+    /// accepts it if the result is all ones (RFC 1071 Section 1). Since
+    /// `expected` is the complement of the sum over the data alone, this is
+    /// evaluated as `!expected + stored`, which also accepts 0xFFFF where
+    /// 0x0000 was expected (+0 and -0 are equal in one's complement
+    /// arithmetic, RFC 1624 Section 3).
+    pub fn is_valid(&self) -> bool {
+        let sum = (!self.expected) as u32 + self.stored as u32;
+        let folded = (sum & 0xFFFF) + (sum >> 16);
+        folded == 0xFFFF
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes from the RFC 1071 Section 3 numerical example
+    const RFC1071_EXAMPLE: [u8; 8] = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+
+    #[test]
+    fn test_checksum_rfc1071_example() {
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&RFC1071_EXAMPLE);
+
+        assert_eq!(0xddf2, checksum.sum());
+        assert_eq!(0x220d, checksum.finish());
+    }
+
+    #[test]
+    fn test_checksum_streaming_odd_chunks() {
+        let mut expect = Checksum::new();
+        expect.add_bytes(&RFC1071_EXAMPLE);
+
+        let mut actual = Checksum::new();
+        actual.add_bytes(&RFC1071_EXAMPLE[..3]);
+        actual.add_bytes(&[]);
+        actual.add_bytes(&RFC1071_EXAMPLE[3..4]);
+        actual.add_bytes(&RFC1071_EXAMPLE[4..]);
+
+        assert_eq!(expect.finish(), actual.finish());
+    }
+
+    #[test]
+    fn test_checksum_odd_length_padding() {
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&[0x12, 0x34, 0x56]);
+
+        let expect = !(0x1234u16 + 0x5600);
+        let actual = checksum.finish();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_checksum_add_u16_u32() {
+        let mut expect = Checksum::new();
+        expect.add_bytes(&RFC1071_EXAMPLE);
+
+        let mut actual = Checksum::new();
+        actual.add_u16(0x0001);
+        actual.add_u16(0xf203);
+        actual.add_u32(0xf4f5f6f7);
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_checksum_empty() {
+        let expect = 0xFFFF;
+        let actual = Checksum::new().finish();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_checksum_ipv4_pseudo_header() {
+        let mut expect = Checksum::new();
+        expect.add_bytes(&[192, 168, 1, 1, 10, 0, 0, 1, 0x00, 6, 0x00, 0x14]);
+
+        let mut actual = Checksum::new();
+        actual.add_ipv4_pseudo_header([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Tcp, 20);
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_checksum_ipv6_pseudo_header() {
+        let mut src_ip = [0u8; 16];
+        let mut dst_ip = [0u8; 16];
+        src_ip[0] = 0x20;
+        dst_ip[15] = 0x02;
+
+        let mut expect = Checksum::new();
+        expect.add_bytes(&src_ip);
+        expect.add_bytes(&dst_ip);
+        expect.add_bytes(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 17]);
+
+        let mut actual = Checksum::new();
+        actual.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Udp, 0x10000);
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_update_u16_matches_recomputation() {
+        let mut data = RFC1071_EXAMPLE;
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&data);
+        let original = checksum.finish();
+
+        // Rewrite the word 0xf4f5 to 0x1234
+        data[4] = 0x12;
+        data[5] = 0x34;
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&data);
+
+        let expect = checksum.finish();
+        let actual = update_u16(original, 0xf4f5, 0x1234);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_update_u16_rfc1624_example() {
+        // RFC 1624 Section 4: HC = 0xDD2F, m = 0x5555 -> m' = 0x3285 gives 0x0000
+        let expect = 0x0000;
+        let actual = update_u16(0xDD2F, 0x5555, 0x3285);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_update_u32_and_bytes() {
+        let mut data = [0x45, 0x00, 0x00, 0x1c, 0xc0, 0xa8, 0x01, 0x01, 0x0a, 0x00];
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&data);
+        let original = checksum.finish();
+
+        let old_addr = [0xc0, 0xa8, 0x01, 0x01];
+        let new_addr = [0xac, 0x10, 0xfe, 0x02];
+        data[4..8].copy_from_slice(&new_addr);
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&data);
+        let expect = checksum.finish();
+
+        let actual = update_u32(
+            original,
+            u32::from_be_bytes(old_addr),
+            u32::from_be_bytes(new_addr),
+        );
+        assert_eq!(expect, actual);
+
+        let actual = update_bytes(original, &old_addr, &new_addr);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_is_valid_matching() {
+        let verification = ChecksumVerification {
+            stored: 0xB1E6,
+            expected: 0xB1E6,
+        };
+        assert!(verification.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_mismatch() {
+        let verification = ChecksumVerification {
+            stored: 0xB1E7,
+            expected: 0xB1E6,
+        };
+        assert!(!verification.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_negative_zero() {
+        // Data summing to 0xFFFF yields 0x0000; 0xFFFF is the same value (-0)
+        let verification = ChecksumVerification {
+            stored: 0xFFFF,
+            expected: 0x0000,
+        };
+        assert!(verification.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_all_zero_data() {
+        // All-zero data yields 0xFFFF; a stored 0x0000 does not verify
+        let verification = ChecksumVerification {
+            stored: 0x0000,
+            expected: 0xFFFF,
+        };
+        assert!(!verification.is_valid());
+    }
+}
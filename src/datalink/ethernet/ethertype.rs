@@ -1,44 +1,130 @@
 use core::convert::TryFrom;
 
-#[derive(Debug, PartialEq)]
+/// Largest value of the EtherType/Length field that is an IEEE 802.3 length
+///
+/// Reference: IEEE 802.3 Clause 3.2.6 - Length/Type field
+pub const MAX_LENGTH: u16 = 1500;
+
+/// Smallest value of the EtherType/Length field that is an EtherType
+pub const MIN_ETHERTYPE: u16 = 0x0600;
+
+/// Value of the EtherType/Length field following the source address
+///
+/// Values up to [`MAX_LENGTH`] are an IEEE 802.3 payload length; values from
+/// [`MIN_ETHERTYPE`] are a protocol type. Any value without a named variant
+/// (including the undefined range in between) is kept in `Other`, so every
+/// value round-trips.
+///
+/// Reference: IEEE 802.3 Clause 3.2.6 / IEEE Registration Authority EtherType registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EtherType {
     Ipv4,
-    Ipv6,
     Arp,
-    Unknown,
+    WakeOnLan,
+    Rarp,
+    /// IEEE 802.1Q customer VLAN tag TPID
+    Vlan,
+    Ipv6,
+    /// IEEE 802.3 MAC Control (Ethernet flow control)
+    FlowControl,
+    MplsUnicast,
+    MplsMulticast,
+    PppoeDiscovery,
+    PppoeSession,
+    /// IEEE 802.1X EAP over LAN
+    Eapol,
+    /// IEEE 802.1ad service VLAN tag (QinQ) TPID
+    QinQ,
+    /// IEEE 802.1AB Link Layer Discovery Protocol
+    Lldp,
+    /// IEEE 802.1AE MAC Security
+    MacSec,
+    /// IEEE 1588 Precision Time Protocol
+    Ptp,
+    /// IEEE 802.3 payload length (0..=1500)
+    Length(u16),
+    /// Any other value
+    Other(u16),
 }
 
 impl EtherType {
-    #[allow(dead_code)]
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        if let Ok(ethertype) = EtherType::try_from(bytes) {
-            ethertype
-        } else {
-            EtherType::Unknown
+    pub fn from_bytes(bytes: [u8; 2]) -> Self {
+        EtherType::from(u16::from_be_bytes(bytes))
+    }
+
+    /// Whether this is a VLAN tag TPID rather than a payload type
+    pub fn is_vlan_tag(&self) -> bool {
+        matches!(self, EtherType::Vlan | EtherType::QinQ)
+    }
+
+    /// Whether this is an IEEE 802.3 length rather than a protocol type
+    pub fn is_length(&self) -> bool {
+        matches!(self, EtherType::Length(_))
+    }
+
+    pub fn to_u16(&self) -> u16 {
+        match self {
+            EtherType::Ipv4 => 0x0800,
+            EtherType::Arp => 0x0806,
+            EtherType::WakeOnLan => 0x0842,
+            EtherType::Rarp => 0x8035,
+            EtherType::Vlan => 0x8100,
+            EtherType::Ipv6 => 0x86DD,
+            EtherType::FlowControl => 0x8808,
+            EtherType::MplsUnicast => 0x8847,
+            EtherType::MplsMulticast => 0x8848,
+            EtherType::PppoeDiscovery => 0x8863,
+            EtherType::PppoeSession => 0x8864,
+            EtherType::Eapol => 0x888E,
+            EtherType::QinQ => 0x88A8,
+            EtherType::Lldp => 0x88CC,
+            EtherType::MacSec => 0x88E5,
+            EtherType::Ptp => 0x88F7,
+            EtherType::Length(value) | EtherType::Other(value) => *value,
         }
     }
 
-    #[allow(dead_code)]
     pub fn to_bytes(&self) -> [u8; 2] {
-        match self {
-            EtherType::Ipv4 => [0x08, 0x00],
-            EtherType::Ipv6 => [0x86, 0xDD],
-            EtherType::Arp => [0x08, 0x06],
-            EtherType::Unknown => [0x00, 0x00],
+        self.to_u16().to_be_bytes()
+    }
+}
+
+impl From<u16> for EtherType {
+    fn from(value: u16) -> Self {
+        match value {
+            0x0800 => EtherType::Ipv4,
+            0x0806 => EtherType::Arp,
+            0x0842 => EtherType::WakeOnLan,
+            0x8035 => EtherType::Rarp,
+            0x8100 => EtherType::Vlan,
+            0x86DD => EtherType::Ipv6,
+            0x8808 => EtherType::FlowControl,
+            0x8847 => EtherType::MplsUnicast,
+            0x8848 => EtherType::MplsMulticast,
+            0x8863 => EtherType::PppoeDiscovery,
+            0x8864 => EtherType::PppoeSession,
+            0x888E => EtherType::Eapol,
+            0x88A8 => EtherType::QinQ,
+            0x88CC => EtherType::Lldp,
+            0x88E5 => EtherType::MacSec,
+            0x88F7 => EtherType::Ptp,
+            0..=MAX_LENGTH => EtherType::Length(value),
+            _ => EtherType::Other(value),
         }
     }
 }
 
+impl From<EtherType> for u16 {
+    fn from(ethertype: EtherType) -> Self {
+        ethertype.to_u16()
+    }
+}
+
 impl TryFrom<[u8; 2]> for EtherType {
     type Error = ();
 
     fn try_from(bytes: [u8; 2]) -> Result<Self, Self::Error> {
-        match (bytes[0], bytes[1]) {
-            (0x08, 0x00) => Ok(EtherType::Ipv4),
-            (0x86, 0xDD) => Ok(EtherType::Ipv6),
-            (0x08, 0x06) => Ok(EtherType::Arp),
-            _ => Ok(EtherType::Unknown),
-        }
+        Ok(EtherType::from_bytes(bytes))
     }
 }
 
@@ -58,11 +144,31 @@ impl TryFrom<&[u8]> for EtherType {
 mod tests {
     use super::*;
 
+    /// Every named variant with its registry value
+    const REGISTRY: [(EtherType, u16); 16] = [
+        (EtherType::Ipv4, 0x0800),
+        (EtherType::Arp, 0x0806),
+        (EtherType::WakeOnLan, 0x0842),
+        (EtherType::Rarp, 0x8035),
+        (EtherType::Vlan, 0x8100),
+        (EtherType::Ipv6, 0x86DD),
+        (EtherType::FlowControl, 0x8808),
+        (EtherType::MplsUnicast, 0x8847),
+        (EtherType::MplsMulticast, 0x8848),
+        (EtherType::PppoeDiscovery, 0x8863),
+        (EtherType::PppoeSession, 0x8864),
+        (EtherType::Eapol, 0x888E),
+        (EtherType::QinQ, 0x88A8),
+        (EtherType::Lldp, 0x88CC),
+        (EtherType::MacSec, 0x88E5),
+        (EtherType::Ptp, 0x88F7),
+    ];
+
     #[test]
     fn test_from_bytes_ipv4() {
         let bytes = [0x08, 0x00];
         let expect = EtherType::Ipv4;
-        let actual = EtherType::from_bytes(&bytes);
+        let actual = EtherType::from_bytes(bytes);
         assert_eq!(expect, actual);
     }
 
@@ -70,7 +176,7 @@ mod tests {
     fn test_from_bytes_ipv6() {
         let bytes = [0x86, 0xDD];
         let expect = EtherType::Ipv6;
-        let actual = EtherType::from_bytes(&bytes);
+        let actual = EtherType::from_bytes(bytes);
         assert_eq!(expect, actual);
     }
 
@@ -78,16 +184,55 @@ mod tests {
     fn test_from_bytes_arp() {
         let bytes = [0x08, 0x06];
         let expect = EtherType::Arp;
-        let actual = EtherType::from_bytes(&bytes);
+        let actual = EtherType::from_bytes(bytes);
         assert_eq!(expect, actual);
     }
 
     #[test]
-    fn test_from_bytes_unknown() {
-        let bytes = [0x00, 0x00];
-        let expect = EtherType::Unknown;
-        let actual = EtherType::from_bytes(&bytes);
-        assert_eq!(expect, actual);
+    fn test_from_bytes_vlan_tags() {
+        assert_eq!(EtherType::Vlan, EtherType::from_bytes([0x81, 0x00]));
+        assert_eq!(EtherType::QinQ, EtherType::from_bytes([0x88, 0xA8]));
+        assert!(EtherType::Vlan.is_vlan_tag());
+        assert!(EtherType::QinQ.is_vlan_tag());
+        assert!(!EtherType::Ipv4.is_vlan_tag());
+    }
+
+    #[test]
+    fn test_from_bytes_length() {
+        assert_eq!(EtherType::Length(0), EtherType::from_bytes([0x00, 0x00]));
+        assert_eq!(EtherType::Length(1500), EtherType::from_bytes([0x05, 0xDC]));
+        assert!(EtherType::Length(46).is_length());
+        assert!(!EtherType::Ipv4.is_length());
+    }
+
+    #[test]
+    fn test_from_bytes_other() {
+        // Between the largest length and the smallest EtherType
+        assert_eq!(
+            EtherType::Other(0x05DD),
+            EtherType::from_bytes([0x05, 0xDD])
+        );
+        assert_eq!(
+            EtherType::Other(0x1234),
+            EtherType::from_bytes([0x12, 0x34])
+        );
+        assert!(!EtherType::Other(0x05DD).is_length());
+    }
+
+    #[test]
+    fn test_registry_roundtrip() {
+        for (ethertype, value) in REGISTRY {
+            assert_eq!(value, ethertype.to_u16());
+            assert_eq!(ethertype, EtherType::from(value));
+        }
+    }
+
+    #[test]
+    fn test_every_value_roundtrips() {
+        for value in 0..=u16::MAX {
+            let actual = EtherType::from(value).to_bytes();
+            assert_eq!(value.to_be_bytes(), actual);
+        }
     }
 
     #[test]
@@ -115,9 +260,17 @@ mod tests {
     }
 
     #[test]
-    fn test_try_from_unknown() {
-        let bytes = [0x00, 0x00];
-        let expect = EtherType::Unknown;
+    fn test_try_from_other() {
+        let bytes = [0x12, 0x34];
+        let expect = EtherType::Other(0x1234);
+        let actual = EtherType::try_from(bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        let bytes: &[u8] = &[0x88, 0xCC];
+        let expect = EtherType::Lldp;
         let actual = EtherType::try_from(bytes).unwrap();
         assert_eq!(expect, actual);
     }
@@ -154,9 +307,15 @@ mod tests {
     }
 
     #[test]
-    fn test_to_bytes_unknown() {
-        let ethertype = EtherType::Unknown;
-        let expect = [0x00, 0x00];
+    fn test_to_bytes_vlan_tags() {
+        assert_eq!([0x81, 0x00], EtherType::Vlan.to_bytes());
+        assert_eq!([0x88, 0xA8], EtherType::QinQ.to_bytes());
+    }
+
+    #[test]
+    fn test_to_bytes_other() {
+        let ethertype = EtherType::Other(0x9000);
+        let expect = [0x90, 0x00];
         let actual = ethertype.to_bytes();
         assert_eq!(expect, actual);
     }
@@ -0,0 +1,117 @@
+/// Length of the Frame Check Sequence in bytes
+///
+/// Reference: IEEE 802.3 Clause 3.2.9 - Frame Check Sequence (FCS) field
+pub const FCS_LEN: usize = 4;
+
+/// Reflected CRC-32 polynomial used by IEEE 802.3 (0x04C11DB7 bit-reversed)
+const CRC32_POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Lookup table for byte-wise CRC-32 computation, generated at compile time
+const CRC32_TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ CRC32_POLYNOMIAL;
+            } else {
+                crc >>= 1;
+            }
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Calculate the IEEE 802.3 CRC-32 over the given bytes
+///
+/// The FCS covers the destination address through the end of the payload
+/// (including any padding). On the wire it is transmitted least significant
+/// byte first, so callers append `crc32(..).to_le_bytes()`.
+///
+/// Reference: IEEE 802.3 Clause 3.2.9
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.add_bytes(bytes);
+    crc.finish()
+}
+
+/// Streaming IEEE 802.3 CRC-32 accumulator
+///
+/// Lets the FCS be computed over a frame that is not contiguous in memory,
+/// e.g. header, payload and padding written separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Crc32 {
+    pub fn new() -> Self {
+        Crc32 { crc: 0xFFFF_FFFF }
+    }
+
+    /// Add bytes to the CRC
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            let index = ((self.crc ^ byte as u32) & 0xFF) as usize;
+            self.crc = (self.crc >> 8) ^ CRC32_TABLE[index];
+        }
+    }
+
+    /// Add `count` zero bytes, e.g. frame padding
+    pub fn add_zeros(&mut self, count: usize) {
+        for _ in 0..count {
+            self.add_bytes(&[0]);
+        }
+    }
+
+    /// Finish the computation and return the FCS value
+    pub fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_check_value() {
+        // Standard CRC-32 check value for the ASCII string "123456789"
+        let expect = 0xCBF4_3926;
+        let actual = crc32(b"123456789");
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_crc32_streaming() {
+        let expect = crc32(b"12345678\0\0");
+
+        let mut crc = Crc32::new();
+        crc.add_bytes(b"1234");
+        crc.add_bytes(b"5678");
+        crc.add_zeros(2);
+        let actual = crc.finish();
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_crc32_empty() {
+        let expect = 0x0000_0000;
+        let actual = crc32(&[]);
+        assert_eq!(expect, actual);
+    }
+}
@@ -1,21 +1,116 @@
+use alloc::vec::Vec;
+
+use super::ethertype::EtherType;
+use super::fcs::Crc32;
+use super::vlan::{VLAN_TAG_LEN, VlanTag};
 use crate::address::mac_addr::MacAddr;
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Length of an Ethernet II header in bytes (destination, source, EtherType)
+pub const ETHERNET_HEADER_LEN: usize = 14;
 
-#[derive(Debug, PartialEq)]
+/// Ethernet II header with an optional stack of VLAN tags
+///
+/// `vlan_tags` lists the tags outermost first; `ethertype` is the type of the
+/// payload following the innermost tag.
+#[derive(Debug, PartialEq, Clone)]
 pub struct EthernetHeader {
     pub dst: MacAddr,
     pub src: MacAddr,
-    pub ethertype: [u8; 2],
+    pub vlan_tags: Vec<VlanTag>,
+    pub ethertype: EtherType,
 }
 
 impl EthernetHeader {
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        EthernetHeader {
+    /// Parse the header, peeling any 802.1Q/802.1ad VLAN tags
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < ETHERNET_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Ethernet,
+                needed: ETHERNET_HEADER_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let mut vlan_tags = Vec::new();
+        let mut offset = 12;
+        loop {
+            let tpid = EtherType::from_bytes([bytes[offset], bytes[offset + 1]]);
+            if !tpid.is_vlan_tag() {
+                break;
+            }
+
+            // The tag is followed by at least another TPID or the EtherType
+            let needed = offset + VLAN_TAG_LEN + 2;
+            if bytes.len() < needed {
+                return Err(Error::Truncated {
+                    layer: Layer::Ethernet,
+                    needed,
+                    got: bytes.len(),
+                });
+            }
+            let tci = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]);
+            vlan_tags.push(VlanTag::from_tci(tpid, tci));
+            offset += VLAN_TAG_LEN;
+        }
+
+        Ok(EthernetHeader {
             dst: MacAddr::new(bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]),
             src: MacAddr::new(bytes[6], bytes[7], bytes[8], bytes[9], bytes[10], bytes[11]),
-            ethertype: bytes[12..14]
-                .try_into()
-                .expect("slice with incorrect length"),
+            vlan_tags,
+            ethertype: EtherType::from_bytes([bytes[offset], bytes[offset + 1]]),
+        })
+    }
+
+    /// Length of the serialized header in bytes, including VLAN tags
+    pub fn serialized_len(&self) -> usize {
+        ETHERNET_HEADER_LEN + self.vlan_tags.len() * VLAN_TAG_LEN
+    }
+
+    /// Serialize Ethernet header into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.serialized_len();
+        ensure_capacity(buf, len)?;
+        buf[0..6].copy_from_slice(&self.dst.octets());
+        buf[6..12].copy_from_slice(&self.src.octets());
+
+        let mut offset = 12;
+        for tag in &self.vlan_tags {
+            offset += tag.write_to(&mut buf[offset..])?;
+        }
+        buf[offset..offset + 2].copy_from_slice(&self.ethertype.to_bytes());
+        Ok(len)
+    }
+
+    /// Whether this is an IEEE 802.3 header, whose EtherType field is a length
+    pub fn is_802_3(&self) -> bool {
+        self.ethertype.is_length()
+    }
+
+    /// Add the serialized header to a CRC without allocating
+    ///
+    /// `ethertype` is written in place of `self.ethertype`, so that a frame can
+    /// supply its derived 802.3 length.
+    pub(crate) fn add_to_crc(&self, crc: &mut Crc32, ethertype: EtherType) -> Result<(), Error> {
+        crc.add_bytes(&self.dst.octets());
+        crc.add_bytes(&self.src.octets());
+        for tag in &self.vlan_tags {
+            let mut tag_bytes = [0u8; VLAN_TAG_LEN];
+            tag.write_to(&mut tag_bytes)?;
+            crc.add_bytes(&tag_bytes);
         }
+        crc.add_bytes(&ethertype.to_bytes());
+        Ok(())
+    }
+
+    /// Serialize Ethernet header to bytes (destination, source, VLAN tags, EtherType)
+    ///
+    /// Fails, like [`EthernetHeader::write_to`], if a VLAN tag is out of range.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
     }
 }
 
@@ -31,12 +126,129 @@ mod tests {
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Source MAC
             0x08, 0x00, // EtherType (IPv4)
         ];
-        let actual = EthernetHeader::from_bytes(bytes);
+        let actual = EthernetHeader::from_bytes(bytes).unwrap();
         let expect = EthernetHeader {
             dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
             src: MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
-            ethertype: [0x08, 0x00],
+            vlan_tags: Vec::new(),
+            ethertype: EtherType::Ipv4,
         };
         assert_eq!(actual, expect);
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ethernet_header_qinq_roundtrip() {
+        let mut inner = VlanTag::new(100);
+        inner.pcp = 3;
+        let expect = EthernetHeader {
+            dst: MacAddr::broadcast(),
+            src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            vlan_tags: alloc::vec![VlanTag::service(200), inner],
+            ethertype: EtherType::Ipv4,
+        };
+
+        let bytes = expect.to_bytes().unwrap();
+        assert_eq!(22, bytes.len());
+        assert_eq!(
+            [0x88, 0xA8, 0x00, 0xC8, 0x81, 0x00, 0x60, 0x64, 0x08, 0x00],
+            bytes[12..]
+        );
+
+        let actual = EthernetHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ethernet_header_other_ethertype_roundtrip() {
+        let bytes: &[u8] = &[
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Destination MAC
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // Source MAC
+            0x89, 0x02, // EtherType (IEEE 802.1ag CFM, no named variant)
+        ];
+        let header = EthernetHeader::from_bytes(bytes).unwrap();
+        assert_eq!(EtherType::Other(0x8902), header.ethertype);
+        assert_eq!(bytes, &header.to_bytes().unwrap()[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ethernet_header_add_to_crc() {
+        let header = EthernetHeader {
+            dst: MacAddr::broadcast(),
+            src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            vlan_tags: alloc::vec![VlanTag::new(1)],
+            ethertype: EtherType::Ipv6,
+        };
+        let mut expect = Crc32::new();
+        expect.add_bytes(&header.to_bytes().unwrap());
+
+        let mut actual = Crc32::new();
+        header.add_to_crc(&mut actual, header.ethertype).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_header_from_bytes_truncated_vlan_tag() {
+        let bytes: &[u8] = &[
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Destination MAC
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // Source MAC
+            0x81, 0x00, 0x00, 0x64, // 802.1Q tag without EtherType
+        ];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ethernet,
+            needed: 18,
+            got: 16,
+        });
+        let actual = EthernetHeader::from_bytes(bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_header_from_bytes_truncated() {
+        let bytes: &[u8] = &[0xff; 13];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ethernet,
+            needed: 14,
+            got: 13,
+        });
+        let actual = EthernetHeader::from_bytes(bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_header_write_to_buffer_too_small() {
+        let header = EthernetHeader {
+            dst: MacAddr::broadcast(),
+            src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            vlan_tags: Vec::new(),
+            ethertype: EtherType::Ipv4,
+        };
+        let mut buf = [0u8; 13];
+        let expect = Err(Error::BufferTooSmall {
+            needed: 14,
+            got: 13,
+        });
+        let actual = header.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ethernet_header_to_bytes() {
+        let header = EthernetHeader {
+            dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+            src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            vlan_tags: Vec::new(),
+            ethertype: EtherType::Ipv6,
+        };
+        let expect = alloc::vec![
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Destination MAC
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // Source MAC
+            0x86, 0xDD, // EtherType (IPv6)
+        ];
+        let actual = header.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
 }
@@ -0,0 +1,226 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// SAP of the Subnetwork Access Protocol extension
+///
+/// Reference: IEEE 802 Clause 10.3 / RFC 1042
+pub const SAP_SNAP: u8 = 0xAA;
+
+/// SAP of the Spanning Tree Protocol (IEEE 802.1D)
+pub const SAP_STP: u8 = 0x42;
+
+/// Control field of an Unnumbered Information PDU
+pub const CONTROL_UI: u8 = 0x03;
+
+/// Length of the SNAP extension in bytes (OUI and protocol ID)
+pub const SNAP_HEADER_LEN: usize = 5;
+
+/// IEEE 802.2 LLC control field
+///
+/// The two low-order bits of the first octet select the format; U-format
+/// PDUs have a 1-octet control field and I/S-format PDUs a 2-octet one.
+///
+/// Reference: IEEE 802.2 Clause 5.4 - Control field formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlcControl {
+    /// U-format (1 octet), e.g. [`CONTROL_UI`]
+    Unnumbered(u8),
+    /// I-format or S-format (2 octets, in transmission order)
+    Numbered([u8; 2]),
+}
+
+impl LlcControl {
+    /// Length of the control field in bytes
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            LlcControl::Unnumbered(_) => 1,
+            LlcControl::Numbered(_) => 2,
+        }
+    }
+}
+
+/// SNAP extension identifying the protocol by OUI and protocol ID
+///
+/// With OUI 00-00-00 the protocol ID is an EtherType (RFC 1042).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapHeader {
+    pub oui: [u8; 3],
+    pub protocol_id: u16,
+}
+
+/// IEEE 802.2 LLC header, optionally followed by a SNAP extension
+///
+/// Reference: IEEE 802.2 Clause 3.2 - LLC PDU format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LlcHeader {
+    pub dsap: u8,
+    pub ssap: u8,
+    pub control: LlcControl,
+    /// Written after the control field when present; parsed when both SAPs
+    /// are [`SAP_SNAP`] and the control field is UI
+    pub snap: Option<SnapHeader>,
+}
+
+impl LlcHeader {
+    /// LLC header for an Unnumbered Information PDU
+    pub fn new(dsap: u8, ssap: u8) -> Self {
+        LlcHeader {
+            dsap,
+            ssap,
+            control: LlcControl::Unnumbered(CONTROL_UI),
+            snap: None,
+        }
+    }
+
+    /// LLC header with a SNAP extension (AA-AA-03)
+    pub fn snap(oui: [u8; 3], protocol_id: u16) -> Self {
+        LlcHeader {
+            snap: Some(SnapHeader { oui, protocol_id }),
+            ..Self::new(SAP_SNAP, SAP_SNAP)
+        }
+    }
+
+    /// Length of the serialized header in bytes
+    pub fn serialized_len(&self) -> usize {
+        let snap_len = if self.snap.is_some() {
+            SNAP_HEADER_LEN
+        } else {
+            0
+        };
+        2 + self.control.serialized_len() + snap_len
+    }
+
+    /// Serialize the header into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.serialized_len();
+        ensure_capacity(buf, len)?;
+
+        buf[0] = self.dsap;
+        buf[1] = self.ssap;
+        let mut offset = 2;
+        match self.control {
+            LlcControl::Unnumbered(control) => buf[offset] = control,
+            LlcControl::Numbered(control) => buf[offset..offset + 2].copy_from_slice(&control),
+        }
+        offset += self.control.serialized_len();
+
+        if let Some(snap) = &self.snap {
+            buf[offset..offset + 3].copy_from_slice(&snap.oui);
+            buf[offset + 3..offset + 5].copy_from_slice(&snap.protocol_id.to_be_bytes());
+        }
+
+        Ok(len)
+    }
+
+    /// Serialize the header to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+
+    /// Parse an LLC header from the start of an 802.3 payload
+    ///
+    /// Returns (header, bytes_consumed).
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        let truncated = |needed: usize| Error::Truncated {
+            layer: Layer::Llc,
+            needed,
+            got: bytes.len(),
+        };
+
+        if bytes.len() < 3 {
+            return Err(truncated(3));
+        }
+
+        let control = if bytes[2] & 0x03 == 0x03 {
+            LlcControl::Unnumbered(bytes[2])
+        } else {
+            if bytes.len() < 4 {
+                return Err(truncated(4));
+            }
+            LlcControl::Numbered([bytes[2], bytes[3]])
+        };
+        let mut header = LlcHeader {
+            dsap: bytes[0],
+            ssap: bytes[1],
+            control,
+            snap: None,
+        };
+        let offset = 2 + control.serialized_len();
+
+        if header.dsap == SAP_SNAP
+            && header.ssap == SAP_SNAP
+            && control == LlcControl::Unnumbered(CONTROL_UI)
+        {
+            if bytes.len() < offset + SNAP_HEADER_LEN {
+                return Err(truncated(offset + SNAP_HEADER_LEN));
+            }
+            header.snap = Some(SnapHeader {
+                oui: [bytes[offset], bytes[offset + 1], bytes[offset + 2]],
+                protocol_id: u16::from_be_bytes([bytes[offset + 3], bytes[offset + 4]]),
+            });
+        }
+
+        let len = header.serialized_len();
+        Ok((header, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_llc_header_stp() {
+        let header = LlcHeader::new(SAP_STP, SAP_STP);
+        let expect = alloc::vec![0x42, 0x42, 0x03];
+        assert_eq!(expect, header.to_bytes());
+
+        let actual = LlcHeader::from_bytes(&[0x42, 0x42, 0x03, 0x00, 0x00]).unwrap();
+        assert_eq!((header, 3), actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_llc_header_snap_cdp() {
+        let header = LlcHeader::snap([0x00, 0x00, 0x0C], 0x2000);
+        let expect = alloc::vec![0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00];
+        assert_eq!(expect, header.to_bytes());
+
+        let actual = LlcHeader::from_bytes(&expect).unwrap();
+        assert_eq!((header, 8), actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_llc_header_numbered_control() {
+        let header = LlcHeader {
+            dsap: 0xF0,
+            ssap: 0xF0,
+            control: LlcControl::Numbered([0x02, 0x04]),
+            snap: None,
+        };
+        let bytes = header.to_bytes();
+        assert_eq!(4, bytes.len());
+
+        let actual = LlcHeader::from_bytes(&bytes).unwrap();
+        assert_eq!((header, 4), actual);
+    }
+
+    #[test]
+    fn test_llc_header_snap_truncated() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Llc,
+            needed: 8,
+            got: 5,
+        });
+        let actual = LlcHeader::from_bytes(&[0xAA, 0xAA, 0x03, 0x00, 0x00]);
+        assert_eq!(expect, actual);
+    }
+}
@@ -1,14 +1,157 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 pub mod ethertype;
+pub mod fcs;
 pub mod header;
+pub mod llc;
+pub mod vlan;
+
+use crate::error::{Error, Layer, ensure_capacity};
+use ethertype::EtherType;
+use fcs::FCS_LEN;
+use header::{ETHERNET_HEADER_LEN, EthernetHeader};
 
-use header::EthernetHeader;
+/// Minimum frame length in bytes, excluding the FCS
+///
+/// Reference: IEEE 802.3 Clause 4.4.2 - minFrameSize (64 octets including the FCS)
+pub const MIN_FRAME_LEN: usize = 60;
 
 impl<'a> EthernetFrame<'a> {
-    #[allow(dead_code)]
-    pub fn from_bytes(bytes: &'a [u8]) -> Self {
-        let header = EthernetHeader::from_bytes(&bytes[0..14]);
-        let payload = &bytes[14..];
-        EthernetFrame { header, payload }
+    /// Parse a frame, distinguishing Ethernet II from IEEE 802.3
+    ///
+    /// For an 802.3 frame the payload is delimited by the length field, so any
+    /// padding is excluded; an Ethernet II payload runs to the end of `bytes`.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = EthernetHeader::from_bytes(bytes)?;
+        let payload = &bytes[header.serialized_len()..];
+
+        let payload = match header.ethertype {
+            EtherType::Length(length) => {
+                let length = length as usize;
+                if payload.len() < length {
+                    return Err(Error::Truncated {
+                        layer: Layer::Ethernet,
+                        needed: header.serialized_len() + length,
+                        got: bytes.len(),
+                    });
+                }
+                &payload[..length]
+            }
+            _ => payload,
+        };
+        Ok(EthernetFrame { header, payload })
+    }
+
+    /// Parse a frame whose last 4 bytes are the FCS and verify it
+    ///
+    /// The returned payload excludes the FCS; see [`EthernetFrame::from_bytes`]
+    /// for how padding is handled.
+    pub fn from_bytes_with_fcs(bytes: &'a [u8]) -> Result<Self, Error> {
+        if bytes.len() < ETHERNET_HEADER_LEN + FCS_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Ethernet,
+                needed: ETHERNET_HEADER_LEN + FCS_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let (frame, trailer) = bytes.split_at(bytes.len() - FCS_LEN);
+        let received = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let expected = fcs::crc32(frame);
+        if expected != received {
+            return Err(Error::BadFcs {
+                expected,
+                got: received,
+            });
+        }
+
+        Self::from_bytes(frame)
+    }
+
+    /// Calculate the FCS over header and payload (padded if `pad` is set)
+    pub fn calculate_fcs(&self, pad: bool) -> Result<u32, Error> {
+        let mut crc = fcs::Crc32::new();
+        self.header.add_to_crc(&mut crc, self.ethertype()?)?;
+        crc.add_bytes(self.payload);
+        crc.add_zeros(self.padding_len(pad));
+        Ok(crc.finish())
+    }
+
+    /// EtherType field as serialized: the payload length for 802.3 frames
+    ///
+    /// An 802.3 payload longer than [`ethertype::MAX_LENGTH`] would be read
+    /// back as an EtherType, so it is rejected.
+    fn ethertype(&self) -> Result<EtherType, Error> {
+        if !self.header.is_802_3() {
+            return Ok(self.header.ethertype);
+        }
+        match u16::try_from(self.payload.len()) {
+            Ok(length) if length <= ethertype::MAX_LENGTH => Ok(EtherType::Length(length)),
+            _ => Err(Error::BadTotalLength {
+                layer: Layer::Ethernet,
+                length: self.payload.len(),
+            }),
+        }
+    }
+
+    /// Number of zero bytes needed to reach [`MIN_FRAME_LEN`] (0 unless `pad` is set)
+    fn padding_len(&self, pad: bool) -> usize {
+        let len = self.header.serialized_len() + self.payload.len();
+        if pad {
+            MIN_FRAME_LEN.saturating_sub(len)
+        } else {
+            0
+        }
+    }
+
+    /// Length of the serialized frame in bytes, including padding and FCS if requested
+    pub fn serialized_len(&self, pad: bool, fcs: bool) -> usize {
+        let fcs_len = if fcs { FCS_LEN } else { 0 };
+        self.header.serialized_len() + self.payload.len() + self.padding_len(pad) + fcs_len
+    }
+
+    /// Serialize the frame into `buf`, returning the number of bytes written
+    ///
+    /// If `pad` is set, the payload is zero-padded so the frame (excluding FCS)
+    /// is at least [`MIN_FRAME_LEN`] bytes. If `fcs` is set, the CRC-32 frame
+    /// check sequence is appended after the (optionally padded) frame.
+    ///
+    /// For an 802.3 frame, the length field written to `buf` is the payload
+    /// length, excluding padding; `self.header` is left unchanged. A payload
+    /// longer than [`ethertype::MAX_LENGTH`] fails with [`Error::BadTotalLength`].
+    ///
+    /// Reference: IEEE 802.3 Clause 3.2.6 (Length/Type), 3.2.8 (Pad) and 3.2.9 (FCS)
+    pub fn write_to(&self, buf: &mut [u8], pad: bool, fcs: bool) -> Result<usize, Error> {
+        let ethertype = self.ethertype()?;
+        ensure_capacity(buf, self.serialized_len(pad, fcs))?;
+
+        let mut len = self.header.write_to(buf)?;
+        buf[len - 2..len].copy_from_slice(&ethertype.to_bytes());
+        buf[len..len + self.payload.len()].copy_from_slice(self.payload);
+        len += self.payload.len();
+
+        let padding_len = self.padding_len(pad);
+        buf[len..len + padding_len].fill(0);
+        len += padding_len;
+
+        if fcs {
+            let crc = fcs::crc32(&buf[..len]);
+            buf[len..len + FCS_LEN].copy_from_slice(&crc.to_le_bytes());
+            len += FCS_LEN;
+        }
+
+        Ok(len)
+    }
+
+    /// Serialize the frame to bytes
+    ///
+    /// See [`EthernetFrame::write_to`] for the meaning of `pad` and `fcs`.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self, pad: bool, fcs: bool) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len(pad, fcs)];
+        self.write_to(&mut bytes, pad, fcs)?;
+        Ok(bytes)
     }
 }
 
@@ -18,10 +161,11 @@ pub struct EthernetFrame<'a> {
     pub payload: &'a [u8],
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
     use crate::address::mac_addr::MacAddr;
+    use ethertype::EtherType;
     use header::EthernetHeader;
 
     #[test]
@@ -36,12 +180,13 @@ mod tests {
             0x0a, 0x01, 0xac, 0x10, 0x0a, 0x02,
         ];
 
-        let ethernet_frame = EthernetFrame::from_bytes(bytes);
+        let ethernet_frame = EthernetFrame::from_bytes(bytes).unwrap();
 
         let expect_header = EthernetHeader {
             dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
             src: MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
-            ethertype: [0x08, 0x00],
+            vlan_tags: Vec::new(),
+            ethertype: EtherType::Ipv4,
         };
         let expect_payload: &[u8] = &[
             0x45, 0x00, 0x00, 0x3c, 0x1c, 0x46, 0x40, 0x00, 0x40, 0x06, 0x00, 0x00, 0xac, 0x10,
@@ -51,4 +196,226 @@ mod tests {
         assert_eq!(ethernet_frame.header, expect_header);
         assert_eq!(ethernet_frame.payload, expect_payload);
     }
+
+    fn arp_request_frame() -> EthernetFrame<'static> {
+        EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+                src: MacAddr(0x00, 0x0a, 0x95, 0x9d, 0x68, 0x16),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Arp,
+            },
+            payload: &[
+                0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00,
+                0x01, // HTYPE, PTYPE, HLEN, PLEN, OPER
+                0x00, 0x0a, 0x95, 0x9d, 0x68, 0x16, // Sender MAC
+                0xc0, 0xa8, 0x01, 0x01, // Sender IP
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Target MAC
+                0xc0, 0xa8, 0x01, 0x02, // Target IP
+            ],
+        }
+    }
+
+    #[test]
+    fn test_ethernet_frame_to_bytes() {
+        let frame = arp_request_frame();
+        let actual = frame.to_bytes(false, false).unwrap();
+
+        assert_eq!(42, actual.len()); // 14-byte header + 28-byte ARP payload
+        assert_eq!(&frame.header.to_bytes().unwrap()[..], &actual[0..14]);
+        assert_eq!(frame.payload, &actual[14..]);
+    }
+
+    #[test]
+    fn test_ethernet_frame_to_bytes_padded() {
+        let frame = arp_request_frame();
+        let actual = frame.to_bytes(true, false).unwrap();
+
+        assert_eq!(MIN_FRAME_LEN, actual.len());
+        assert_eq!(frame.payload, &actual[14..42]);
+        assert!(actual[42..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_ethernet_frame_to_bytes_padding_not_needed() {
+        let payload = [0xAB; 100];
+        let frame = EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
+                src: MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x01),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Ipv4,
+            },
+            payload: &payload,
+        };
+        let actual = frame.to_bytes(true, false).unwrap();
+        assert_eq!(114, actual.len());
+    }
+
+    #[test]
+    fn test_ethernet_frame_to_bytes_with_fcs() {
+        let frame = arp_request_frame();
+        let actual = frame.to_bytes(true, true).unwrap();
+
+        assert_eq!(MIN_FRAME_LEN + FCS_LEN, actual.len());
+
+        let expect = frame.calculate_fcs(true).unwrap().to_le_bytes();
+        assert_eq!(expect, actual[60..64]);
+
+        // Running the CRC over frame + FCS yields the constant CRC-32 residue
+        let residue = fcs::crc32(&actual);
+        assert_eq!(0x2144_DF1C, residue);
+    }
+
+    #[test]
+    fn test_ethernet_frame_write_to() {
+        let frame = arp_request_frame();
+        let expect = frame.to_bytes(true, true).unwrap();
+
+        let mut buf = [0xEEu8; 128];
+        let len = frame.write_to(&mut buf, true, true).unwrap();
+        assert_eq!(frame.serialized_len(true, true), len);
+        assert_eq!(expect[..], buf[..len]);
+    }
+
+    #[test]
+    fn test_ethernet_frame_write_to_buffer_too_small() {
+        let frame = arp_request_frame();
+        let mut buf = [0u8; 63];
+        let expect = Err(Error::BufferTooSmall {
+            needed: 64,
+            got: 63,
+        });
+        let actual = frame.write_to(&mut buf, true, true);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_frame_from_bytes_with_fcs() {
+        let frame = arp_request_frame();
+        let bytes = frame.to_bytes(false, true).unwrap();
+
+        let actual = EthernetFrame::from_bytes_with_fcs(&bytes).unwrap();
+        assert_eq!(frame, actual);
+    }
+
+    #[test]
+    fn test_ethernet_frame_from_bytes_with_fcs_mismatch() {
+        let frame = arp_request_frame();
+        let mut bytes = frame.to_bytes(true, true).unwrap();
+        bytes[20] ^= 0x01; // Corrupt one payload bit
+
+        let actual = EthernetFrame::from_bytes_with_fcs(&bytes);
+        assert!(matches!(actual, Err(Error::BadFcs { .. })));
+    }
+
+    #[test]
+    fn test_ethernet_frame_from_bytes_with_fcs_too_short() {
+        let bytes = [0u8; 17];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ethernet,
+            needed: 18,
+            got: 17,
+        });
+        let actual = EthernetFrame::from_bytes_with_fcs(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_frame_vlan_tagged_roundtrip() {
+        let mut frame = arp_request_frame();
+        frame.header.vlan_tags.push(vlan::VlanTag::new(42));
+
+        let bytes = frame.to_bytes(true, true).unwrap();
+        assert_eq!([0x81, 0x00, 0x00, 0x2A, 0x08, 0x06], bytes[12..18]);
+        assert_eq!(frame.payload, &bytes[18..46]);
+        assert_eq!(
+            frame.calculate_fcs(true).unwrap().to_le_bytes(),
+            bytes[60..64]
+        );
+
+        let actual = EthernetFrame::from_bytes_with_fcs(&bytes).unwrap();
+        assert_eq!(frame.header, actual.header);
+        assert_eq!(frame.payload, &actual.payload[..28]);
+    }
+
+    fn stp_frame(payload: &[u8]) -> EthernetFrame<'_> {
+        EthernetFrame {
+            header: EthernetHeader {
+                dst: MacAddr(0x01, 0x80, 0xC2, 0x00, 0x00, 0x00),
+                src: MacAddr(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+                vlan_tags: Vec::new(),
+                ethertype: EtherType::Length(0),
+            },
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_ethernet_frame_802_3_length_computed() {
+        let mut payload = llc::LlcHeader::new(llc::SAP_STP, llc::SAP_STP).to_bytes();
+        payload.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        let frame = stp_frame(&payload);
+
+        let bytes = frame.to_bytes(true, true).unwrap();
+        assert_eq!(MIN_FRAME_LEN + FCS_LEN, bytes.len());
+        assert_eq!([0x00, 0x07], bytes[12..14]); // Length excludes padding
+        assert_eq!(
+            frame.calculate_fcs(true).unwrap().to_le_bytes(),
+            bytes[60..64]
+        );
+        assert_eq!(EtherType::Length(0), frame.header.ethertype);
+
+        // Parsing strips the padding using the length field
+        let actual = EthernetFrame::from_bytes_with_fcs(&bytes).unwrap();
+        assert!(actual.header.is_802_3());
+        assert_eq!(EtherType::Length(7), actual.header.ethertype);
+        assert_eq!(&payload[..], actual.payload);
+
+        let (llc, consumed) = llc::LlcHeader::from_bytes(actual.payload).unwrap();
+        assert_eq!(llc::SAP_STP, llc.dsap);
+        assert_eq!(3, consumed);
+    }
+
+    #[test]
+    fn test_ethernet_frame_802_3_truncated() {
+        let mut bytes = stp_frame(&[0x42, 0x42, 0x03, 0x00])
+            .to_bytes(false, false)
+            .unwrap();
+        bytes[13] = 10; // Claim more payload than present
+
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ethernet,
+            needed: 24,
+            got: 18,
+        });
+        let actual = EthernetFrame::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ethernet_frame_802_3_payload_too_long() {
+        let payload = [0u8; 1501];
+        let frame = stp_frame(&payload);
+        let mut buf = [0u8; 1600];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Ethernet,
+            length: 1501,
+        });
+        assert_eq!(expect, frame.write_to(&mut buf, false, false).map(|_| ()));
+        assert_eq!(expect, frame.calculate_fcs(false).map(|_| ()));
+
+        // The largest payload the length field can express is accepted
+        let frame = stp_frame(&payload[..1500]);
+        let bytes = frame.to_bytes(false, false).unwrap();
+        assert_eq!([0x05, 0xDC], bytes[12..14]);
+    }
+
+    #[test]
+    fn test_ethernet_frame_from_bytes_truncated() {
+        let bytes = [0u8; 10];
+        let actual = EthernetFrame::from_bytes(&bytes);
+        assert!(matches!(actual, Err(Error::Truncated { .. })));
+    }
 }
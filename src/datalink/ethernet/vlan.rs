@@ -0,0 +1,120 @@
+use super::ethertype::EtherType;
+use crate::error::{Error, ensure_capacity};
+
+/// Length of a VLAN tag in bytes (TPID and TCI)
+pub const VLAN_TAG_LEN: usize = 4;
+
+/// IEEE 802.1Q VLAN tag
+///
+/// Customer tags (C-TAG) use TPID 0x8100 and service tags (S-TAG, 802.1ad
+/// QinQ) use TPID 0x88A8.
+///
+/// Reference: IEEE 802.1Q Clause 9.6 - VLAN Tag Control Information
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VlanTag {
+    pub tpid: EtherType,
+    /// Priority Code Point (3 bits)
+    pub pcp: u8,
+    /// Drop Eligible Indicator
+    pub dei: bool,
+    /// VLAN Identifier (12 bits)
+    pub vid: u16,
+}
+
+impl VlanTag {
+    /// Customer VLAN tag (TPID 0x8100) with priority 0
+    pub fn new(vid: u16) -> Self {
+        VlanTag {
+            tpid: EtherType::Vlan,
+            pcp: 0,
+            dei: false,
+            vid,
+        }
+    }
+
+    /// Service VLAN tag (TPID 0x88A8) with priority 0, used as the outer QinQ tag
+    pub fn service(vid: u16) -> Self {
+        VlanTag {
+            tpid: EtherType::QinQ,
+            ..Self::new(vid)
+        }
+    }
+
+    /// Tag Control Information: PCP, DEI and VID packed into 16 bits
+    ///
+    /// Fails with [`Error::BadVlanTag`] if the PCP is above 7 or the VID is
+    /// above 0x0FFF.
+    pub fn tci(&self) -> Result<u16, Error> {
+        if self.pcp > 0x07 || self.vid > 0x0FFF {
+            return Err(Error::BadVlanTag {
+                pcp: self.pcp,
+                vid: self.vid,
+            });
+        }
+        Ok(((self.pcp as u16) << 13) | ((self.dei as u16) << 12) | self.vid)
+    }
+
+    /// Build a tag from its TPID and Tag Control Information
+    pub fn from_tci(tpid: EtherType, tci: u16) -> Self {
+        VlanTag {
+            tpid,
+            pcp: (tci >> 13) as u8,
+            dei: tci & 0x1000 != 0,
+            vid: tci & 0x0FFF,
+        }
+    }
+
+    /// Serialize the tag into `buf`, returning the number of bytes written
+    ///
+    /// Fails as [`VlanTag::tci`] does if the PCP or VID is out of range.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let tci = self.tci()?;
+        ensure_capacity(buf, VLAN_TAG_LEN)?;
+        buf[0..2].copy_from_slice(&self.tpid.to_bytes());
+        buf[2..4].copy_from_slice(&tci.to_be_bytes());
+        Ok(VLAN_TAG_LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vlan_tag_tci() {
+        let tag = VlanTag {
+            tpid: EtherType::Vlan,
+            pcp: 5,
+            dei: true,
+            vid: 100,
+        };
+        assert_eq!(Ok(0xB064), tag.tci());
+        assert_eq!(tag, VlanTag::from_tci(EtherType::Vlan, 0xB064));
+    }
+
+    #[test]
+    fn test_vlan_tag_rejects_out_of_range_fields() {
+        let expect = Err(Error::BadVlanTag {
+            pcp: 0,
+            vid: 0x1000,
+        });
+        assert_eq!(expect, VlanTag::new(0x1000).tci());
+
+        let mut tag = VlanTag::new(0x0FFF);
+        tag.pcp = 8;
+        let expect = Err(Error::BadVlanTag {
+            pcp: 8,
+            vid: 0x0FFF,
+        });
+        assert_eq!(expect, tag.write_to(&mut [0u8; 4]));
+    }
+
+    #[test]
+    fn test_vlan_tag_write_to() {
+        let mut buf = [0u8; 4];
+        let len = VlanTag::service(10).write_to(&mut buf).unwrap();
+
+        assert_eq!(4, len);
+        assert_eq!([0x88, 0xA8, 0x00, 0x0A], buf);
+    }
+}
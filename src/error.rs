@@ -0,0 +1,219 @@
+use core::fmt;
+
+/// Protocol layer an [`Error`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Layer {
+    Ethernet,
+    Llc,
+    Arp,
+    Ipv4,
+    Ipv6,
+    Icmpv4,
+    Icmpv6,
+    Tcp,
+    Udp,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Layer::Ethernet => "Ethernet",
+            Layer::Llc => "LLC",
+            Layer::Arp => "ARP",
+            Layer::Ipv4 => "IPv4",
+            Layer::Ipv6 => "IPv6",
+            Layer::Icmpv4 => "ICMPv4",
+            Layer::Icmpv6 => "ICMPv6",
+            Layer::Tcp => "TCP",
+            Layer::Udp => "UDP",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Errors returned when parsing or building packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input ended before the layer was complete
+    Truncated {
+        layer: Layer,
+        needed: usize,
+        got: usize,
+    },
+    /// The version field does not match the layer (e.g. IPv4 version != 4)
+    BadVersion { layer: Layer, version: u8 },
+    /// A header length field (IPv4 IHL, TCP data offset) is out of range
+    BadHeaderLength { layer: Layer, length: usize },
+    /// A total length field is smaller than the header it covers, or a payload
+    /// is too long for the length field that delimits it
+    BadTotalLength { layer: Layer, length: usize },
+    /// An option's length field is invalid for its kind
+    BadOptionLength {
+        layer: Layer,
+        kind: u8,
+        length: usize,
+    },
+    /// An option kind that is not recognized
+    UnknownOption { layer: Layer, kind: u8 },
+    /// Options are longer than the header's option space
+    OptionSpaceExceeded {
+        layer: Layer,
+        length: usize,
+        limit: usize,
+    },
+    /// The Ethernet frame check sequence does not match the frame contents
+    BadFcs { expected: u32, got: u32 },
+    /// The output buffer passed to `write_to` is too small
+    BufferTooSmall { needed: usize, got: usize },
+    /// A layer was added to the builder without the network layer it depends on
+    ///
+    /// An Ethernet II frame needs one unless its EtherType is set explicitly.
+    MissingNetworkLayer { layer: Layer },
+    /// A builder modifier was called before the layer it modifies was added
+    MissingLayer { layer: Layer },
+    /// A packet with the Don't Fragment flag set is larger than the MTU
+    DontFragment { length: usize, mtu: usize },
+    /// The MTU cannot hold a header plus one 8-byte fragment block
+    MtuTooSmall { mtu: usize },
+    /// A fragment is inconsistent with the datagram it belongs to
+    BadFragment { offset: usize, length: usize },
+    /// A fragment overlaps data already received and the policy rejects overlaps
+    FragmentOverlap { offset: usize, length: usize },
+    /// Buffering a fragment would exceed the reassembly memory limit
+    ReassemblyLimit { needed: usize, limit: usize },
+    /// A string or integer is not a valid 48-bit MAC address
+    BadMacAddr,
+    /// A VLAN tag's PCP or VID does not fit its 3- or 12-bit field
+    BadVlanTag { pcp: u8, vid: u16 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated { layer, needed, got } => {
+                write!(
+                    f,
+                    "truncated {layer} data: needed {needed} bytes, got {got}"
+                )
+            }
+            Error::BadVersion { layer, version } => {
+                write!(f, "invalid {layer} version {version}")
+            }
+            Error::BadHeaderLength { layer, length } => {
+                write!(f, "invalid {layer} header length {length}")
+            }
+            Error::BadTotalLength { layer, length } => {
+                write!(f, "invalid {layer} total length {length}")
+            }
+            Error::BadOptionLength {
+                layer,
+                kind,
+                length,
+            } => write!(f, "invalid length {length} for {layer} option kind {kind}"),
+            Error::UnknownOption { layer, kind } => {
+                write!(f, "unknown {layer} option kind {kind}")
+            }
+            Error::OptionSpaceExceeded {
+                layer,
+                length,
+                limit,
+            } => write!(
+                f,
+                "{length} bytes of {layer} options exceed the {limit}-byte option space"
+            ),
+            Error::BadFcs { expected, got } => {
+                write!(
+                    f,
+                    "Ethernet FCS mismatch: expected {expected:#010x}, got {got:#010x}"
+                )
+            }
+            Error::BufferTooSmall { needed, got } => {
+                write!(f, "buffer too small: needed {needed} bytes, got {got}")
+            }
+            Error::MissingNetworkLayer { layer } => {
+                write!(f, "{layer} requires a network layer")
+            }
+            Error::MissingLayer { layer } => write!(f, "no {layer} layer to modify"),
+            Error::DontFragment { length, mtu } => {
+                write!(
+                    f,
+                    "packet of {length} bytes exceeds MTU {mtu} with Don't Fragment set"
+                )
+            }
+            Error::MtuTooSmall { mtu } => write!(f, "MTU {mtu} is too small to fragment into"),
+            Error::BadFragment { offset, length } => {
+                write!(f, "invalid fragment of {length} bytes at offset {offset}")
+            }
+            Error::FragmentOverlap { offset, length } => {
+                write!(
+                    f,
+                    "fragment of {length} bytes at offset {offset} overlaps received data"
+                )
+            }
+            Error::ReassemblyLimit { needed, limit } => {
+                write!(
+                    f,
+                    "reassembly needs {needed} bytes, exceeding the limit of {limit}"
+                )
+            }
+            Error::BadMacAddr => f.write_str("invalid MAC address"),
+            Error::BadVlanTag { pcp, vid } => write!(f, "invalid VLAN tag: PCP {pcp}, VID {vid}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Check that an output buffer can hold `needed` bytes
+pub(crate) fn ensure_capacity(buf: &[u8], needed: usize) -> Result<(), Error> {
+    if buf.len() < needed {
+        return Err(Error::BufferTooSmall {
+            needed,
+            got: buf.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_display_truncated() {
+        let error = Error::Truncated {
+            layer: Layer::Tcp,
+            needed: 20,
+            got: 3,
+        };
+        let expect = "truncated TCP data: needed 20 bytes, got 3";
+        let actual = error.to_string();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_display_bad_version() {
+        let error = Error::BadVersion {
+            layer: Layer::Ipv4,
+            version: 5,
+        };
+        let expect = "invalid IPv4 version 5";
+        let actual = error.to_string();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_display_bad_fcs() {
+        let error = Error::BadFcs {
+            expected: 0xCBF43926,
+            got: 0,
+        };
+        let expect = "Ethernet FCS mismatch: expected 0xcbf43926, got 0x00000000";
+        let actual = error.to_string();
+        assert_eq!(expect, actual);
+    }
+}
@@ -4,6 +4,14 @@
 //!
 //! A no_std library for building network packets.
 //!
+//! ## Features
+//!
+//! - `alloc` (default): `Vec`-returning `to_bytes` helpers, IPv4 fragmentation
+//!   and [`PacketBuilder`]. Without it, packets are serialized with `write_to`
+//!   into a caller-provided buffer. The `alloc` crate itself is still required,
+//!   since VLAN tag stacks, option sets and IPv6 extension headers are `Vec`s.
+//! - `std`: implements `std::error::Error` for [`Error`].
+//!
 //! ## TCP Example
 //!
 //! ```ignore
@@ -24,77 +32,25 @@
 //! // Generate packet bytes with checksum
 //! let src_ip = [192, 168, 1, 100];
 //! let dst_ip = [192, 168, 1, 1];
-//! let packet_bytes = packet.to_bytes_ipv4(src_ip, dst_ip);
+//! let packet_bytes = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
 //!
 //! // packet_bytes now contains a complete TCP segment
 //! assert_eq!(24, packet_bytes.len()); // 20-byte header + 4-byte MSS option
 //! ```
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod address;
+#[cfg(feature = "alloc")]
+mod builder;
+pub mod checksum;
 pub mod datalink;
+pub mod error;
 pub mod network;
 pub mod transport;
 
-use crate::address::mac_addr::MacAddr;
-use datalink::ethernet::EthernetFrame;
-use datalink::ethernet::header::EthernetHeader;
-
-pub struct PacketBuilder;
-
-impl Default for PacketBuilder {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl PacketBuilder {
-    pub fn new() -> Self {
-        PacketBuilder
-    }
-
-    pub fn ethernet<'a>(
-        &self,
-        destination: MacAddr,
-        source: MacAddr,
-        ethertype: [u8; 2],
-        payload: &'a [u8],
-    ) -> EthernetFrame<'a> {
-        EthernetFrame {
-            header: EthernetHeader {
-                dst: destination,
-                src: source,
-                ethertype,
-            },
-            payload,
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use datalink::ethernet::EthernetFrame;
-    use datalink::ethernet::header::EthernetHeader;
-
-    #[test]
-    fn test_packet_builder_ethernet() {
-        let builder = PacketBuilder::new();
-        let expect = EthernetFrame {
-            header: EthernetHeader {
-                dst: MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
-                src: MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
-                ethertype: [0x08, 0x00],
-            },
-            payload: &[0x45, 0x00],
-        };
-        let actual = builder.ethernet(
-            MacAddr(0xff, 0xff, 0xff, 0xff, 0xff, 0xff),
-            MacAddr(0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
-            [0x08, 0x00],
-            &[0x45, 0x00],
-        );
-        assert_eq!(expect, actual);
-    }
-}
+#[cfg(feature = "alloc")]
+pub use builder::PacketBuilder;
+pub use error::{Error, Layer};
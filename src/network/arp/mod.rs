@@ -0,0 +1,299 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod operation;
+
+use crate::address::mac_addr::MacAddr;
+use crate::error::{Error, Layer, ensure_capacity};
+use operation::ArpOperation;
+
+/// Length of an ARP packet for Ethernet and IPv4 in bytes
+pub const ARP_PACKET_LEN: usize = 28;
+
+/// Hardware type for Ethernet
+///
+/// Reference: IANA ARP Parameters - Hardware Types
+pub const HARDWARE_TYPE_ETHERNET: u16 = 1;
+
+/// Protocol type for IPv4 (the IPv4 EtherType)
+pub const PROTOCOL_TYPE_IPV4: u16 = 0x0800;
+
+/// Length of the fixed part preceding the addresses (types, lengths, operation)
+const ARP_FIXED_LEN: usize = 8;
+
+/// ARP packet with Ethernet hardware and IPv4 protocol addresses
+///
+/// Reference: RFC 826
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArpPacket {
+    pub hardware_type: u16,
+    pub protocol_type: u16,
+    pub operation: ArpOperation,
+    pub sender_hardware_address: MacAddr,
+    pub sender_protocol_address: [u8; 4],
+    pub target_hardware_address: MacAddr,
+    pub target_protocol_address: [u8; 4],
+}
+
+impl ArpPacket {
+    pub fn new(
+        operation: ArpOperation,
+        sender_hardware_address: MacAddr,
+        sender_protocol_address: [u8; 4],
+        target_hardware_address: MacAddr,
+        target_protocol_address: [u8; 4],
+    ) -> Self {
+        ArpPacket {
+            hardware_type: HARDWARE_TYPE_ETHERNET,
+            protocol_type: PROTOCOL_TYPE_IPV4,
+            operation,
+            sender_hardware_address,
+            sender_protocol_address,
+            target_hardware_address,
+            target_protocol_address,
+        }
+    }
+
+    /// Request asking who has `target_ip`; the target hardware address is zero
+    pub fn request(sender_mac: MacAddr, sender_ip: [u8; 4], target_ip: [u8; 4]) -> Self {
+        Self::new(
+            ArpOperation::Request,
+            sender_mac,
+            sender_ip,
+            MacAddr::new(0, 0, 0, 0, 0, 0),
+            target_ip,
+        )
+    }
+
+    /// Reply telling the requester at `target_mac`/`target_ip` that `sender_ip` is at `sender_mac`
+    pub fn reply(
+        sender_mac: MacAddr,
+        sender_ip: [u8; 4],
+        target_mac: MacAddr,
+        target_ip: [u8; 4],
+    ) -> Self {
+        Self::new(
+            ArpOperation::Reply,
+            sender_mac,
+            sender_ip,
+            target_mac,
+            target_ip,
+        )
+    }
+
+    /// Gratuitous ARP request announcing that `ip` is at `mac`
+    ///
+    /// Sender and target protocol addresses are both `ip`.
+    /// Reference: RFC 5944 Section 4.6
+    pub fn gratuitous(mac: MacAddr, ip: [u8; 4]) -> Self {
+        Self::request(mac, ip, ip)
+    }
+
+    /// ARP Probe checking whether `ip` is already in use
+    ///
+    /// The sender protocol address is all zeros so that no cache is updated.
+    /// Reference: RFC 5227 Section 2.1.1
+    pub fn probe(mac: MacAddr, ip: [u8; 4]) -> Self {
+        Self::request(mac, [0; 4], ip)
+    }
+
+    /// ARP Announcement claiming `ip` after probing
+    ///
+    /// Identical to a probe except that the sender protocol address is `ip`,
+    /// which makes it a gratuitous ARP request.
+    /// Reference: RFC 5227 Section 2.3
+    pub fn announce(mac: MacAddr, ip: [u8; 4]) -> Self {
+        Self::gratuitous(mac, ip)
+    }
+
+    /// Whether sender and target protocol addresses are the same
+    pub fn is_gratuitous(&self) -> bool {
+        self.sender_protocol_address == self.target_protocol_address
+    }
+
+    /// Whether this is a request with an all-zero sender protocol address
+    pub fn is_probe(&self) -> bool {
+        self.operation == ArpOperation::Request && self.sender_protocol_address == [0; 4]
+    }
+
+    /// Parse an ARP packet
+    ///
+    /// Only 6-byte hardware and 4-byte protocol addresses are supported; other
+    /// address lengths are reported as a bad header length. Trailing bytes,
+    /// such as Ethernet padding, are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < ARP_FIXED_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Arp,
+                needed: ARP_FIXED_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let hardware_len = bytes[4] as usize;
+        let protocol_len = bytes[5] as usize;
+        if hardware_len != 6 || protocol_len != 4 {
+            return Err(Error::BadHeaderLength {
+                layer: Layer::Arp,
+                length: ARP_FIXED_LEN + 2 * (hardware_len + protocol_len),
+            });
+        }
+        if bytes.len() < ARP_PACKET_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Arp,
+                needed: ARP_PACKET_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        Ok(ArpPacket {
+            hardware_type: u16::from_be_bytes([bytes[0], bytes[1]]),
+            protocol_type: u16::from_be_bytes([bytes[2], bytes[3]]),
+            operation: ArpOperation::from(u16::from_be_bytes([bytes[6], bytes[7]])),
+            sender_hardware_address: MacAddr::new(
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13],
+            ),
+            sender_protocol_address: [bytes[14], bytes[15], bytes[16], bytes[17]],
+            target_hardware_address: MacAddr::new(
+                bytes[18], bytes[19], bytes[20], bytes[21], bytes[22], bytes[23],
+            ),
+            target_protocol_address: [bytes[24], bytes[25], bytes[26], bytes[27]],
+        })
+    }
+
+    /// Length of the serialized packet in bytes
+    pub fn serialized_len(&self) -> usize {
+        ARP_PACKET_LEN
+    }
+
+    /// Serialize the packet into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, ARP_PACKET_LEN)?;
+
+        // Bytes 0-3: Hardware Type, Protocol Type
+        buf[0..2].copy_from_slice(&self.hardware_type.to_be_bytes());
+        buf[2..4].copy_from_slice(&self.protocol_type.to_be_bytes());
+
+        // Bytes 4-7: Hardware Length, Protocol Length, Operation
+        buf[4] = 6;
+        buf[5] = 4;
+        buf[6..8].copy_from_slice(&self.operation.to_u16().to_be_bytes());
+
+        // Bytes 8-27: Sender and Target addresses
+        buf[8..14].copy_from_slice(&self.sender_hardware_address.octets());
+        buf[14..18].copy_from_slice(&self.sender_protocol_address);
+        buf[18..24].copy_from_slice(&self.target_hardware_address.octets());
+        buf[24..28].copy_from_slice(&self.target_protocol_address);
+
+        Ok(ARP_PACKET_LEN)
+    }
+
+    /// Serialize the packet to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; ARP_PACKET_LEN];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arp_request_to_bytes() {
+        let packet = ArpPacket::request(
+            MacAddr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01),
+            [192, 168, 1, 100],
+            [192, 168, 1, 1],
+        );
+
+        let expect = [
+            0x00, 0x01, // Hardware Type: Ethernet
+            0x08, 0x00, // Protocol Type: IPv4
+            0x06, 0x04, // Hardware Length, Protocol Length
+            0x00, 0x01, // Operation: Request
+            0x02, 0x00, 0x00, 0x00, 0x00, 0x01, // Sender Hardware Address
+            192, 168, 1, 100, // Sender Protocol Address
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Target Hardware Address
+            192, 168, 1, 1, // Target Protocol Address
+        ];
+        let mut actual = [0u8; ARP_PACKET_LEN];
+        packet.write_to(&mut actual).unwrap();
+
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_arp_reply_roundtrip() {
+        let expect = ArpPacket::reply(
+            MacAddr::new(0x02, 0, 0, 0, 0, 2),
+            [10, 0, 0, 2],
+            MacAddr::new(0x02, 0, 0, 0, 0, 1),
+            [10, 0, 0, 1],
+        );
+        let bytes = expect.to_bytes();
+
+        let actual = ArpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert_eq!(ArpOperation::Reply, actual.operation);
+    }
+
+    #[test]
+    fn test_arp_gratuitous_probe_announce() {
+        let mac = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let probe = ArpPacket::probe(mac, [169, 254, 1, 2]);
+        assert!(probe.is_probe());
+        assert!(!probe.is_gratuitous());
+        assert_eq!([0; 4], probe.sender_protocol_address);
+        assert_eq!([169, 254, 1, 2], probe.target_protocol_address);
+
+        let mac = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let announce = ArpPacket::announce(mac, [169, 254, 1, 2]);
+        assert!(announce.is_gratuitous());
+        assert!(!announce.is_probe());
+        assert_eq!(ArpOperation::Request, announce.operation);
+        assert_eq!(
+            MacAddr::new(0, 0, 0, 0, 0, 0),
+            announce.target_hardware_address
+        );
+    }
+
+    #[test]
+    fn test_arp_from_bytes_ignores_padding() {
+        let mut bytes = [0u8; 46];
+        ArpPacket::request(MacAddr::broadcast(), [1, 2, 3, 4], [5, 6, 7, 8])
+            .write_to(&mut bytes)
+            .unwrap();
+
+        let actual = ArpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!([5, 6, 7, 8], actual.target_protocol_address);
+    }
+
+    #[test]
+    fn test_arp_from_bytes_truncated() {
+        let bytes = [0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x02];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Arp,
+            needed: 28,
+            got: 9,
+        });
+        let actual = ArpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_arp_from_bytes_unsupported_address_lengths() {
+        // IPv6 protocol addresses are not supported
+        let bytes = [0x00, 0x01, 0x86, 0xDD, 0x06, 0x10, 0x00, 0x01];
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Arp,
+            length: 52,
+        });
+        let actual = ArpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+}
@@ -0,0 +1,58 @@
+/// ARP operation code
+///
+/// Reference: RFC 826, RFC 903 (RARP) / IANA ARP Parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArpOperation {
+    Request,
+    Reply,
+    /// RARP request (RFC 903)
+    ReverseRequest,
+    /// RARP reply (RFC 903)
+    ReverseReply,
+    /// Any other operation code
+    Other(u16),
+}
+
+impl ArpOperation {
+    pub fn to_u16(self) -> u16 {
+        match self {
+            ArpOperation::Request => 1,
+            ArpOperation::Reply => 2,
+            ArpOperation::ReverseRequest => 3,
+            ArpOperation::ReverseReply => 4,
+            ArpOperation::Other(value) => value,
+        }
+    }
+}
+
+impl From<u16> for ArpOperation {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => ArpOperation::Request,
+            2 => ArpOperation::Reply,
+            3 => ArpOperation::ReverseRequest,
+            4 => ArpOperation::ReverseReply,
+            _ => ArpOperation::Other(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arp_operation_roundtrip() {
+        for value in 0..=10 {
+            let actual = ArpOperation::from(value).to_u16();
+            assert_eq!(value, actual);
+        }
+    }
+
+    #[test]
+    fn test_arp_operation_from_u16() {
+        assert_eq!(ArpOperation::Request, ArpOperation::from(1));
+        assert_eq!(ArpOperation::ReverseReply, ArpOperation::from(4));
+        assert_eq!(ArpOperation::Other(0x0100), ArpOperation::from(0x0100));
+    }
+}
@@ -0,0 +1,373 @@
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Length of the ICMP header (type, code, checksum and the 4-byte rest of header)
+pub const ICMPV4_HEADER_LEN: usize = 8;
+
+/// Length of Timestamp and Timestamp Reply messages, which have no data
+const TIMESTAMP_LEN: usize = 20;
+
+// ICMP message types
+/// Echo Reply (RFC 792)
+pub const ECHO_REPLY: u8 = 0;
+/// Destination Unreachable (RFC 792)
+pub const DESTINATION_UNREACHABLE: u8 = 3;
+/// Redirect (RFC 792)
+pub const REDIRECT: u8 = 5;
+/// Echo Request (RFC 792)
+pub const ECHO_REQUEST: u8 = 8;
+/// Time Exceeded (RFC 792)
+pub const TIME_EXCEEDED: u8 = 11;
+/// Parameter Problem (RFC 792)
+pub const PARAMETER_PROBLEM: u8 = 12;
+/// Timestamp (RFC 792)
+pub const TIMESTAMP: u8 = 13;
+/// Timestamp Reply (RFC 792)
+pub const TIMESTAMP_REPLY: u8 = 14;
+
+/// Destination Unreachable code for "fragmentation needed and DF set"
+///
+/// Reference: RFC 792, RFC 1191 Section 4 (next-hop MTU)
+pub const FRAGMENTATION_NEEDED: u8 = 4;
+
+/// Typed ICMP message body
+///
+/// Error messages carry the quoted original datagram in `original`; see
+/// [`write_quote`](super::write_quote).
+///
+/// Reference: RFC 792
+#[derive(Debug, PartialEq)]
+pub enum Icmpv4Message<'a> {
+    /// Echo Reply (Type 0)
+    EchoReply {
+        identifier: u16,
+        sequence_number: u16,
+        data: &'a [u8],
+    },
+    /// Destination Unreachable (Type 3); `next_hop_mtu` is used with code 4
+    DestinationUnreachable {
+        code: u8,
+        next_hop_mtu: u16,
+        original: &'a [u8],
+    },
+    /// Redirect (Type 5)
+    Redirect {
+        code: u8,
+        gateway: [u8; 4],
+        original: &'a [u8],
+    },
+    /// Echo Request (Type 8)
+    EchoRequest {
+        identifier: u16,
+        sequence_number: u16,
+        data: &'a [u8],
+    },
+    /// Time Exceeded (Type 11)
+    TimeExceeded { code: u8, original: &'a [u8] },
+    /// Parameter Problem (Type 12); `pointer` identifies the octet in error
+    ParameterProblem {
+        code: u8,
+        pointer: u8,
+        original: &'a [u8],
+    },
+    /// Timestamp (Type 13)
+    Timestamp {
+        identifier: u16,
+        sequence_number: u16,
+        originate: u32,
+        receive: u32,
+        transmit: u32,
+    },
+    /// Timestamp Reply (Type 14)
+    TimestampReply {
+        identifier: u16,
+        sequence_number: u16,
+        originate: u32,
+        receive: u32,
+        transmit: u32,
+    },
+    /// Any other message, preserved as-is
+    Unknown {
+        icmp_type: u8,
+        code: u8,
+        rest_of_header: [u8; 4],
+        data: &'a [u8],
+    },
+}
+
+impl<'a> Icmpv4Message<'a> {
+    /// Get the ICMP type field
+    pub fn icmp_type(&self) -> u8 {
+        match self {
+            Icmpv4Message::EchoReply { .. } => ECHO_REPLY,
+            Icmpv4Message::DestinationUnreachable { .. } => DESTINATION_UNREACHABLE,
+            Icmpv4Message::Redirect { .. } => REDIRECT,
+            Icmpv4Message::EchoRequest { .. } => ECHO_REQUEST,
+            Icmpv4Message::TimeExceeded { .. } => TIME_EXCEEDED,
+            Icmpv4Message::ParameterProblem { .. } => PARAMETER_PROBLEM,
+            Icmpv4Message::Timestamp { .. } => TIMESTAMP,
+            Icmpv4Message::TimestampReply { .. } => TIMESTAMP_REPLY,
+            Icmpv4Message::Unknown { icmp_type, .. } => *icmp_type,
+        }
+    }
+
+    /// Get the ICMP code field
+    pub fn code(&self) -> u8 {
+        match self {
+            Icmpv4Message::DestinationUnreachable { code, .. }
+            | Icmpv4Message::Redirect { code, .. }
+            | Icmpv4Message::TimeExceeded { code, .. }
+            | Icmpv4Message::ParameterProblem { code, .. }
+            | Icmpv4Message::Unknown { code, .. } => *code,
+            _ => 0,
+        }
+    }
+
+    /// Get the variable-length data following the fixed part of the message
+    pub fn data(&self) -> &'a [u8] {
+        match self {
+            Icmpv4Message::EchoReply { data, .. }
+            | Icmpv4Message::EchoRequest { data, .. }
+            | Icmpv4Message::Unknown { data, .. } => data,
+            Icmpv4Message::DestinationUnreachable { original, .. }
+            | Icmpv4Message::Redirect { original, .. }
+            | Icmpv4Message::TimeExceeded { original, .. }
+            | Icmpv4Message::ParameterProblem { original, .. } => original,
+            Icmpv4Message::Timestamp { .. } | Icmpv4Message::TimestampReply { .. } => &[],
+        }
+    }
+
+    /// Length of the fixed part of the message in bytes
+    pub fn header_len(&self) -> usize {
+        match self {
+            Icmpv4Message::Timestamp { .. } | Icmpv4Message::TimestampReply { .. } => TIMESTAMP_LEN,
+            _ => ICMPV4_HEADER_LEN,
+        }
+    }
+
+    /// Serialize the fixed part of the message into `buf` with a zero checksum
+    pub fn write_header(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.header_len();
+        ensure_capacity(buf, len)?;
+        let bytes = &mut buf[..len];
+
+        // Byte 0: Type, Byte 1: Code, Bytes 2-3: Checksum
+        bytes[0] = self.icmp_type();
+        bytes[1] = self.code();
+        bytes[2..4].fill(0);
+
+        // Bytes 4-7: Rest of header
+        match self {
+            Icmpv4Message::EchoReply {
+                identifier,
+                sequence_number,
+                ..
+            }
+            | Icmpv4Message::EchoRequest {
+                identifier,
+                sequence_number,
+                ..
+            } => {
+                bytes[4..6].copy_from_slice(&identifier.to_be_bytes());
+                bytes[6..8].copy_from_slice(&sequence_number.to_be_bytes());
+            }
+            Icmpv4Message::DestinationUnreachable { next_hop_mtu, .. } => {
+                bytes[4..6].fill(0);
+                bytes[6..8].copy_from_slice(&next_hop_mtu.to_be_bytes());
+            }
+            Icmpv4Message::Redirect { gateway, .. } => bytes[4..8].copy_from_slice(gateway),
+            Icmpv4Message::TimeExceeded { .. } => bytes[4..8].fill(0),
+            Icmpv4Message::ParameterProblem { pointer, .. } => {
+                bytes[4] = *pointer;
+                bytes[5..8].fill(0);
+            }
+            Icmpv4Message::Timestamp {
+                identifier,
+                sequence_number,
+                originate,
+                receive,
+                transmit,
+            }
+            | Icmpv4Message::TimestampReply {
+                identifier,
+                sequence_number,
+                originate,
+                receive,
+                transmit,
+            } => {
+                bytes[4..6].copy_from_slice(&identifier.to_be_bytes());
+                bytes[6..8].copy_from_slice(&sequence_number.to_be_bytes());
+                bytes[8..12].copy_from_slice(&originate.to_be_bytes());
+                bytes[12..16].copy_from_slice(&receive.to_be_bytes());
+                bytes[16..20].copy_from_slice(&transmit.to_be_bytes());
+            }
+            Icmpv4Message::Unknown { rest_of_header, .. } => {
+                bytes[4..8].copy_from_slice(rest_of_header)
+            }
+        }
+
+        Ok(len)
+    }
+
+    /// Parse a message from bytes, borrowing its data
+    ///
+    /// The checksum field is not interpreted here.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        if bytes.len() < ICMPV4_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Icmpv4,
+                needed: ICMPV4_HEADER_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let icmp_type = bytes[0];
+        let code = bytes[1];
+        let identifier = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let sequence_number = u16::from_be_bytes([bytes[6], bytes[7]]);
+        let data = &bytes[ICMPV4_HEADER_LEN..];
+
+        let message = match icmp_type {
+            ECHO_REPLY => Icmpv4Message::EchoReply {
+                identifier,
+                sequence_number,
+                data,
+            },
+            DESTINATION_UNREACHABLE => Icmpv4Message::DestinationUnreachable {
+                code,
+                next_hop_mtu: sequence_number,
+                original: data,
+            },
+            REDIRECT => Icmpv4Message::Redirect {
+                code,
+                gateway: [bytes[4], bytes[5], bytes[6], bytes[7]],
+                original: data,
+            },
+            ECHO_REQUEST => Icmpv4Message::EchoRequest {
+                identifier,
+                sequence_number,
+                data,
+            },
+            TIME_EXCEEDED => Icmpv4Message::TimeExceeded {
+                code,
+                original: data,
+            },
+            PARAMETER_PROBLEM => Icmpv4Message::ParameterProblem {
+                code,
+                pointer: bytes[4],
+                original: data,
+            },
+            TIMESTAMP | TIMESTAMP_REPLY => {
+                if bytes.len() < TIMESTAMP_LEN {
+                    return Err(Error::Truncated {
+                        layer: Layer::Icmpv4,
+                        needed: TIMESTAMP_LEN,
+                        got: bytes.len(),
+                    });
+                }
+                let originate = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+                let receive = u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+                let transmit = u32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]);
+                if icmp_type == TIMESTAMP {
+                    Icmpv4Message::Timestamp {
+                        identifier,
+                        sequence_number,
+                        originate,
+                        receive,
+                        transmit,
+                    }
+                } else {
+                    Icmpv4Message::TimestampReply {
+                        identifier,
+                        sequence_number,
+                        originate,
+                        receive,
+                        transmit,
+                    }
+                }
+            }
+            _ => Icmpv4Message::Unknown {
+                icmp_type,
+                code,
+                rest_of_header: [bytes[4], bytes[5], bytes[6], bytes[7]],
+                data,
+            },
+        };
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icmpv4_message_type_and_code() {
+        let message = Icmpv4Message::DestinationUnreachable {
+            code: FRAGMENTATION_NEEDED,
+            next_hop_mtu: 1400,
+            original: &[],
+        };
+        assert_eq!(3, message.icmp_type());
+        assert_eq!(4, message.code());
+
+        let message = Icmpv4Message::EchoRequest {
+            identifier: 1,
+            sequence_number: 1,
+            data: b"ping",
+        };
+        assert_eq!(8, message.icmp_type());
+        assert_eq!(0, message.code());
+        assert_eq!(b"ping", message.data());
+    }
+
+    #[test]
+    fn test_icmpv4_message_write_header_parameter_problem() {
+        let message = Icmpv4Message::ParameterProblem {
+            code: 0,
+            pointer: 9,
+            original: &[],
+        };
+        let mut buf = [0xFFu8; 8];
+        let len = message.write_header(&mut buf).unwrap();
+
+        assert_eq!(8, len);
+        assert_eq!([12, 0, 0, 0, 9, 0, 0, 0], buf);
+    }
+
+    #[test]
+    fn test_icmpv4_message_write_header_redirect() {
+        let message = Icmpv4Message::Redirect {
+            code: 1, // Redirect for host
+            gateway: [10, 0, 0, 254],
+            original: &[],
+        };
+        let mut buf = [0u8; 8];
+        message.write_header(&mut buf).unwrap();
+        assert_eq!([5, 1, 0, 0, 10, 0, 0, 254], buf);
+    }
+
+    #[test]
+    fn test_icmpv4_message_from_bytes_timestamp_truncated() {
+        let bytes = [13, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv4,
+            needed: 20,
+            got: 12,
+        });
+        let actual = Icmpv4Message::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_icmpv4_message_from_bytes_unknown() {
+        let bytes = [42, 7, 0, 0, 1, 2, 3, 4, 0xAA];
+        let expect = Icmpv4Message::Unknown {
+            icmp_type: 42,
+            code: 7,
+            rest_of_header: [1, 2, 3, 4],
+            data: &[0xAA],
+        };
+        let actual = Icmpv4Message::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+}
@@ -0,0 +1,297 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod message;
+
+use crate::checksum::{Checksum, ChecksumVerification};
+use crate::error::{Error, ensure_capacity};
+use crate::network::ipv4::Ipv4Packet;
+use message::Icmpv4Message;
+
+/// Number of bytes of the original payload quoted in ICMP error messages
+///
+/// Reference: RFC 792 - "Internet Header + 64 bits of Data Datagram"
+pub const QUOTED_PAYLOAD_LEN: usize = 8;
+
+/// ICMP message with its checksum
+///
+/// Reference: RFC 792
+#[derive(Debug, PartialEq)]
+pub struct Icmpv4Packet<'a> {
+    pub message: Icmpv4Message<'a>,
+    pub checksum: u16,
+}
+
+impl<'a> Icmpv4Packet<'a> {
+    pub fn new(message: Icmpv4Message<'a>) -> Self {
+        Icmpv4Packet {
+            message,
+            checksum: 0,
+        }
+    }
+
+    /// Parse an ICMP message, borrowing its data from `bytes`
+    ///
+    /// All bytes after the fixed part of the message are taken as data, so
+    /// `bytes` should be delimited by the enclosing IPv4 packet.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let message = Icmpv4Message::from_bytes(bytes)?;
+        let checksum = u16::from_be_bytes([bytes[2], bytes[3]]);
+        Ok(Icmpv4Packet { message, checksum })
+    }
+
+    /// Calculate the ICMP checksum over the message with the checksum field set to 0
+    pub fn calculate_checksum(&self) -> u16 {
+        let mut header = [0u8; 20];
+        let header_len = self
+            .message
+            .write_header(&mut header)
+            .expect("fixed part of message fits in 20 bytes");
+
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&header[..header_len]);
+        checksum.add_bytes(self.message.data());
+        checksum.finish()
+    }
+
+    /// Verify the stored checksum against the message
+    pub fn verify_checksum(&self) -> ChecksumVerification {
+        ChecksumVerification {
+            stored: self.checksum,
+            expected: self.calculate_checksum(),
+        }
+    }
+
+    /// Length of the serialized message in bytes
+    pub fn serialized_len(&self) -> usize {
+        self.message.header_len() + self.message.data().len()
+    }
+
+    /// Serialize the message into `buf`, returning the number of bytes written
+    ///
+    /// The checksum written to `buf` is calculated from the message; unlike
+    /// [`Icmpv4Packet::to_bytes`], `self.checksum` is left unchanged.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        let header_len = self.message.write_header(buf)?;
+        buf[header_len..total_len].copy_from_slice(self.message.data());
+
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&buf[..total_len]);
+        buf[2..4].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        Ok(total_len)
+    }
+
+    /// Update the checksum, then serialize the message
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&mut self) -> Vec<u8> {
+        self.checksum = self.calculate_checksum();
+
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+}
+
+/// Length of the quote of `packet` carried in an ICMP error message
+pub fn quote_len(packet: &Ipv4Packet) -> usize {
+    packet.header_len() + packet.payload.len().min(QUOTED_PAYLOAD_LEN)
+}
+
+/// Write the offending datagram's header plus the first 8 payload bytes into `buf`
+///
+/// The header and options are quoted as stored in `packet`, so a received
+/// datagram is quoted with the total length and checksum it arrived with.
+/// Fails if the options cannot be serialized.
+///
+/// Reference: RFC 792 - Destination Unreachable Message
+pub fn write_quote(packet: &Ipv4Packet, buf: &mut [u8]) -> Result<usize, Error> {
+    let len = quote_len(packet);
+    ensure_capacity(buf, len)?;
+
+    let header_len = packet.header.write_to(buf)?;
+    let header_len = header_len + packet.options.write_to(&mut buf[header_len..])?;
+    buf[header_len..len].copy_from_slice(&packet.payload[..len - header_len]);
+
+    Ok(len)
+}
+
+/// Quote the offending datagram for use as the `original` of an error message
+#[cfg(feature = "alloc")]
+pub fn quote(packet: &Ipv4Packet) -> Result<Vec<u8>, Error> {
+    let mut bytes = alloc::vec![0; quote_len(packet)];
+    write_quote(packet, &mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use super::message::*;
+    use super::*;
+    use crate::error::Layer;
+    use crate::network::ip_protocol::IpProtocol;
+    #[cfg(feature = "alloc")]
+    use crate::network::ipv4::header::Ipv4Header;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_echo_request_to_bytes() {
+        let mut packet = Icmpv4Packet::new(Icmpv4Message::EchoRequest {
+            identifier: 0x1234,
+            sequence_number: 1,
+            data: b"abcd",
+        });
+
+        let expect = vec![
+            0x08, 0x00, // Type, Code
+            0x21, 0x04, // Checksum
+            0x12, 0x34, // Identifier
+            0x00, 0x01, // Sequence Number
+            b'a', b'b', b'c', b'd', // Data
+        ];
+        let actual = packet.to_bytes();
+
+        assert_eq!(expect, actual);
+        assert_eq!(0x2104, packet.checksum);
+        assert!(packet.verify_checksum().is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_echo_reply_roundtrip() {
+        let mut expect = Icmpv4Packet::new(Icmpv4Message::EchoReply {
+            identifier: 7,
+            sequence_number: 42,
+            data: &[1, 2, 3],
+        });
+        let bytes = expect.to_bytes();
+
+        let actual = Icmpv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert!(actual.verify_checksum().is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_timestamp_roundtrip() {
+        let mut expect = Icmpv4Packet::new(Icmpv4Message::TimestampReply {
+            identifier: 1,
+            sequence_number: 2,
+            originate: 1000,
+            receive: 2000,
+            transmit: 2001,
+        });
+        let bytes = expect.to_bytes();
+        assert_eq!(20, bytes.len());
+
+        let actual = Icmpv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_icmpv4_from_bytes_truncated() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv4,
+            needed: 8,
+            got: 4,
+        });
+        let actual = Icmpv4Packet::from_bytes(&[8, 0, 0, 0]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_verify_checksum_corrupted() {
+        let mut packet = Icmpv4Packet::new(Icmpv4Message::TimeExceeded {
+            code: 0,
+            original: &[0x45, 0x00],
+        });
+        let mut bytes = packet.to_bytes();
+        bytes[9] ^= 0xFF;
+
+        let actual = Icmpv4Packet::from_bytes(&bytes).unwrap();
+        assert!(!actual.verify_checksum().is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_quote_truncates_payload() {
+        let payload = [0xAAu8; 20];
+        let mut original =
+            Ipv4Packet::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Udp, &payload);
+        original.update_total_length().unwrap();
+
+        let actual = quote(&original).unwrap();
+
+        assert_eq!(28, quote_len(&original));
+        assert_eq!(28, actual.len());
+        assert_eq!([0xAA; 8], actual[20..]);
+
+        // The quoted header still describes the full original datagram
+        let header = Ipv4Header::from_bytes(&actual).unwrap();
+        assert_eq!(40, header.total_length);
+        assert_eq!(IpProtocol::Udp, header.protocol);
+        assert_eq!([10, 0, 0, 1], header.destination_address);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_quote_header_verbatim() {
+        let payload = [0xBBu8; 12];
+        let mut original =
+            Ipv4Packet::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Udp, &payload);
+        original.header.total_length = 1500;
+        original.header.header_checksum = 0xDEAD;
+
+        let actual = quote(&original).unwrap();
+
+        let header = Ipv4Header::from_bytes(&actual).unwrap();
+        assert_eq!(1500, header.total_length);
+        assert_eq!(0xDEAD, header.header_checksum);
+        assert_eq!(original.header.to_bytes(), actual[..20]);
+        assert_eq!([0xBB; 8], actual[20..]);
+    }
+
+    #[test]
+    fn test_icmpv4_quote_short_payload() {
+        let original =
+            Ipv4Packet::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Tcp, &[1, 2, 3]);
+        assert_eq!(23, quote_len(&original));
+
+        let mut buf = [0u8; 22];
+        let expect = Err(Error::BufferTooSmall {
+            needed: 23,
+            got: 22,
+        });
+        assert_eq!(expect, write_quote(&original, &mut buf));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv4_fragmentation_needed() {
+        let payload = [0u8; 1500];
+        let original = Ipv4Packet::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Tcp, &payload);
+        let quoted = quote(&original).unwrap();
+
+        let mut packet = Icmpv4Packet::new(Icmpv4Message::DestinationUnreachable {
+            code: FRAGMENTATION_NEEDED,
+            next_hop_mtu: 1400,
+            original: &quoted,
+        });
+        let bytes = packet.to_bytes();
+
+        assert_eq!([3, 4], bytes[0..2]);
+        assert_eq!([0, 0, 0x05, 0x78], bytes[4..8]);
+        assert_eq!(quoted[..], bytes[8..]);
+
+        let actual = Icmpv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(packet, actual);
+    }
+}
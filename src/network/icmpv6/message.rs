@@ -0,0 +1,746 @@
+use alloc::vec::Vec;
+
+use super::mld::{MldV2Query, MulticastAddressRecord, check_count};
+use super::ndp::NdpOptions;
+use super::read_address;
+use crate::checksum::Checksum;
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Length of the ICMPv6 header (type, code, checksum and the 4-byte message body start)
+pub const ICMPV6_HEADER_LEN: usize = 8;
+
+/// Length of the largest fixed part of a message (Redirect)
+pub(crate) const MAX_FIXED_LEN: usize = 40;
+
+// ICMPv6 message types
+/// Destination Unreachable (RFC 4443 Section 3.1)
+pub const DESTINATION_UNREACHABLE: u8 = 1;
+/// Packet Too Big (RFC 4443 Section 3.2)
+pub const PACKET_TOO_BIG: u8 = 2;
+/// Time Exceeded (RFC 4443 Section 3.3)
+pub const TIME_EXCEEDED: u8 = 3;
+/// Parameter Problem (RFC 4443 Section 3.4)
+pub const PARAMETER_PROBLEM: u8 = 4;
+/// Echo Request (RFC 4443 Section 4.1)
+pub const ECHO_REQUEST: u8 = 128;
+/// Echo Reply (RFC 4443 Section 4.2)
+pub const ECHO_REPLY: u8 = 129;
+/// Multicast Listener Query, MLDv1 or MLDv2 (RFC 2710 Section 3, RFC 3810 Section 5.1)
+pub const MULTICAST_LISTENER_QUERY: u8 = 130;
+/// Version 1 Multicast Listener Report (RFC 2710 Section 3)
+pub const MULTICAST_LISTENER_REPORT: u8 = 131;
+/// Multicast Listener Done (RFC 2710 Section 3)
+pub const MULTICAST_LISTENER_DONE: u8 = 132;
+/// Router Solicitation (RFC 4861 Section 4.1)
+pub const ROUTER_SOLICITATION: u8 = 133;
+/// Router Advertisement (RFC 4861 Section 4.2)
+pub const ROUTER_ADVERTISEMENT: u8 = 134;
+/// Neighbor Solicitation (RFC 4861 Section 4.3)
+pub const NEIGHBOR_SOLICITATION: u8 = 135;
+/// Neighbor Advertisement (RFC 4861 Section 4.4)
+pub const NEIGHBOR_ADVERTISEMENT: u8 = 136;
+/// Redirect (RFC 4861 Section 4.5)
+pub const REDIRECT: u8 = 137;
+/// Version 2 Multicast Listener Report (RFC 3810 Section 5.2)
+pub const MULTICAST_LISTENER_REPORT_V2: u8 = 143;
+
+/// Length of an MLDv1 message in bytes
+const MLD_LEN: usize = 24;
+
+/// Length of the fixed part of an MLDv2 query in bytes
+const MLDV2_QUERY_FIXED_LEN: usize = 28;
+
+/// Typed ICMPv6 message body
+///
+/// Reference: RFC 4443, RFC 4861 (Neighbor Discovery), RFC 2710 (MLDv1),
+/// RFC 3810 (MLDv2)
+#[derive(Debug, PartialEq)]
+pub enum Icmpv6Message<'a> {
+    /// Destination Unreachable (Type 1)
+    DestinationUnreachable { code: u8, original: &'a [u8] },
+    /// Packet Too Big (Type 2)
+    PacketTooBig { mtu: u32, original: &'a [u8] },
+    /// Time Exceeded (Type 3)
+    TimeExceeded { code: u8, original: &'a [u8] },
+    /// Parameter Problem (Type 4); `pointer` is the offset of the error
+    ParameterProblem {
+        code: u8,
+        pointer: u32,
+        original: &'a [u8],
+    },
+    /// Echo Request (Type 128)
+    EchoRequest {
+        identifier: u16,
+        sequence_number: u16,
+        data: &'a [u8],
+    },
+    /// Echo Reply (Type 129)
+    EchoReply {
+        identifier: u16,
+        sequence_number: u16,
+        data: &'a [u8],
+    },
+    /// MLDv1 Multicast Listener Query (Type 130, 24 bytes)
+    MldQuery {
+        maximum_response_delay: u16,
+        multicast_address: [u8; 16],
+    },
+    /// MLDv1 Multicast Listener Report (Type 131)
+    MldReport { multicast_address: [u8; 16] },
+    /// MLDv1 Multicast Listener Done (Type 132)
+    MldDone { multicast_address: [u8; 16] },
+    /// MLDv2 Multicast Listener Query (Type 130, 28 bytes or more)
+    MldV2Query(MldV2Query),
+    /// MLDv2 Multicast Listener Report (Type 143)
+    MldV2Report {
+        records: Vec<MulticastAddressRecord>,
+    },
+    /// Router Solicitation (Type 133)
+    RouterSolicitation { options: NdpOptions },
+    /// Router Advertisement (Type 134)
+    RouterAdvertisement {
+        current_hop_limit: u8,
+        /// M flag: addresses are available via DHCPv6
+        managed: bool,
+        /// O flag: other configuration is available via DHCPv6
+        other_config: bool,
+        /// Remaining 6 bits of the flags byte
+        reserved: u8,
+        router_lifetime: u16,
+        reachable_time: u32,
+        retrans_timer: u32,
+        options: NdpOptions,
+    },
+    /// Neighbor Solicitation (Type 135)
+    NeighborSolicitation {
+        target: [u8; 16],
+        options: NdpOptions,
+    },
+    /// Neighbor Advertisement (Type 136)
+    NeighborAdvertisement {
+        /// R flag: the sender is a router
+        router: bool,
+        /// S flag: sent in response to a Neighbor Solicitation
+        solicited: bool,
+        /// O flag: override an existing cache entry
+        override_flag: bool,
+        target: [u8; 16],
+        options: NdpOptions,
+    },
+    /// Redirect (Type 137)
+    Redirect {
+        target: [u8; 16],
+        destination: [u8; 16],
+        options: NdpOptions,
+    },
+    /// Any other message, preserved as-is
+    Unknown {
+        icmp_type: u8,
+        code: u8,
+        rest_of_header: [u8; 4],
+        data: &'a [u8],
+    },
+}
+
+impl<'a> Icmpv6Message<'a> {
+    /// Get the ICMPv6 type field
+    pub fn icmp_type(&self) -> u8 {
+        match self {
+            Icmpv6Message::DestinationUnreachable { .. } => DESTINATION_UNREACHABLE,
+            Icmpv6Message::PacketTooBig { .. } => PACKET_TOO_BIG,
+            Icmpv6Message::TimeExceeded { .. } => TIME_EXCEEDED,
+            Icmpv6Message::ParameterProblem { .. } => PARAMETER_PROBLEM,
+            Icmpv6Message::EchoRequest { .. } => ECHO_REQUEST,
+            Icmpv6Message::EchoReply { .. } => ECHO_REPLY,
+            Icmpv6Message::MldQuery { .. } | Icmpv6Message::MldV2Query(_) => {
+                MULTICAST_LISTENER_QUERY
+            }
+            Icmpv6Message::MldReport { .. } => MULTICAST_LISTENER_REPORT,
+            Icmpv6Message::MldDone { .. } => MULTICAST_LISTENER_DONE,
+            Icmpv6Message::MldV2Report { .. } => MULTICAST_LISTENER_REPORT_V2,
+            Icmpv6Message::RouterSolicitation { .. } => ROUTER_SOLICITATION,
+            Icmpv6Message::RouterAdvertisement { .. } => ROUTER_ADVERTISEMENT,
+            Icmpv6Message::NeighborSolicitation { .. } => NEIGHBOR_SOLICITATION,
+            Icmpv6Message::NeighborAdvertisement { .. } => NEIGHBOR_ADVERTISEMENT,
+            Icmpv6Message::Redirect { .. } => REDIRECT,
+            Icmpv6Message::Unknown { icmp_type, .. } => *icmp_type,
+        }
+    }
+
+    /// Get the ICMPv6 code field
+    pub fn code(&self) -> u8 {
+        match self {
+            Icmpv6Message::DestinationUnreachable { code, .. }
+            | Icmpv6Message::TimeExceeded { code, .. }
+            | Icmpv6Message::ParameterProblem { code, .. }
+            | Icmpv6Message::Unknown { code, .. } => *code,
+            _ => 0,
+        }
+    }
+
+    /// Neighbor Discovery options carried by the message, if any
+    pub fn ndp_options(&self) -> Option<&NdpOptions> {
+        match self {
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::RouterAdvertisement { options, .. }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::NeighborAdvertisement { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => Some(options),
+            _ => None,
+        }
+    }
+
+    /// Length of the fixed part of the message in bytes
+    pub fn header_len(&self) -> usize {
+        match self {
+            Icmpv6Message::RouterAdvertisement { .. } => 16,
+            Icmpv6Message::MldQuery { .. }
+            | Icmpv6Message::MldReport { .. }
+            | Icmpv6Message::MldDone { .. }
+            | Icmpv6Message::NeighborSolicitation { .. }
+            | Icmpv6Message::NeighborAdvertisement { .. } => MLD_LEN,
+            Icmpv6Message::MldV2Query(_) => MLDV2_QUERY_FIXED_LEN,
+            Icmpv6Message::Redirect { .. } => MAX_FIXED_LEN,
+            _ => ICMPV6_HEADER_LEN,
+        }
+    }
+
+    /// Length of the variable part of the message in bytes
+    fn body_len(&self) -> usize {
+        match self {
+            Icmpv6Message::DestinationUnreachable { original, .. }
+            | Icmpv6Message::PacketTooBig { original, .. }
+            | Icmpv6Message::TimeExceeded { original, .. }
+            | Icmpv6Message::ParameterProblem { original, .. } => original.len(),
+            Icmpv6Message::EchoRequest { data, .. }
+            | Icmpv6Message::EchoReply { data, .. }
+            | Icmpv6Message::Unknown { data, .. } => data.len(),
+            Icmpv6Message::MldQuery { .. }
+            | Icmpv6Message::MldReport { .. }
+            | Icmpv6Message::MldDone { .. } => 0,
+            Icmpv6Message::MldV2Query(query) => query.sources.len() * 16,
+            Icmpv6Message::MldV2Report { records } => records
+                .iter()
+                .map(MulticastAddressRecord::serialized_len)
+                .sum(),
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::RouterAdvertisement { options, .. }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::NeighborAdvertisement { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => options.serialized_len(),
+        }
+    }
+
+    /// Length of the serialized message in bytes
+    pub fn serialized_len(&self) -> usize {
+        self.header_len() + self.body_len()
+    }
+
+    /// Serialize the fixed part of the message into `buf` with a zero checksum
+    pub fn write_header(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.header_len();
+        ensure_capacity(buf, len)?;
+        let bytes = &mut buf[..len];
+
+        // Byte 0: Type, Byte 1: Code, Bytes 2-3: Checksum
+        bytes[0] = self.icmp_type();
+        bytes[1] = self.code();
+        bytes[2..].fill(0);
+
+        match self {
+            Icmpv6Message::DestinationUnreachable { .. }
+            | Icmpv6Message::TimeExceeded { .. }
+            | Icmpv6Message::RouterSolicitation { .. } => {}
+            Icmpv6Message::PacketTooBig { mtu: value, .. }
+            | Icmpv6Message::ParameterProblem { pointer: value, .. } => {
+                bytes[4..8].copy_from_slice(&value.to_be_bytes());
+            }
+            Icmpv6Message::EchoRequest {
+                identifier,
+                sequence_number,
+                ..
+            }
+            | Icmpv6Message::EchoReply {
+                identifier,
+                sequence_number,
+                ..
+            } => {
+                bytes[4..6].copy_from_slice(&identifier.to_be_bytes());
+                bytes[6..8].copy_from_slice(&sequence_number.to_be_bytes());
+            }
+            Icmpv6Message::MldQuery {
+                maximum_response_delay,
+                multicast_address,
+            } => {
+                bytes[4..6].copy_from_slice(&maximum_response_delay.to_be_bytes());
+                bytes[8..24].copy_from_slice(multicast_address);
+            }
+            Icmpv6Message::MldReport { multicast_address }
+            | Icmpv6Message::MldDone { multicast_address } => {
+                bytes[8..24].copy_from_slice(multicast_address);
+            }
+            Icmpv6Message::MldV2Query(query) => {
+                bytes[4..6].copy_from_slice(&query.maximum_response_code.to_be_bytes());
+                bytes[8..24].copy_from_slice(&query.multicast_address);
+                bytes[24] =
+                    ((query.suppress_router_processing as u8) << 3) | (query.robustness & 0x07);
+                bytes[25] = query.query_interval_code;
+                bytes[26..28].copy_from_slice(&(query.sources.len() as u16).to_be_bytes());
+            }
+            Icmpv6Message::MldV2Report { records } => {
+                bytes[6..8].copy_from_slice(&(records.len() as u16).to_be_bytes());
+            }
+            Icmpv6Message::RouterAdvertisement {
+                current_hop_limit,
+                managed,
+                other_config,
+                reserved,
+                router_lifetime,
+                reachable_time,
+                retrans_timer,
+                ..
+            } => {
+                bytes[4] = *current_hop_limit;
+                bytes[5] =
+                    ((*managed as u8) << 7) | ((*other_config as u8) << 6) | (reserved & 0x3F);
+                bytes[6..8].copy_from_slice(&router_lifetime.to_be_bytes());
+                bytes[8..12].copy_from_slice(&reachable_time.to_be_bytes());
+                bytes[12..16].copy_from_slice(&retrans_timer.to_be_bytes());
+            }
+            Icmpv6Message::NeighborSolicitation { target, .. } => {
+                bytes[8..24].copy_from_slice(target);
+            }
+            Icmpv6Message::NeighborAdvertisement {
+                router,
+                solicited,
+                override_flag,
+                target,
+                ..
+            } => {
+                bytes[4] = ((*router as u8) << 7)
+                    | ((*solicited as u8) << 6)
+                    | ((*override_flag as u8) << 5);
+                bytes[8..24].copy_from_slice(target);
+            }
+            Icmpv6Message::Redirect {
+                target,
+                destination,
+                ..
+            } => {
+                bytes[8..24].copy_from_slice(target);
+                bytes[24..40].copy_from_slice(destination);
+            }
+            Icmpv6Message::Unknown { rest_of_header, .. } => {
+                bytes[4..8].copy_from_slice(rest_of_header);
+            }
+        }
+
+        Ok(len)
+    }
+
+    /// Serialize the variable part of the message into `buf`
+    fn write_body(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.body_len();
+        ensure_capacity(buf, len)?;
+
+        match self {
+            Icmpv6Message::DestinationUnreachable { original: data, .. }
+            | Icmpv6Message::PacketTooBig { original: data, .. }
+            | Icmpv6Message::TimeExceeded { original: data, .. }
+            | Icmpv6Message::ParameterProblem { original: data, .. }
+            | Icmpv6Message::EchoRequest { data, .. }
+            | Icmpv6Message::EchoReply { data, .. }
+            | Icmpv6Message::Unknown { data, .. } => buf[..len].copy_from_slice(data),
+            Icmpv6Message::MldQuery { .. }
+            | Icmpv6Message::MldReport { .. }
+            | Icmpv6Message::MldDone { .. } => {}
+            Icmpv6Message::MldV2Query(query) => {
+                for (chunk, source) in buf[..len].chunks_exact_mut(16).zip(&query.sources) {
+                    chunk.copy_from_slice(source);
+                }
+            }
+            Icmpv6Message::MldV2Report { records } => {
+                let mut offset = 0;
+                for record in records {
+                    offset += record.write_to(&mut buf[offset..])?;
+                }
+            }
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::RouterAdvertisement { options, .. }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::NeighborAdvertisement { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => {
+                options.write_to(buf)?;
+            }
+        }
+
+        Ok(len)
+    }
+
+    /// Serialize the message into `buf` with a zero checksum
+    ///
+    /// Fails with [`Error::BadTotalLength`] if an MLDv2 message has more than
+    /// 65535 sources or records, or if an option or record cannot be serialized.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            Icmpv6Message::MldV2Query(query) => check_count(query.sources.len(), self.body_len())?,
+            Icmpv6Message::MldV2Report { records } => check_count(records.len(), self.body_len())?,
+            _ => {}
+        }
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        let header_len = self.write_header(buf)?;
+        self.write_body(&mut buf[header_len..])?;
+        Ok(total_len)
+    }
+
+    /// Add the message, with a zero checksum, to a checksum without allocating
+    pub(crate) fn add_to_checksum(&self, checksum: &mut Checksum) {
+        let mut header = [0u8; MAX_FIXED_LEN];
+        let header_len = self
+            .write_header(&mut header)
+            .expect("buffer sized for the largest fixed part");
+        checksum.add_bytes(&header[..header_len]);
+
+        match self {
+            Icmpv6Message::DestinationUnreachable { original: data, .. }
+            | Icmpv6Message::PacketTooBig { original: data, .. }
+            | Icmpv6Message::TimeExceeded { original: data, .. }
+            | Icmpv6Message::ParameterProblem { original: data, .. }
+            | Icmpv6Message::EchoRequest { data, .. }
+            | Icmpv6Message::EchoReply { data, .. }
+            | Icmpv6Message::Unknown { data, .. } => checksum.add_bytes(data),
+            Icmpv6Message::MldQuery { .. }
+            | Icmpv6Message::MldReport { .. }
+            | Icmpv6Message::MldDone { .. } => {}
+            Icmpv6Message::MldV2Query(query) => {
+                for source in &query.sources {
+                    checksum.add_bytes(source);
+                }
+            }
+            Icmpv6Message::MldV2Report { records } => {
+                for record in records {
+                    record.add_to_checksum(checksum);
+                }
+            }
+            Icmpv6Message::RouterSolicitation { options }
+            | Icmpv6Message::RouterAdvertisement { options, .. }
+            | Icmpv6Message::NeighborSolicitation { options, .. }
+            | Icmpv6Message::NeighborAdvertisement { options, .. }
+            | Icmpv6Message::Redirect { options, .. } => options.add_to_checksum(checksum),
+        }
+    }
+
+    /// Parse a message from bytes, borrowing its data
+    ///
+    /// The checksum field is not interpreted here. A Multicast Listener Query
+    /// is parsed as MLDv1 if it is exactly 24 bytes long and as MLDv2 otherwise
+    /// (RFC 3810 Section 8.1).
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let truncated = |needed: usize| Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed,
+            got: bytes.len(),
+        };
+
+        if bytes.len() < ICMPV6_HEADER_LEN {
+            return Err(truncated(ICMPV6_HEADER_LEN));
+        }
+
+        let icmp_type = bytes[0];
+        let code = bytes[1];
+        let word = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        let identifier = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let sequence_number = u16::from_be_bytes([bytes[6], bytes[7]]);
+        let data = &bytes[ICMPV6_HEADER_LEN..];
+
+        let fixed_len = match icmp_type {
+            ROUTER_ADVERTISEMENT => 16,
+            MULTICAST_LISTENER_QUERY
+            | MULTICAST_LISTENER_REPORT
+            | MULTICAST_LISTENER_DONE
+            | NEIGHBOR_SOLICITATION
+            | NEIGHBOR_ADVERTISEMENT => MLD_LEN,
+            REDIRECT => MAX_FIXED_LEN,
+            _ => ICMPV6_HEADER_LEN,
+        };
+        if bytes.len() < fixed_len {
+            return Err(truncated(fixed_len));
+        }
+
+        let message = match icmp_type {
+            DESTINATION_UNREACHABLE => Icmpv6Message::DestinationUnreachable {
+                code,
+                original: data,
+            },
+            PACKET_TOO_BIG => Icmpv6Message::PacketTooBig {
+                mtu: word,
+                original: data,
+            },
+            TIME_EXCEEDED => Icmpv6Message::TimeExceeded {
+                code,
+                original: data,
+            },
+            PARAMETER_PROBLEM => Icmpv6Message::ParameterProblem {
+                code,
+                pointer: word,
+                original: data,
+            },
+            ECHO_REQUEST => Icmpv6Message::EchoRequest {
+                identifier,
+                sequence_number,
+                data,
+            },
+            ECHO_REPLY => Icmpv6Message::EchoReply {
+                identifier,
+                sequence_number,
+                data,
+            },
+            MULTICAST_LISTENER_QUERY if bytes.len() == MLD_LEN => Icmpv6Message::MldQuery {
+                maximum_response_delay: identifier,
+                multicast_address: read_address(&bytes[8..24]),
+            },
+            MULTICAST_LISTENER_QUERY => {
+                if bytes.len() < MLDV2_QUERY_FIXED_LEN {
+                    return Err(truncated(MLDV2_QUERY_FIXED_LEN));
+                }
+                let source_count = u16::from_be_bytes([bytes[26], bytes[27]]) as usize;
+                let sources_end = MLDV2_QUERY_FIXED_LEN + source_count * 16;
+                if bytes.len() < sources_end {
+                    return Err(truncated(sources_end));
+                }
+                Icmpv6Message::MldV2Query(MldV2Query {
+                    maximum_response_code: identifier,
+                    multicast_address: read_address(&bytes[8..24]),
+                    suppress_router_processing: bytes[24] & 0x08 != 0,
+                    robustness: bytes[24] & 0x07,
+                    query_interval_code: bytes[25],
+                    sources: bytes[MLDV2_QUERY_FIXED_LEN..sources_end]
+                        .chunks_exact(16)
+                        .map(read_address)
+                        .collect(),
+                })
+            }
+            MULTICAST_LISTENER_REPORT => Icmpv6Message::MldReport {
+                multicast_address: read_address(&bytes[8..24]),
+            },
+            MULTICAST_LISTENER_DONE => Icmpv6Message::MldDone {
+                multicast_address: read_address(&bytes[8..24]),
+            },
+            MULTICAST_LISTENER_REPORT_V2 => {
+                let record_count = sequence_number as usize;
+                let mut records = Vec::with_capacity(record_count.min(data.len() / 20));
+                let mut offset = 0;
+                for _ in 0..record_count {
+                    let (record, consumed) = MulticastAddressRecord::from_bytes(&data[offset..])?;
+                    records.push(record);
+                    offset += consumed;
+                }
+                Icmpv6Message::MldV2Report { records }
+            }
+            ROUTER_SOLICITATION => Icmpv6Message::RouterSolicitation {
+                options: NdpOptions::from_bytes(data)?,
+            },
+            ROUTER_ADVERTISEMENT => Icmpv6Message::RouterAdvertisement {
+                current_hop_limit: bytes[4],
+                managed: bytes[5] & 0x80 != 0,
+                other_config: bytes[5] & 0x40 != 0,
+                reserved: bytes[5] & 0x3F,
+                router_lifetime: sequence_number,
+                reachable_time: u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+                retrans_timer: u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
+                options: NdpOptions::from_bytes(&bytes[16..])?,
+            },
+            NEIGHBOR_SOLICITATION => Icmpv6Message::NeighborSolicitation {
+                target: read_address(&bytes[8..24]),
+                options: NdpOptions::from_bytes(&bytes[24..])?,
+            },
+            NEIGHBOR_ADVERTISEMENT => Icmpv6Message::NeighborAdvertisement {
+                router: bytes[4] & 0x80 != 0,
+                solicited: bytes[4] & 0x40 != 0,
+                override_flag: bytes[4] & 0x20 != 0,
+                target: read_address(&bytes[8..24]),
+                options: NdpOptions::from_bytes(&bytes[24..])?,
+            },
+            REDIRECT => Icmpv6Message::Redirect {
+                target: read_address(&bytes[8..24]),
+                destination: read_address(&bytes[24..40]),
+                options: NdpOptions::from_bytes(&bytes[40..])?,
+            },
+            _ => Icmpv6Message::Unknown {
+                icmp_type,
+                code,
+                rest_of_header: [bytes[4], bytes[5], bytes[6], bytes[7]],
+                data,
+            },
+        };
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::mac_addr::MacAddr;
+    use crate::network::icmpv6::mld::{CHANGE_TO_EXCLUDE_MODE, MODE_IS_INCLUDE};
+    use crate::network::icmpv6::ndp::NdpOption;
+    use alloc::vec;
+
+    fn roundtrip(message: Icmpv6Message) {
+        let mut buf = vec![0; message.serialized_len()];
+        message.write_to(&mut buf).unwrap();
+
+        let mut expect = Checksum::new();
+        expect.add_bytes(&buf);
+        let mut actual = Checksum::new();
+        message.add_to_checksum(&mut actual);
+        assert_eq!(expect, actual);
+
+        assert_eq!(message, Icmpv6Message::from_bytes(&buf).unwrap());
+    }
+
+    #[test]
+    fn test_icmpv6_message_roundtrip() {
+        roundtrip(Icmpv6Message::PacketTooBig {
+            mtu: 1280,
+            original: &[0x60, 0, 0, 0],
+        });
+        roundtrip(Icmpv6Message::ParameterProblem {
+            code: 1,
+            pointer: 40,
+            original: &[0x60],
+        });
+        roundtrip(Icmpv6Message::MldQuery {
+            maximum_response_delay: 10000,
+            multicast_address: [0; 16],
+        });
+        roundtrip(Icmpv6Message::MldDone {
+            multicast_address: [0xFF; 16],
+        });
+        roundtrip(Icmpv6Message::Redirect {
+            target: [0xFE; 16],
+            destination: [0x20; 16],
+            options: NdpOptions::new(),
+        });
+        roundtrip(Icmpv6Message::Unknown {
+            icmp_type: 200,
+            code: 1,
+            rest_of_header: [1, 2, 3, 4],
+            data: &[5],
+        });
+    }
+
+    #[test]
+    fn test_icmpv6_router_advertisement() {
+        let mut options = NdpOptions::new();
+        options.add(NdpOption::SourceLinkLayerAddress(MacAddr::new(
+            0x02, 0, 0, 0, 0, 1,
+        )));
+        options.add(NdpOption::Mtu(1500));
+        let message = Icmpv6Message::RouterAdvertisement {
+            current_hop_limit: 64,
+            managed: true,
+            other_config: false,
+            reserved: 0,
+            router_lifetime: 1800,
+            reachable_time: 0,
+            retrans_timer: 0,
+            options,
+        };
+
+        let mut buf = [0u8; 32];
+        assert_eq!(32, message.write_to(&mut buf).unwrap());
+        assert_eq!([134, 0, 0, 0, 64, 0x80, 0x07, 0x08], buf[..8]);
+        assert_eq!([1, 1], buf[16..18]);
+
+        roundtrip(message);
+    }
+
+    #[test]
+    fn test_icmpv6_neighbor_advertisement_flags() {
+        let message = Icmpv6Message::NeighborAdvertisement {
+            router: false,
+            solicited: true,
+            override_flag: true,
+            target: [0xFE; 16],
+            options: NdpOptions::new(),
+        };
+
+        let mut buf = [0u8; 24];
+        message.write_to(&mut buf).unwrap();
+        assert_eq!([136, 0, 0, 0, 0x60, 0, 0, 0], buf[..8]);
+
+        roundtrip(message);
+    }
+
+    #[test]
+    fn test_icmpv6_mldv2_query_and_report() {
+        let mut query = MldV2Query::new();
+        query.multicast_address = [0xFF; 16];
+        query.suppress_router_processing = true;
+        query.sources = vec![[0x20; 16], [0x30; 16]];
+
+        let message = Icmpv6Message::MldV2Query(query);
+        assert_eq!(60, message.serialized_len());
+        roundtrip(message);
+
+        let mut record = MulticastAddressRecord::new(CHANGE_TO_EXCLUDE_MODE, [0xFF; 16]);
+        record.sources.push([0x20; 16]);
+        let message = Icmpv6Message::MldV2Report {
+            records: vec![record, MulticastAddressRecord::new(1, [0xFE; 16])],
+        };
+
+        let mut buf = [0u8; 64];
+        assert_eq!(64, message.write_to(&mut buf).unwrap());
+        assert_eq!([143, 0, 0, 0, 0, 0, 0, 2], buf[..8]);
+        roundtrip(message);
+    }
+
+    #[test]
+    fn test_icmpv6_mldv2_too_many_entries() {
+        let mut query = MldV2Query::new();
+        query.sources = vec![[0x20; 16]; 65536];
+        let message = Icmpv6Message::MldV2Query(query);
+        let mut buf = vec![0; message.serialized_len()];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Icmpv6,
+            length: 65536 * 16,
+        });
+        assert_eq!(expect, message.write_to(&mut buf));
+
+        let message = Icmpv6Message::MldV2Report {
+            records: vec![MulticastAddressRecord::new(MODE_IS_INCLUDE, [0xFF; 16]); 65536],
+        };
+        let mut buf = vec![0; message.serialized_len()];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Icmpv6,
+            length: 65536 * 20,
+        });
+        assert_eq!(expect, message.write_to(&mut buf));
+    }
+
+    #[test]
+    fn test_icmpv6_mld_query_bad_length() {
+        let bytes = [130u8; 26];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed: 28,
+            got: 26,
+        });
+        assert_eq!(expect, Icmpv6Message::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn test_icmpv6_neighbor_solicitation_truncated() {
+        let bytes = [135, 0, 0, 0, 0, 0, 0, 0];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed: 24,
+            got: 8,
+        });
+        assert_eq!(expect, Icmpv6Message::from_bytes(&bytes));
+    }
+}
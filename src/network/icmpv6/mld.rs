@@ -0,0 +1,320 @@
+use alloc::vec::Vec;
+
+use super::read_address;
+use crate::checksum::Checksum;
+use crate::error::{Error, Layer, ensure_capacity};
+
+// Multicast Address Record types
+/// Current state is INCLUDE the listed sources (RFC 3810 Section 5.2.12)
+pub const MODE_IS_INCLUDE: u8 = 1;
+/// Current state is EXCLUDE the listed sources (RFC 3810 Section 5.2.12)
+pub const MODE_IS_EXCLUDE: u8 = 2;
+/// Filter mode changed to INCLUDE (RFC 3810 Section 5.2.12)
+pub const CHANGE_TO_INCLUDE_MODE: u8 = 3;
+/// Filter mode changed to EXCLUDE (RFC 3810 Section 5.2.12)
+pub const CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+/// Listed sources added to the filter (RFC 3810 Section 5.2.12)
+pub const ALLOW_NEW_SOURCES: u8 = 5;
+/// Listed sources removed from the filter (RFC 3810 Section 5.2.12)
+pub const BLOCK_OLD_SOURCES: u8 = 6;
+
+/// Length of the fixed part of a Multicast Address Record in bytes
+const RECORD_FIXED_LEN: usize = 20;
+
+/// MLDv2 Multicast Listener Query fields following the multicast address
+///
+/// Reference: RFC 3810 Section 5.1
+#[derive(Debug, PartialEq, Clone)]
+pub struct MldV2Query {
+    /// Maximum Response Code; see [`MldV2Query::maximum_response_delay`]
+    pub maximum_response_code: u16,
+    pub multicast_address: [u8; 16],
+    /// S flag: suppress router-side processing
+    pub suppress_router_processing: bool,
+    /// Querier's Robustness Variable (3 bits)
+    pub robustness: u8,
+    /// Querier's Query Interval Code; see [`MldV2Query::query_interval`]
+    pub query_interval_code: u8,
+    pub sources: Vec<[u8; 16]>,
+}
+
+impl MldV2Query {
+    /// General query with default robustness (2) and query interval (125 s)
+    ///
+    /// Reference: RFC 3810 Section 9
+    pub fn new() -> Self {
+        MldV2Query {
+            maximum_response_code: 10000,
+            multicast_address: [0; 16],
+            suppress_router_processing: false,
+            robustness: 2,
+            query_interval_code: 125,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Maximum Response Delay in milliseconds, decoded from the Maximum Response Code
+    ///
+    /// Reference: RFC 3810 Section 5.1.3
+    pub fn maximum_response_delay(&self) -> u32 {
+        let code = self.maximum_response_code as u32;
+        if code < 0x8000 {
+            return code;
+        }
+        let mantissa = code & 0x0FFF;
+        let exponent = (code >> 12) & 0x07;
+        (mantissa | 0x1000) << (exponent + 3)
+    }
+
+    /// Querier's Query Interval in seconds, decoded from the Query Interval Code
+    ///
+    /// Reference: RFC 3810 Section 5.1.9
+    pub fn query_interval(&self) -> u32 {
+        let code = self.query_interval_code as u32;
+        if code < 0x80 {
+            return code;
+        }
+        let mantissa = code & 0x0F;
+        let exponent = (code >> 4) & 0x07;
+        (mantissa | 0x10) << (exponent + 3)
+    }
+}
+
+impl Default for MldV2Query {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Multicast Address Record of an MLDv2 report
+///
+/// Reference: RFC 3810 Section 5.2.4
+#[derive(Debug, PartialEq, Clone)]
+pub struct MulticastAddressRecord {
+    pub record_type: u8,
+    pub multicast_address: [u8; 16],
+    pub sources: Vec<[u8; 16]>,
+    /// Auxiliary data, zero padded to a multiple of 4 bytes
+    pub auxiliary_data: Vec<u8>,
+}
+
+impl MulticastAddressRecord {
+    pub fn new(record_type: u8, multicast_address: [u8; 16]) -> Self {
+        MulticastAddressRecord {
+            record_type,
+            multicast_address,
+            sources: Vec::new(),
+            auxiliary_data: Vec::new(),
+        }
+    }
+
+    /// Length of the padded auxiliary data in bytes
+    fn auxiliary_data_len(&self) -> usize {
+        self.auxiliary_data.len().div_ceil(4) * 4
+    }
+
+    /// Length of the serialized record in bytes
+    pub fn serialized_len(&self) -> usize {
+        RECORD_FIXED_LEN + self.sources.len() * 16 + self.auxiliary_data_len()
+    }
+
+    /// Fixed part of the record: type, aux data length, source count and address
+    ///
+    /// Lengths too large for their fields are truncated here; `write_to`
+    /// rejects them before using these bytes.
+    fn fixed_bytes(&self) -> [u8; RECORD_FIXED_LEN] {
+        let mut bytes = [0u8; RECORD_FIXED_LEN];
+        bytes[0] = self.record_type;
+        bytes[1] = (self.auxiliary_data_len() / 4) as u8;
+        bytes[2..4].copy_from_slice(&(self.sources.len() as u16).to_be_bytes());
+        bytes[4..20].copy_from_slice(&self.multicast_address);
+        bytes
+    }
+
+    /// Serialize the record into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the auxiliary data is longer
+    /// than the 255 32-bit words its Aux Data Len field can express, or with
+    /// [`Error::BadTotalLength`] if there are more than 65535 sources.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        check_count(self.sources.len(), self.sources.len() * 16)?;
+        let auxiliary_data_len = self.auxiliary_data_len();
+        if auxiliary_data_len / 4 > u8::MAX as usize {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Icmpv6,
+                kind: self.record_type,
+                length: auxiliary_data_len,
+            });
+        }
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        buf[..RECORD_FIXED_LEN].copy_from_slice(&self.fixed_bytes());
+        let mut offset = RECORD_FIXED_LEN;
+        for source in &self.sources {
+            buf[offset..offset + 16].copy_from_slice(source);
+            offset += 16;
+        }
+        buf[offset..offset + self.auxiliary_data.len()].copy_from_slice(&self.auxiliary_data);
+        buf[offset + self.auxiliary_data.len()..total_len].fill(0);
+
+        Ok(total_len)
+    }
+
+    /// Add the serialized record to a checksum without allocating
+    pub(crate) fn add_to_checksum(&self, checksum: &mut Checksum) {
+        checksum.add_bytes(&self.fixed_bytes());
+        for source in &self.sources {
+            checksum.add_bytes(source);
+        }
+        checksum.add_bytes(&self.auxiliary_data);
+        checksum.add_bytes(&[0; 3][..self.auxiliary_data_len() - self.auxiliary_data.len()]);
+    }
+
+    /// Parse a record from bytes
+    ///
+    /// Returns (record, bytes_consumed).
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        if bytes.len() < RECORD_FIXED_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Icmpv6,
+                needed: RECORD_FIXED_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let auxiliary_data_len = bytes[1] as usize * 4;
+        let source_count = u16::from_be_bytes([bytes[2], bytes[3]]) as usize;
+        let sources_end = RECORD_FIXED_LEN + source_count * 16;
+        let total_len = sources_end + auxiliary_data_len;
+        if bytes.len() < total_len {
+            return Err(Error::Truncated {
+                layer: Layer::Icmpv6,
+                needed: total_len,
+                got: bytes.len(),
+            });
+        }
+
+        let record = MulticastAddressRecord {
+            record_type: bytes[0],
+            multicast_address: read_address(&bytes[4..20]),
+            sources: bytes[RECORD_FIXED_LEN..sources_end]
+                .chunks_exact(16)
+                .map(read_address)
+                .collect(),
+            auxiliary_data: bytes[sources_end..total_len].to_vec(),
+        };
+        Ok((record, total_len))
+    }
+}
+
+/// Check that `count` entries fit a 16-bit Number of Sources or Records field
+///
+/// `length` is the serialized length of the entries, reported on failure.
+pub(crate) fn check_count(count: usize, length: usize) -> Result<(), Error> {
+    if count > u16::MAX as usize {
+        return Err(Error::BadTotalLength {
+            layer: Layer::Icmpv6,
+            length,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_mldv2_query_maximum_response_delay() {
+        let mut query = MldV2Query::new();
+        assert_eq!(10000, query.maximum_response_delay());
+
+        // Exponent 0, mantissa 0: 0x1000 << 3
+        query.maximum_response_code = 0x8000;
+        assert_eq!(32768, query.maximum_response_delay());
+
+        query.maximum_response_code = 0xFFFF;
+        assert_eq!(0x1FFF << 10, query.maximum_response_delay());
+    }
+
+    #[test]
+    fn test_mldv2_query_query_interval() {
+        let mut query = MldV2Query::new();
+        assert_eq!(125, query.query_interval());
+
+        query.query_interval_code = 0x80;
+        assert_eq!(128, query.query_interval());
+
+        query.query_interval_code = 0xFF;
+        assert_eq!(0x1F << 10, query.query_interval());
+    }
+
+    #[test]
+    fn test_multicast_address_record_roundtrip() {
+        let mut record = MulticastAddressRecord::new(MODE_IS_EXCLUDE, [0xFF; 16]);
+        record.sources.push([0x20; 16]);
+        record.auxiliary_data = vec![1, 2];
+
+        let mut buf = vec![0; record.serialized_len()];
+        record.write_to(&mut buf).unwrap();
+
+        assert_eq!(40, buf.len());
+        assert_eq!([2, 1, 0, 1], buf[..4]);
+        assert_eq!([1, 2, 0, 0], buf[36..]);
+
+        let mut checksum = Checksum::new();
+        record.add_to_checksum(&mut checksum);
+        let mut expect = Checksum::new();
+        expect.add_bytes(&buf);
+        assert_eq!(expect, checksum);
+
+        // Auxiliary data is read back with its padding
+        record.auxiliary_data = vec![1, 2, 0, 0];
+        let actual = MulticastAddressRecord::from_bytes(&buf).unwrap();
+        assert_eq!((record, 40), actual);
+    }
+
+    #[test]
+    fn test_multicast_address_record_truncated() {
+        let mut bytes = [0u8; 20];
+        bytes[3] = 2; // Two sources announced, none present
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed: 52,
+            got: 20,
+        });
+        let actual = MulticastAddressRecord::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_multicast_address_record_write_oversized() {
+        let mut record = MulticastAddressRecord::new(ALLOW_NEW_SOURCES, [0xFF; 16]);
+        record.auxiliary_data = vec![0; 1024];
+        let mut buf = vec![0; record.serialized_len()];
+
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Icmpv6,
+            kind: ALLOW_NEW_SOURCES,
+            length: 1024,
+        });
+        let actual = record.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_multicast_address_record_too_many_sources() {
+        let mut record = MulticastAddressRecord::new(MODE_IS_INCLUDE, [0xFF; 16]);
+        record.sources = vec![[0x20; 16]; 65536];
+        let mut buf = vec![0; record.serialized_len()];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Icmpv6,
+            length: 65536 * 16,
+        });
+        let actual = record.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+}
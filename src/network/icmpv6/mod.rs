@@ -0,0 +1,217 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod message;
+pub mod mld;
+pub mod ndp;
+
+use crate::checksum::{Checksum, ChecksumVerification};
+use crate::error::{Error, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use message::Icmpv6Message;
+
+/// ICMPv6 message with its checksum
+///
+/// The checksum covers the IPv6 pseudo-header, so unlike ICMPv4 the source and
+/// destination addresses are needed to compute or verify it.
+///
+/// Reference: RFC 4443 Section 2.3
+#[derive(Debug, PartialEq)]
+pub struct Icmpv6Packet<'a> {
+    pub message: Icmpv6Message<'a>,
+    pub checksum: u16,
+}
+
+impl<'a> Icmpv6Packet<'a> {
+    pub fn new(message: Icmpv6Message<'a>) -> Self {
+        Icmpv6Packet {
+            message,
+            checksum: 0,
+        }
+    }
+
+    /// Parse an ICMPv6 message, borrowing its data from `bytes`
+    ///
+    /// All bytes after the fixed part of the message are taken as data or
+    /// options, so `bytes` should be delimited by the enclosing IPv6 packet.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let message = Icmpv6Message::from_bytes(bytes)?;
+        let checksum = u16::from_be_bytes([bytes[2], bytes[3]]);
+        Ok(Icmpv6Packet { message, checksum })
+    }
+
+    /// Calculate the ICMPv6 checksum including the IPv6 pseudo-header
+    ///
+    /// With a routing header, `dst_ip` is the final destination; see
+    /// [`Ipv6Packet::final_destination`](crate::network::ipv6::Ipv6Packet::final_destination).
+    pub fn calculate_checksum(&self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> u16 {
+        let mut checksum = Checksum::new();
+        checksum.add_ipv6_pseudo_header(
+            src_ip,
+            dst_ip,
+            IpProtocol::Icmpv6,
+            self.serialized_len() as u32,
+        );
+        self.message.add_to_checksum(&mut checksum);
+        checksum.finish()
+    }
+
+    /// Verify the stored checksum using the IPv6 pseudo-header
+    pub fn verify_checksum(&self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> ChecksumVerification {
+        ChecksumVerification {
+            stored: self.checksum,
+            expected: self.calculate_checksum(src_ip, dst_ip),
+        }
+    }
+
+    /// Length of the serialized message in bytes
+    pub fn serialized_len(&self) -> usize {
+        self.message.serialized_len()
+    }
+
+    /// Serialize the message into `buf`, returning the number of bytes written
+    ///
+    /// The checksum written to `buf` is calculated from the message and IPv6
+    /// pseudo-header; unlike [`Icmpv6Packet::to_bytes`], `self.checksum` is
+    /// left unchanged.
+    pub fn write_to(
+        &self,
+        buf: &mut [u8],
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+    ) -> Result<usize, Error> {
+        let len = self.serialized_len();
+        ensure_capacity(buf, len)?;
+        self.message.write_to(buf)?;
+
+        let mut checksum = Checksum::new();
+        checksum.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Icmpv6, len as u32);
+        checksum.add_bytes(&buf[..len]);
+        buf[2..4].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        Ok(len)
+    }
+
+    /// Update the checksum, then serialize the message
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&mut self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> Result<Vec<u8>, Error> {
+        self.checksum = self.calculate_checksum(src_ip, dst_ip);
+
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes, src_ip, dst_ip)?;
+        Ok(bytes)
+    }
+}
+
+/// Copy a 16-byte IPv6 address out of `bytes`
+fn read_address(bytes: &[u8]) -> [u8; 16] {
+    let mut address = [0u8; 16];
+    address.copy_from_slice(&bytes[..16]);
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::message::*;
+    #[cfg(feature = "alloc")]
+    use super::ndp::{NdpOption, NdpOptions};
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::address::mac_addr::MacAddr;
+    use crate::error::Layer;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    const SRC: [u8; 16] = [0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    const DST: [u8; 16] = [0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv6_echo_request_to_bytes() {
+        let mut packet = Icmpv6Packet::new(Icmpv6Message::EchoRequest {
+            identifier: 0x1234,
+            sequence_number: 1,
+            data: b"ab",
+        });
+
+        let expect = vec![
+            0x80, 0x00, // Type, Code
+            0x0F, 0x1F, // Checksum
+            0x12, 0x34, // Identifier
+            0x00, 0x01, // Sequence Number
+            b'a', b'b', // Data
+        ];
+        let actual = packet.to_bytes(SRC, DST).unwrap();
+
+        assert_eq!(expect, actual);
+        assert_eq!(0x0F1F, packet.checksum);
+        assert!(packet.verify_checksum(SRC, DST).is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv6_neighbor_solicitation_roundtrip() {
+        let mut options = NdpOptions::new();
+        options.add(NdpOption::SourceLinkLayerAddress(MacAddr::new(
+            0x02, 0, 0, 0, 0, 1,
+        )));
+        let mut expect = Icmpv6Packet::new(Icmpv6Message::NeighborSolicitation {
+            target: DST,
+            options,
+        });
+
+        let bytes = expect.to_bytes(SRC, DST).unwrap();
+        assert_eq!(32, bytes.len());
+
+        let actual = Icmpv6Packet::from_bytes(&bytes).unwrap();
+        assert!(actual.verify_checksum(SRC, DST).is_valid());
+        assert!(!actual.verify_checksum(SRC, [0xFF; 16]).is_valid());
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_icmpv6_write_to_matches_calculate_checksum() {
+        let packet = Icmpv6Packet::new(Icmpv6Message::PacketTooBig {
+            mtu: 1280,
+            original: &[0x60, 0x00, 0x00, 0x00, 0x00],
+        });
+
+        let mut buf = [0u8; 13];
+        assert_eq!(13, packet.write_to(&mut buf, SRC, DST).unwrap());
+
+        let expect = packet.calculate_checksum(SRC, DST);
+        let actual = u16::from_be_bytes([buf[2], buf[3]]);
+        assert_eq!(expect, actual);
+        assert_eq!(0, packet.checksum);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_icmpv6_to_bytes_oversized_option() {
+        let mut options = NdpOptions::new();
+        options.add(NdpOption::Unknown {
+            option_type: 99,
+            data: vec![0; 2040],
+        });
+        let mut packet = Icmpv6Packet::new(Icmpv6Message::RouterSolicitation { options });
+
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Icmpv6,
+            kind: 99,
+            length: 2048,
+        });
+        let actual = packet.to_bytes(SRC, DST);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_icmpv6_from_bytes_truncated() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed: 8,
+            got: 2,
+        });
+        let actual = Icmpv6Packet::from_bytes(&[128, 0]);
+        assert_eq!(expect, actual);
+    }
+}
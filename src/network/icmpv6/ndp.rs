@@ -0,0 +1,495 @@
+use alloc::vec::Vec;
+
+use super::read_address;
+use crate::address::mac_addr::MacAddr;
+use crate::checksum::Checksum;
+use crate::error::{Error, Layer, ensure_capacity};
+
+// Neighbor Discovery option types
+/// Source Link-layer Address (RFC 4861 Section 4.6.1)
+pub const SOURCE_LINK_LAYER_ADDRESS: u8 = 1;
+/// Target Link-layer Address (RFC 4861 Section 4.6.1)
+pub const TARGET_LINK_LAYER_ADDRESS: u8 = 2;
+/// Prefix Information (RFC 4861 Section 4.6.2)
+pub const PREFIX_INFORMATION: u8 = 3;
+/// MTU (RFC 4861 Section 4.6.4)
+pub const MTU: u8 = 5;
+/// Recursive DNS Server (RFC 8106 Section 5.1)
+pub const RECURSIVE_DNS_SERVER: u8 = 25;
+
+/// Length of a Prefix Information option in bytes
+const PREFIX_INFORMATION_LEN: usize = 32;
+
+/// Length of the fixed part of a Recursive DNS Server option in bytes
+const RECURSIVE_DNS_SERVER_FIXED_LEN: usize = 8;
+
+/// Prefix Information option
+///
+/// Reference: RFC 4861 Section 4.6.2
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrefixInformation {
+    pub prefix_length: u8,
+    /// L flag: the prefix can be used for on-link determination
+    pub on_link: bool,
+    /// A flag: the prefix can be used for stateless address autoconfiguration
+    pub autonomous: bool,
+    pub valid_lifetime: u32,
+    pub preferred_lifetime: u32,
+    pub prefix: [u8; 16],
+}
+
+/// Neighbor Discovery option
+///
+/// Reference: RFC 4861 Section 4.6
+#[derive(Debug, PartialEq, Clone)]
+pub enum NdpOption {
+    /// Source Link-Layer Address (Type 1) for Ethernet
+    SourceLinkLayerAddress(MacAddr),
+    /// Target Link-Layer Address (Type 2) for Ethernet
+    TargetLinkLayerAddress(MacAddr),
+    /// Prefix Information (Type 3)
+    PrefixInformation(PrefixInformation),
+    /// MTU (Type 5)
+    Mtu(u32),
+    /// Recursive DNS Server (Type 25), RFC 8106 Section 5.1
+    RecursiveDnsServer {
+        lifetime: u32,
+        servers: Vec<[u8; 16]>,
+    },
+    /// Any other option, with its data preserved as-is (zero padded to 8 octets)
+    Unknown { option_type: u8, data: Vec<u8> },
+}
+
+impl NdpOption {
+    /// Get the option type field
+    pub fn option_type(&self) -> u8 {
+        match self {
+            NdpOption::SourceLinkLayerAddress(_) => SOURCE_LINK_LAYER_ADDRESS,
+            NdpOption::TargetLinkLayerAddress(_) => TARGET_LINK_LAYER_ADDRESS,
+            NdpOption::PrefixInformation(_) => PREFIX_INFORMATION,
+            NdpOption::Mtu(_) => MTU,
+            NdpOption::RecursiveDnsServer { .. } => RECURSIVE_DNS_SERVER,
+            NdpOption::Unknown { option_type, .. } => *option_type,
+        }
+    }
+
+    /// Length of the serialized option in bytes (a multiple of 8)
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            NdpOption::SourceLinkLayerAddress(_)
+            | NdpOption::TargetLinkLayerAddress(_)
+            | NdpOption::Mtu(_) => 8,
+            NdpOption::PrefixInformation(_) => PREFIX_INFORMATION_LEN,
+            NdpOption::RecursiveDnsServer { servers, .. } => {
+                RECURSIVE_DNS_SERVER_FIXED_LEN + servers.len() * 16
+            }
+            NdpOption::Unknown { data, .. } => (2 + data.len()).div_ceil(8) * 8,
+        }
+    }
+
+    /// Length of the fixed part written by `write_fixed` in bytes
+    fn fixed_len(&self) -> usize {
+        match self {
+            NdpOption::RecursiveDnsServer { .. } => RECURSIVE_DNS_SERVER_FIXED_LEN,
+            NdpOption::Unknown { .. } => 2,
+            _ => self.serialized_len(),
+        }
+    }
+
+    /// Write the type, length and all fields preceding any variable-length data
+    ///
+    /// A length too large for its field is truncated here; `write_to` rejects
+    /// it before using these bytes.
+    fn write_fixed(&self, buf: &mut [u8]) {
+        // Byte 0: Type, Byte 1: Length in units of 8 octets
+        buf[0] = self.option_type();
+        buf[1] = (self.serialized_len() / 8) as u8;
+
+        match self {
+            NdpOption::SourceLinkLayerAddress(address)
+            | NdpOption::TargetLinkLayerAddress(address) => {
+                buf[2..8].copy_from_slice(&address.octets());
+            }
+            NdpOption::PrefixInformation(info) => {
+                buf[2] = info.prefix_length;
+                buf[3] = ((info.on_link as u8) << 7) | ((info.autonomous as u8) << 6);
+                buf[4..8].copy_from_slice(&info.valid_lifetime.to_be_bytes());
+                buf[8..12].copy_from_slice(&info.preferred_lifetime.to_be_bytes());
+                buf[12..16].fill(0); // Reserved2
+                buf[16..32].copy_from_slice(&info.prefix);
+            }
+            NdpOption::Mtu(mtu) => {
+                buf[2..4].fill(0); // Reserved
+                buf[4..8].copy_from_slice(&mtu.to_be_bytes());
+            }
+            NdpOption::RecursiveDnsServer { lifetime, .. } => {
+                buf[2..4].fill(0); // Reserved
+                buf[4..8].copy_from_slice(&lifetime.to_be_bytes());
+            }
+            NdpOption::Unknown { .. } => {}
+        }
+    }
+
+    /// Serialize the option into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the option is longer than the
+    /// 255 units of 8 octets its Length field can express.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        if total_len / 8 > u8::MAX as usize {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Icmpv6,
+                kind: self.option_type(),
+                length: total_len,
+            });
+        }
+        ensure_capacity(buf, total_len)?;
+
+        let fixed_len = self.fixed_len();
+        self.write_fixed(buf);
+
+        match self {
+            NdpOption::RecursiveDnsServer { servers, .. } => {
+                for (chunk, server) in buf[fixed_len..total_len].chunks_exact_mut(16).zip(servers) {
+                    chunk.copy_from_slice(server);
+                }
+            }
+            NdpOption::Unknown { data, .. } => {
+                buf[fixed_len..fixed_len + data.len()].copy_from_slice(data);
+                buf[fixed_len + data.len()..total_len].fill(0);
+            }
+            _ => {}
+        }
+
+        Ok(total_len)
+    }
+
+    /// Add the serialized option to a checksum without allocating
+    fn add_to_checksum(&self, checksum: &mut Checksum) {
+        let mut fixed = [0u8; PREFIX_INFORMATION_LEN];
+        let fixed_len = self.fixed_len();
+        self.write_fixed(&mut fixed);
+        checksum.add_bytes(&fixed[..fixed_len]);
+
+        match self {
+            NdpOption::RecursiveDnsServer { servers, .. } => {
+                for server in servers {
+                    checksum.add_bytes(server);
+                }
+            }
+            NdpOption::Unknown { data, .. } => {
+                checksum.add_bytes(data);
+                // Zero padding does not change the sum, but keeps the alignment
+                checksum.add_bytes(&[0; 8][..self.serialized_len() - fixed_len - data.len()]);
+            }
+            _ => {}
+        }
+    }
+
+    /// Parse an option from bytes
+    ///
+    /// Returns (option, bytes_consumed). An option with a length of zero is
+    /// rejected, as RFC 4861 Section 4.6 requires.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        if bytes.len() < 2 {
+            return Err(Error::Truncated {
+                layer: Layer::Icmpv6,
+                needed: 2,
+                got: bytes.len(),
+            });
+        }
+
+        let option_type = bytes[0];
+        let len = bytes[1] as usize * 8;
+        if len == 0 {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Icmpv6,
+                kind: option_type,
+                length: 0,
+            });
+        }
+        if bytes.len() < len {
+            return Err(Error::Truncated {
+                layer: Layer::Icmpv6,
+                needed: len,
+                got: bytes.len(),
+            });
+        }
+        let bytes = &bytes[..len];
+
+        let bad_length = Error::BadOptionLength {
+            layer: Layer::Icmpv6,
+            kind: option_type,
+            length: len,
+        };
+
+        let option = match option_type {
+            SOURCE_LINK_LAYER_ADDRESS | TARGET_LINK_LAYER_ADDRESS if len == 8 => {
+                let address =
+                    MacAddr::new(bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]);
+                if option_type == SOURCE_LINK_LAYER_ADDRESS {
+                    NdpOption::SourceLinkLayerAddress(address)
+                } else {
+                    NdpOption::TargetLinkLayerAddress(address)
+                }
+            }
+            PREFIX_INFORMATION => {
+                if len != PREFIX_INFORMATION_LEN {
+                    return Err(bad_length);
+                }
+                NdpOption::PrefixInformation(PrefixInformation {
+                    prefix_length: bytes[2],
+                    on_link: bytes[3] & 0x80 != 0,
+                    autonomous: bytes[3] & 0x40 != 0,
+                    valid_lifetime: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+                    preferred_lifetime: u32::from_be_bytes([
+                        bytes[8], bytes[9], bytes[10], bytes[11],
+                    ]),
+                    prefix: read_address(&bytes[16..32]),
+                })
+            }
+            MTU => {
+                if len != 8 {
+                    return Err(bad_length);
+                }
+                NdpOption::Mtu(u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]))
+            }
+            RECURSIVE_DNS_SERVER => {
+                // At least one address, and whole addresses only (Length = 1 + 2n)
+                if len < RECURSIVE_DNS_SERVER_FIXED_LEN + 16 || len % 16 != 8 {
+                    return Err(bad_length);
+                }
+                NdpOption::RecursiveDnsServer {
+                    lifetime: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+                    servers: bytes[RECURSIVE_DNS_SERVER_FIXED_LEN..]
+                        .chunks_exact(16)
+                        .map(read_address)
+                        .collect(),
+                }
+            }
+            // Link-layer addresses of other lengths are not Ethernet; keep them as-is
+            _ => NdpOption::Unknown {
+                option_type,
+                data: bytes[2..].to_vec(),
+            },
+        };
+        Ok((option, len))
+    }
+}
+
+/// Neighbor Discovery option list
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NdpOptions {
+    pub options: Vec<NdpOption>,
+}
+
+impl NdpOptions {
+    pub fn new() -> Self {
+        NdpOptions {
+            options: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, option: NdpOption) {
+        self.options.push(option);
+    }
+
+    /// Link-layer address from the first Source Link-Layer Address option
+    pub fn source_link_layer_address(&self) -> Option<&MacAddr> {
+        self.options.iter().find_map(|option| match option {
+            NdpOption::SourceLinkLayerAddress(address) => Some(address),
+            _ => None,
+        })
+    }
+
+    /// Link-layer address from the first Target Link-Layer Address option
+    pub fn target_link_layer_address(&self) -> Option<&MacAddr> {
+        self.options.iter().find_map(|option| match option {
+            NdpOption::TargetLinkLayerAddress(address) => Some(address),
+            _ => None,
+        })
+    }
+
+    /// Length of the serialized options in bytes
+    pub fn serialized_len(&self) -> usize {
+        self.options.iter().map(NdpOption::serialized_len).sum()
+    }
+
+    /// Serialize all options into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        let mut offset = 0;
+        for option in &self.options {
+            offset += option.write_to(&mut buf[offset..])?;
+        }
+        Ok(offset)
+    }
+
+    /// Add the serialized options to a checksum without allocating
+    pub(crate) fn add_to_checksum(&self, checksum: &mut Checksum) {
+        for option in &self.options {
+            option.add_to_checksum(checksum);
+        }
+    }
+
+    /// Parse all options filling `bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut options = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let (option, consumed) = NdpOption::from_bytes(&bytes[offset..])?;
+            options.push(option);
+            offset += consumed;
+        }
+        Ok(NdpOptions { options })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn write(option: &NdpOption) -> Vec<u8> {
+        let mut buf = vec![0; option.serialized_len()];
+        option.write_to(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_ndp_option_source_link_layer_address() {
+        let option = NdpOption::SourceLinkLayerAddress(MacAddr::new(0x02, 0, 0, 0, 0, 1));
+        let expect = vec![0x01, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+        assert_eq!(expect, write(&option));
+
+        let actual = NdpOption::from_bytes(&expect).unwrap();
+        assert_eq!((option, 8), actual);
+    }
+
+    #[test]
+    fn test_ndp_option_prefix_information() {
+        let mut prefix = [0u8; 16];
+        prefix[..8].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0]);
+        let option = NdpOption::PrefixInformation(PrefixInformation {
+            prefix_length: 64,
+            on_link: true,
+            autonomous: true,
+            valid_lifetime: 2592000,
+            preferred_lifetime: 604800,
+            prefix,
+        });
+
+        let bytes = write(&option);
+        assert_eq!(32, bytes.len());
+        assert_eq!([0x03, 0x04, 64, 0xC0, 0x00, 0x27, 0x8D, 0x00], bytes[..8]);
+
+        let actual = NdpOption::from_bytes(&bytes).unwrap();
+        assert_eq!((option, 32), actual);
+    }
+
+    #[test]
+    fn test_ndp_option_mtu() {
+        let option = NdpOption::Mtu(1500);
+        let expect = vec![0x05, 0x01, 0x00, 0x00, 0x00, 0x00, 0x05, 0xDC];
+        assert_eq!(expect, write(&option));
+    }
+
+    #[test]
+    fn test_ndp_option_recursive_dns_server() {
+        let option = NdpOption::RecursiveDnsServer {
+            lifetime: 300,
+            servers: vec![[0x20; 16], [0x30; 16]],
+        };
+
+        let bytes = write(&option);
+        assert_eq!(40, bytes.len());
+        assert_eq!([25, 5, 0, 0, 0, 0, 0x01, 0x2C], bytes[..8]);
+
+        let actual = NdpOption::from_bytes(&bytes).unwrap();
+        assert_eq!((option, 40), actual);
+    }
+
+    #[test]
+    fn test_ndp_option_unknown_padded() {
+        let option = NdpOption::Unknown {
+            option_type: 200,
+            data: vec![1, 2, 3],
+        };
+        let expect = vec![200, 1, 1, 2, 3, 0, 0, 0];
+        assert_eq!(expect, write(&option));
+    }
+
+    #[test]
+    fn test_ndp_option_zero_length() {
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Icmpv6,
+            kind: 1,
+            length: 0,
+        });
+        let actual = NdpOption::from_bytes(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ndp_option_truncated() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Icmpv6,
+            needed: 32,
+            got: 8,
+        });
+        let actual = NdpOption::from_bytes(&[3, 4, 64, 0, 0, 0, 0, 0]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ndp_options_add_to_checksum() {
+        let mut options = NdpOptions::new();
+        options.add(NdpOption::SourceLinkLayerAddress(MacAddr::new(
+            2, 0, 0, 0, 0, 1,
+        )));
+        options.add(NdpOption::RecursiveDnsServer {
+            lifetime: 60,
+            servers: vec![[0xAB; 16]],
+        });
+        options.add(NdpOption::Unknown {
+            option_type: 99,
+            data: vec![0xFF; 6],
+        });
+
+        let mut buf = vec![0; options.serialized_len()];
+        options.write_to(&mut buf).unwrap();
+        let mut expect = Checksum::new();
+        expect.add_bytes(&buf);
+
+        let mut actual = Checksum::new();
+        options.add_to_checksum(&mut actual);
+        assert_eq!(expect, actual);
+
+        assert_eq!(options, NdpOptions::from_bytes(&buf).unwrap());
+        assert_eq!(
+            Some(&MacAddr::new(2, 0, 0, 0, 0, 1)),
+            options.source_link_layer_address()
+        );
+        assert_eq!(None, options.target_link_layer_address());
+    }
+
+    #[test]
+    fn test_ndp_option_write_oversized() {
+        // 256 servers need 8 + 256 * 16 = 4104 bytes, over 255 units of 8 octets
+        let option = NdpOption::RecursiveDnsServer {
+            lifetime: 60,
+            servers: vec![[0; 16]; 256],
+        };
+        let mut buf = vec![0; option.serialized_len()];
+
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Icmpv6,
+            kind: RECURSIVE_DNS_SERVER,
+            length: 4104,
+        });
+        let actual = option.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+}
@@ -0,0 +1,209 @@
+/// Value of the IPv4 Protocol and IPv6 Next Header fields
+///
+/// Any value without a named variant is kept in `Other`, so every value
+/// round-trips.
+///
+/// Reference: IANA Assigned Internet Protocol Numbers registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpProtocol {
+    /// IPv6 Hop-by-Hop Options header (RFC 8200)
+    HopByHop,
+    Icmp,
+    Igmp,
+    /// IPv4 encapsulation (RFC 2003)
+    IpInIp,
+    Tcp,
+    Udp,
+    /// IPv6 encapsulation (RFC 2473)
+    Ipv6,
+    /// IPv6 Routing header (RFC 8200)
+    Ipv6Routing,
+    /// IPv6 Fragment header (RFC 8200)
+    Ipv6Fragment,
+    /// Generic Routing Encapsulation (RFC 2784)
+    Gre,
+    /// Encapsulating Security Payload (RFC 4303)
+    Esp,
+    /// Authentication Header (RFC 4302)
+    Ah,
+    Icmpv6,
+    /// No next header (RFC 8200)
+    Ipv6NoNextHeader,
+    /// IPv6 Destination Options header (RFC 8200)
+    Ipv6DestinationOptions,
+    Ospf,
+    /// Protocol Independent Multicast (RFC 7761)
+    Pim,
+    /// Virtual Router Redundancy Protocol (RFC 9568)
+    Vrrp,
+    Sctp,
+    /// Mobility header (RFC 6275)
+    Ipv6Mobility,
+    /// UDP-Lite (RFC 3828)
+    UdpLite,
+    /// MPLS in IP (RFC 4023)
+    MplsInIp,
+    /// Host Identity Protocol (RFC 7401)
+    Hip,
+    /// Shim6 protocol (RFC 5533)
+    Shim6,
+    /// Any other value
+    Other(u8),
+}
+
+impl IpProtocol {
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            IpProtocol::HopByHop => 0,
+            IpProtocol::Icmp => 1,
+            IpProtocol::Igmp => 2,
+            IpProtocol::IpInIp => 4,
+            IpProtocol::Tcp => 6,
+            IpProtocol::Udp => 17,
+            IpProtocol::Ipv6 => 41,
+            IpProtocol::Ipv6Routing => 43,
+            IpProtocol::Ipv6Fragment => 44,
+            IpProtocol::Gre => 47,
+            IpProtocol::Esp => 50,
+            IpProtocol::Ah => 51,
+            IpProtocol::Icmpv6 => 58,
+            IpProtocol::Ipv6NoNextHeader => 59,
+            IpProtocol::Ipv6DestinationOptions => 60,
+            IpProtocol::Ospf => 89,
+            IpProtocol::Pim => 103,
+            IpProtocol::Vrrp => 112,
+            IpProtocol::Sctp => 132,
+            IpProtocol::Ipv6Mobility => 135,
+            IpProtocol::UdpLite => 136,
+            IpProtocol::MplsInIp => 137,
+            IpProtocol::Hip => 139,
+            IpProtocol::Shim6 => 140,
+            IpProtocol::Other(value) => *value,
+        }
+    }
+
+    /// Whether this value identifies an IPv6 extension header
+    ///
+    /// Includes the experimental values 253 and 254.
+    ///
+    /// Reference: RFC 8200 Section 4, IANA IPv6 Extension Header Types registry
+    pub fn is_ipv6_extension_header(&self) -> bool {
+        matches!(
+            self,
+            IpProtocol::HopByHop
+                | IpProtocol::Ipv6Routing
+                | IpProtocol::Ipv6Fragment
+                | IpProtocol::Esp
+                | IpProtocol::Ah
+                | IpProtocol::Ipv6DestinationOptions
+                | IpProtocol::Ipv6Mobility
+                | IpProtocol::Hip
+                | IpProtocol::Shim6
+                | IpProtocol::Other(253 | 254)
+        )
+    }
+}
+
+impl From<u8> for IpProtocol {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => IpProtocol::HopByHop,
+            1 => IpProtocol::Icmp,
+            2 => IpProtocol::Igmp,
+            4 => IpProtocol::IpInIp,
+            6 => IpProtocol::Tcp,
+            17 => IpProtocol::Udp,
+            41 => IpProtocol::Ipv6,
+            43 => IpProtocol::Ipv6Routing,
+            44 => IpProtocol::Ipv6Fragment,
+            47 => IpProtocol::Gre,
+            50 => IpProtocol::Esp,
+            51 => IpProtocol::Ah,
+            58 => IpProtocol::Icmpv6,
+            59 => IpProtocol::Ipv6NoNextHeader,
+            60 => IpProtocol::Ipv6DestinationOptions,
+            89 => IpProtocol::Ospf,
+            103 => IpProtocol::Pim,
+            112 => IpProtocol::Vrrp,
+            132 => IpProtocol::Sctp,
+            135 => IpProtocol::Ipv6Mobility,
+            136 => IpProtocol::UdpLite,
+            137 => IpProtocol::MplsInIp,
+            139 => IpProtocol::Hip,
+            140 => IpProtocol::Shim6,
+            _ => IpProtocol::Other(value),
+        }
+    }
+}
+
+impl From<IpProtocol> for u8 {
+    fn from(protocol: IpProtocol) -> Self {
+        protocol.to_u8()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every named variant with its registry value
+    const REGISTRY: [(IpProtocol, u8); 24] = [
+        (IpProtocol::HopByHop, 0),
+        (IpProtocol::Icmp, 1),
+        (IpProtocol::Igmp, 2),
+        (IpProtocol::IpInIp, 4),
+        (IpProtocol::Tcp, 6),
+        (IpProtocol::Udp, 17),
+        (IpProtocol::Ipv6, 41),
+        (IpProtocol::Ipv6Routing, 43),
+        (IpProtocol::Ipv6Fragment, 44),
+        (IpProtocol::Gre, 47),
+        (IpProtocol::Esp, 50),
+        (IpProtocol::Ah, 51),
+        (IpProtocol::Icmpv6, 58),
+        (IpProtocol::Ipv6NoNextHeader, 59),
+        (IpProtocol::Ipv6DestinationOptions, 60),
+        (IpProtocol::Ospf, 89),
+        (IpProtocol::Pim, 103),
+        (IpProtocol::Vrrp, 112),
+        (IpProtocol::Sctp, 132),
+        (IpProtocol::Ipv6Mobility, 135),
+        (IpProtocol::UdpLite, 136),
+        (IpProtocol::MplsInIp, 137),
+        (IpProtocol::Hip, 139),
+        (IpProtocol::Shim6, 140),
+    ];
+
+    #[test]
+    fn test_registry_roundtrip() {
+        for (protocol, value) in REGISTRY {
+            assert_eq!(value, protocol.to_u8());
+            assert_eq!(protocol, IpProtocol::from(value));
+        }
+    }
+
+    #[test]
+    fn test_every_value_roundtrips() {
+        for value in 0..=u8::MAX {
+            let actual = u8::from(IpProtocol::from(value));
+            assert_eq!(value, actual);
+        }
+    }
+
+    #[test]
+    fn test_from_other() {
+        let expect = IpProtocol::Other(200);
+        let actual = IpProtocol::from(200);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_is_ipv6_extension_header() {
+        let extensions = [0, 43, 44, 50, 51, 60, 135, 139, 140, 253, 254];
+        for value in 0..=u8::MAX {
+            let expect = extensions.contains(&value);
+            let actual = IpProtocol::from(value).is_ipv6_extension_header();
+            assert_eq!(expect, actual, "protocol {value}");
+        }
+    }
+}
@@ -1,19 +1,30 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use crate::network::qos::{Dscp, Ecn, traffic_class};
+
+/// Length of the fixed IPv4 header in bytes (IHL of 5 words)
+pub const IPV4_HEADER_LEN: usize = 20;
+
+/// Largest value of the 13-bit Fragment Offset field, in units of 8 bytes
+pub const MAX_FRAGMENT_OFFSET: u16 = 0x1FFF;
+
 /// IPv4 header structure based on RFC 791
 ///
 /// Reference: RFC 791 Section 3.1 - Internet Header Format
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ipv4Header {
     pub version: u8,                  // 4 bits - IP version (always 4 for IPv4)
     pub ihl: u8,                      // 4 bits - Internet Header Length in 32-bit words
-    pub type_of_service: u8,          // 8 bits - Type of Service (ToS) / DSCP
+    pub type_of_service: u8,          // 8 bits - DSCP (6 bits) + ECN (2 bits)
     pub total_length: u16,            // 16 bits - Total length of IP datagram in bytes
     pub identification: u16,          // 16 bits - Identification for fragmentation
     pub flags: Ipv4Flags,             // 3 bits - Control flags (Reserved, DF, MF)
     pub fragment_offset: u16,         // 13 bits - Fragment offset in 8-byte units
     pub time_to_live: u8,             // 8 bits - TTL
-    pub protocol: u8,                 // 8 bits - Next level protocol
+    pub protocol: IpProtocol,         // 8 bits - Next level protocol
     pub header_checksum: u16,         // 16 bits - Header checksum
     pub source_address: [u8; 4],      // 32 bits - Source IP address
     pub destination_address: [u8; 4], // 32 bits - Destination IP address
@@ -25,7 +36,7 @@ pub struct Ipv4Header {
 /// Bit 0: Reserved (must be zero)
 /// Bit 1: DF (Don't Fragment) - 0 = May Fragment, 1 = Don't Fragment
 /// Bit 2: MF (More Fragments) - 0 = Last Fragment, 1 = More Fragments
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ipv4Flags {
     pub reserved: bool,       // Bit 0 - Reserved, must be zero
     pub dont_fragment: bool,  // Bit 1 - DF flag
@@ -55,7 +66,7 @@ impl Ipv4Flags {
         if self.more_fragments {
             flags |= 0x2000; // Bit 13
         }
-        flags | (fragment_offset & 0x1FFF) // Lower 13 bits for fragment offset
+        flags | (fragment_offset & MAX_FRAGMENT_OFFSET) // Lower 13 bits for fragment offset
     }
 
     /// Create flags from a u16 value during deserialization
@@ -65,7 +76,7 @@ impl Ipv4Flags {
             dont_fragment: (value & 0x4000) != 0,
             more_fragments: (value & 0x2000) != 0,
         };
-        let fragment_offset = value & 0x1FFF;
+        let fragment_offset = value & MAX_FRAGMENT_OFFSET;
         (flags, fragment_offset)
     }
 }
@@ -77,7 +88,11 @@ impl Default for Ipv4Flags {
 }
 
 impl Ipv4Header {
-    pub fn new(source_address: [u8; 4], destination_address: [u8; 4], protocol: u8) -> Self {
+    pub fn new(
+        source_address: [u8; 4],
+        destination_address: [u8; 4],
+        protocol: IpProtocol,
+    ) -> Self {
         Ipv4Header {
             version: 4,
             ihl: 5, // Minimum header length (20 bytes = 5 words)
@@ -94,52 +109,93 @@ impl Ipv4Header {
         }
     }
 
+    /// Differentiated Services Code Point from the upper six bits of the Type of Service
+    pub fn dscp(&self) -> Dscp {
+        Dscp::from_traffic_class(self.type_of_service)
+    }
+
+    /// Set the DSCP, leaving the ECN bits unchanged
+    pub fn set_dscp(&mut self, dscp: Dscp) {
+        self.type_of_service = traffic_class(dscp, self.ecn());
+    }
+
+    /// Explicit Congestion Notification from the lower two bits of the Type of Service
+    pub fn ecn(&self) -> Ecn {
+        Ecn::from_traffic_class(self.type_of_service)
+    }
+
+    /// Set the ECN, leaving the DSCP bits unchanged
+    pub fn set_ecn(&mut self, ecn: Ecn) {
+        self.type_of_service = traffic_class(self.dscp(), ecn);
+    }
+
+    /// Length of the serialized fixed header in bytes (options are separate)
+    pub fn serialized_len(&self) -> usize {
+        IPV4_HEADER_LEN
+    }
+
     /// Serialize IPv4 header to bytes (network byte order)
     ///
     /// Reference: RFC 791 Section 3.1 for field layout
+    #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(20);
+        let mut bytes = alloc::vec![0; IPV4_HEADER_LEN];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+
+    /// Serialize IPv4 header into `buf` (network byte order), returning the number of bytes written
+    ///
+    /// Reference: RFC 791 Section 3.1 for field layout
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, IPV4_HEADER_LEN)?;
+        let bytes = &mut buf[..IPV4_HEADER_LEN];
 
         // Byte 0: Version (4 bits) + IHL (4 bits)
-        bytes.push((self.version << 4) | (self.ihl & 0x0F));
+        bytes[0] = (self.version << 4) | (self.ihl & 0x0F);
 
         // Byte 1: Type of Service
-        bytes.push(self.type_of_service);
+        bytes[1] = self.type_of_service;
 
         // Bytes 2-3: Total Length (big-endian)
-        bytes.extend_from_slice(&self.total_length.to_be_bytes());
+        bytes[2..4].copy_from_slice(&self.total_length.to_be_bytes());
 
         // Bytes 4-5: Identification (big-endian)
-        bytes.extend_from_slice(&self.identification.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.identification.to_be_bytes());
 
         // Bytes 6-7: Flags (3 bits) + Fragment Offset (13 bits) (big-endian)
         let flags_and_frag = self.flags.to_u16(self.fragment_offset);
-        bytes.extend_from_slice(&flags_and_frag.to_be_bytes());
+        bytes[6..8].copy_from_slice(&flags_and_frag.to_be_bytes());
 
         // Byte 8: Time to Live
-        bytes.push(self.time_to_live);
+        bytes[8] = self.time_to_live;
 
         // Byte 9: Protocol
-        bytes.push(self.protocol);
+        bytes[9] = self.protocol.to_u8();
 
         // Bytes 10-11: Header Checksum (big-endian)
-        bytes.extend_from_slice(&self.header_checksum.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.header_checksum.to_be_bytes());
 
         // Bytes 12-15: Source Address
-        bytes.extend_from_slice(&self.source_address);
+        bytes[12..16].copy_from_slice(&self.source_address);
 
         // Bytes 16-19: Destination Address
-        bytes.extend_from_slice(&self.destination_address);
+        bytes[16..20].copy_from_slice(&self.destination_address);
 
-        bytes
+        Ok(IPV4_HEADER_LEN)
     }
 
     /// Deserialize IPv4 header from bytes (network byte order)
     ///
     /// Reference: RFC 791 Section 3.1 for field layout
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
-        if bytes.len() < 20 {
-            return Err("IPv4 header must be at least 20 bytes");
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < IPV4_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: IPV4_HEADER_LEN,
+                got: bytes.len(),
+            });
         }
 
         // Byte 0: Version + IHL
@@ -148,11 +204,17 @@ impl Ipv4Header {
         let ihl = version_ihl & 0x0F;
 
         if version != 4 {
-            return Err("Invalid IP version");
+            return Err(Error::BadVersion {
+                layer: Layer::Ipv4,
+                version,
+            });
         }
 
         if ihl < 5 {
-            return Err("Invalid IHL (must be at least 5)");
+            return Err(Error::BadHeaderLength {
+                layer: Layer::Ipv4,
+                length: ihl as usize * 4,
+            });
         }
 
         // Byte 1: Type of Service
@@ -172,7 +234,7 @@ impl Ipv4Header {
         let time_to_live = bytes[8];
 
         // Byte 9: Protocol
-        let protocol = bytes[9];
+        let protocol = IpProtocol::from(bytes[9]);
 
         // Bytes 10-11: Header Checksum
         let header_checksum = u16::from_be_bytes([bytes[10], bytes[11]]);
@@ -262,7 +324,7 @@ mod tests {
     fn test_ipv4_header_new() {
         let src_ip = [192, 168, 1, 1];
         let dst_ip = [10, 0, 0, 1];
-        let protocol = 6; // TCP
+        let protocol = IpProtocol::Tcp;
         let header = Ipv4Header::new(src_ip, dst_ip, protocol);
 
         assert_eq!(4, header.version);
@@ -275,10 +337,11 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_header_to_bytes() {
         let src_ip = [192, 168, 1, 1];
         let dst_ip = [10, 0, 0, 1];
-        let mut header = Ipv4Header::new(src_ip, dst_ip, 6);
+        let mut header = Ipv4Header::new(src_ip, dst_ip, IpProtocol::Tcp);
         header.total_length = 40; // 20-byte header + 20-byte payload
         header.identification = 0x1234;
         header.flags.dont_fragment = true;
@@ -306,6 +369,43 @@ mod tests {
         assert_eq!(dst_ip, &bytes[16..20]);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_header_write_to() {
+        let mut header = Ipv4Header::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Tcp);
+        header.total_length = 40;
+        let expect = header.to_bytes();
+
+        let mut buf = [0u8; 24];
+        let len = header.write_to(&mut buf).unwrap();
+        assert_eq!(header.serialized_len(), len);
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 20,
+            got: 19,
+        });
+        let actual = header.write_to(&mut buf[..19]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_header_dscp_ecn() {
+        let mut header = Ipv4Header::new([192, 168, 1, 1], [10, 0, 0, 1], IpProtocol::Tcp);
+        header.set_dscp(Dscp::EF);
+        header.set_ecn(Ecn::Ect0);
+
+        assert_eq!(0xBA, header.type_of_service);
+        assert_eq!(Dscp::EF, header.dscp());
+        assert_eq!(Ecn::Ect0, header.ecn());
+
+        // Changing one field leaves the other in place
+        header.set_dscp(Dscp::CS1);
+        assert_eq!(0x22, header.type_of_service);
+        header.set_ecn(Ecn::NotEct);
+        assert_eq!(0x20, header.type_of_service);
+    }
+
     #[test]
     fn test_ipv4_header_from_bytes() {
         let bytes = [
@@ -327,7 +427,7 @@ mod tests {
         assert!(!header.flags.more_fragments);
         assert_eq!(0, header.fragment_offset);
         assert_eq!(64, header.time_to_live);
-        assert_eq!(6, header.protocol);
+        assert_eq!(IpProtocol::Tcp, header.protocol);
         assert_eq!(0x5678, header.header_checksum);
         assert_eq!([192, 168, 1, 1], header.source_address);
         assert_eq!([10, 0, 0, 1], header.destination_address);
@@ -337,20 +437,33 @@ mod tests {
     fn test_ipv4_header_from_bytes_invalid() {
         // Too short
         let short_bytes = [0x45, 0x00];
-        assert!(Ipv4Header::from_bytes(&short_bytes).is_err());
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv4,
+            needed: 20,
+            got: 2,
+        });
+        assert_eq!(expect, Ipv4Header::from_bytes(&short_bytes));
 
         // Invalid version
         let invalid_version = [
             0x55, 0x00, 0x00, 0x28, 0x12, 0x34, 0x40, 0x00, 0x40, 0x06, 0x56, 0x78, 0xC0, 0xA8,
             0x01, 0x01, 0x0A, 0x00, 0x00, 0x01,
         ];
-        assert!(Ipv4Header::from_bytes(&invalid_version).is_err());
+        let expect = Err(Error::BadVersion {
+            layer: Layer::Ipv4,
+            version: 5,
+        });
+        assert_eq!(expect, Ipv4Header::from_bytes(&invalid_version));
 
         // Invalid IHL (less than 5)
         let invalid_ihl = [
             0x44, 0x00, 0x00, 0x28, 0x12, 0x34, 0x40, 0x00, 0x40, 0x06, 0x56, 0x78, 0xC0, 0xA8,
             0x01, 0x01, 0x0A, 0x00, 0x00, 0x01,
         ];
-        assert!(Ipv4Header::from_bytes(&invalid_ihl).is_err());
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Ipv4,
+            length: 16,
+        });
+        assert_eq!(expect, Ipv4Header::from_bytes(&invalid_ihl));
     }
 }
@@ -1,9 +1,14 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 pub mod header;
 pub mod options;
+pub mod reassembly;
 
-use header::Ipv4Header;
+use crate::checksum::{Checksum, ChecksumVerification};
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use header::{IPV4_HEADER_LEN, Ipv4Header};
 use options::Ipv4Options;
 
 /// IPv4 packet combining header, options, and payload
@@ -15,7 +20,7 @@ pub struct Ipv4Packet<'a> {
 }
 
 impl<'a> Ipv4Packet<'a> {
-    pub fn new(src_ip: [u8; 4], dst_ip: [u8; 4], protocol: u8, payload: &'a [u8]) -> Self {
+    pub fn new(src_ip: [u8; 4], dst_ip: [u8; 4], protocol: IpProtocol, payload: &'a [u8]) -> Self {
         Ipv4Packet {
             header: Ipv4Header::new(src_ip, dst_ip, protocol),
             options: Ipv4Options::new(),
@@ -23,80 +28,269 @@ impl<'a> Ipv4Packet<'a> {
         }
     }
 
+    /// Parse an IPv4 datagram, borrowing the payload from `bytes`
+    ///
+    /// The IHL delimits the options and the total length delimits the payload,
+    /// so any link-layer padding after the datagram is ignored
+    /// (RFC 791 Section 3.1).
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = Ipv4Header::from_bytes(bytes)?;
+
+        let header_len = header.ihl as usize * 4;
+        if bytes.len() < header_len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: header_len,
+                got: bytes.len(),
+            });
+        }
+
+        let total_len = header.total_length as usize;
+        if total_len < header_len {
+            return Err(Error::BadTotalLength {
+                layer: Layer::Ipv4,
+                length: total_len,
+            });
+        }
+        if bytes.len() < total_len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: total_len,
+                got: bytes.len(),
+            });
+        }
+
+        let options = Ipv4Options::from_bytes(&bytes[IPV4_HEADER_LEN..header_len])?;
+        let payload = &bytes[header_len..total_len];
+
+        Ok(Ipv4Packet {
+            header,
+            options,
+            payload,
+        })
+    }
+
     /// Calculate and set the correct IHL (Internet Header Length) based on header and options
     pub fn update_ihl(&mut self) {
         let header_words = 5; // Minimum header is 20 bytes = 5 words
         let option_words = self.options.words_needed();
-        self.header.ihl = header_words + option_words;
+        self.header.ihl = option_words.saturating_add(header_words);
     }
 
-    /// Calculate and set the total length field
-    pub fn update_total_length(&mut self) {
-        let header_len = (self.header.ihl as u16) * 4;
-        let total_len = header_len + self.payload.len() as u16;
-        self.header.total_length = total_len;
+    /// Calculate and set the total length field from the IHL and payload
+    ///
+    /// Fails with [`Error::BadTotalLength`] if the datagram would be longer
+    /// than 65535 bytes.
+    pub fn update_total_length(&mut self) -> Result<(), Error> {
+        let total_len = self.header.ihl as usize * 4 + self.payload.len();
+        self.header.total_length = checked_total_length(total_len)?;
+        Ok(())
     }
 
     /// Calculate IPv4 header checksum (header only, not payload)
-    pub fn calculate_header_checksum(&self) -> u16 {
-        let mut sum = 0u32;
-
-        // Serialize header with checksum field set to 0
-        let mut header_bytes = self.header.to_bytes();
+    ///
+    /// Fails if the options cannot be serialized.
+    pub fn calculate_header_checksum(&self) -> Result<u16, Error> {
+        let mut checksum = Checksum::new();
+
+        // Header with checksum field set to 0
+        let mut header_bytes = [0u8; IPV4_HEADER_LEN];
+        self.header
+            .write_to(&mut header_bytes)
+            .expect("buffer sized for the IPv4 header");
         header_bytes[10] = 0; // Clear checksum field
         header_bytes[11] = 0;
+        checksum.add_bytes(&header_bytes);
 
         // Add options
-        let option_bytes = self.options.to_bytes();
-        header_bytes.extend_from_slice(&option_bytes);
+        self.options.add_to_checksum(&mut checksum)?;
 
-        // Process header + options in 16-bit chunks
-        for chunk in header_bytes.chunks(2) {
-            if chunk.len() == 2 {
-                sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-            } else {
-                sum += (chunk[0] as u32) << 8; // Pad with zero
-            }
+        Ok(checksum.finish())
+    }
+
+    /// Verify the stored header checksum against the header and options
+    ///
+    /// Reference: RFC 791 Section 3.1 - Header Checksum
+    pub fn verify_checksum(&self) -> Result<ChecksumVerification, Error> {
+        Ok(ChecksumVerification {
+            stored: self.header.header_checksum,
+            expected: self.calculate_header_checksum()?,
+        })
+    }
+
+    /// Length of the serialized packet in bytes (header, padded options and payload)
+    pub fn serialized_len(&self) -> usize {
+        self.header_len() + self.payload.len()
+    }
+
+    /// Length of the serialized header in bytes (fixed header and padded options)
+    pub fn header_len(&self) -> usize {
+        self.header.serialized_len() + self.options.serialized_len()
+    }
+
+    /// Serialize the header and options into `buf`, returning the number of bytes written
+    ///
+    /// The IHL, total length, and checksum are calculated as in
+    /// [`Ipv4Packet::write_to`]; the payload is not written. Fails with
+    /// [`Error::BadTotalLength`] if header and payload exceed 65535 bytes.
+    pub fn write_header_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let header_len = self.header_len();
+        let total_len = checked_total_length(self.serialized_len())?;
+        ensure_capacity(buf, header_len)?;
+
+        self.header.write_to(buf)?;
+        self.options.write_to(&mut buf[IPV4_HEADER_LEN..])?;
+
+        // Byte 0: Version + IHL (at most 15 words since the options fit the
+        // option space), Bytes 2-3: Total Length
+        buf[0] = (self.header.version << 4) | ((header_len / 4) as u8 & 0x0F);
+        buf[2..4].copy_from_slice(&total_len.to_be_bytes());
+
+        // Bytes 10-11: Header Checksum over header and options
+        buf[10..12].fill(0);
+        let mut checksum = Checksum::new();
+        checksum.add_bytes(&buf[..header_len]);
+        buf[10..12].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        Ok(header_len)
+    }
+
+    /// Serialize the complete packet into `buf`, returning the number of bytes written
+    ///
+    /// The IHL, total length, and checksum written to `buf` are calculated from
+    /// the options and payload; unlike [`Ipv4Packet::to_bytes`], the fields in
+    /// `self.header` are left unchanged.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        let header_len = self.write_header_to(buf)?;
+        buf[header_len..total_len].copy_from_slice(self.payload);
+
+        Ok(total_len)
+    }
+
+    /// Split the packet into fragments that each fit within `mtu` bytes
+    ///
+    /// Every fragment carries a multiple of 8 payload bytes except the last,
+    /// shares the identification, and has MF set unless it is the last
+    /// fragment of the original packet. The first fragment keeps all options;
+    /// later fragments keep only those with the copied flag. IHL, total length,
+    /// and checksum are set on each fragment. A packet that already fits is
+    /// returned as a single packet.
+    ///
+    /// Reference: RFC 791 Section 3.2 - Fragmentation and Reassembly
+    #[cfg(feature = "alloc")]
+    pub fn fragment(&self, mtu: usize) -> Result<Vec<Ipv4Packet<'a>>, Error> {
+        let length = self.serialized_len();
+        if length <= mtu {
+            let mut packet = Ipv4Packet {
+                header: self.header.clone(),
+                options: self.options.clone(),
+                payload: self.payload,
+            };
+            packet.update_derived_fields()?;
+            return Ok(alloc::vec![packet]);
+        }
+        if self.header.flags.dont_fragment {
+            return Err(Error::DontFragment { length, mtu });
         }
 
-        // Fold carry bits
-        while (sum >> 16) != 0 {
-            sum = (sum & 0xFFFF) + (sum >> 16);
+        let copied = self.options.copied();
+        let mut fragments = Vec::new();
+        let mut offset = 0;
+        loop {
+            let options = if offset == 0 {
+                self.options.clone()
+            } else {
+                copied.clone()
+            };
+            let header_len = IPV4_HEADER_LEN + options.serialized_len();
+            let block_len = mtu.saturating_sub(header_len) / 8 * 8;
+            if block_len == 0 {
+                return Err(Error::MtuTooSmall { mtu });
+            }
+            let end = (offset + block_len).min(self.payload.len());
+            let last = end == self.payload.len();
+
+            // A fragment starting beyond the 13-bit offset field cannot be sent
+            let fragment_offset = u16::try_from(offset / 8)
+                .ok()
+                .and_then(|blocks| self.header.fragment_offset.checked_add(blocks))
+                .filter(|&fragment_offset| fragment_offset <= header::MAX_FRAGMENT_OFFSET)
+                .ok_or(Error::BadFragment {
+                    offset: self.header.fragment_offset as usize * 8 + offset,
+                    length: end - offset,
+                })?;
+
+            let mut header = self.header.clone();
+            header.fragment_offset = fragment_offset;
+            header.flags.more_fragments = !last || self.header.flags.more_fragments;
+
+            let mut fragment = Ipv4Packet {
+                header,
+                options,
+                payload: &self.payload[offset..end],
+            };
+            fragment.update_derived_fields()?;
+            fragments.push(fragment);
+
+            if last {
+                return Ok(fragments);
+            }
+            offset = end;
         }
+    }
 
-        // One's complement
-        !sum as u16
+    /// Set the IHL, total length, and checksum from the options and payload
+    #[cfg(feature = "alloc")]
+    fn update_derived_fields(&mut self) -> Result<(), Error> {
+        self.update_ihl();
+        self.update_total_length()?;
+        self.header.header_checksum = self.calculate_header_checksum()?;
+        Ok(())
     }
 
     /// Update IHL, total length, and checksum, then serialize the complete packet
-    pub fn to_bytes(&mut self) -> Vec<u8> {
+    ///
+    /// Fails, like [`Ipv4Packet::write_to`], if the options cannot be serialized.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>, Error> {
         // Update calculated fields
-        self.update_ihl();
-        self.update_total_length();
-
-        // Calculate and set checksum
-        self.header.header_checksum = self.calculate_header_checksum();
+        self.update_derived_fields()?;
 
         // Serialize
-        let mut bytes = self.header.to_bytes();
-        bytes.extend_from_slice(&self.options.to_bytes());
-        bytes.extend_from_slice(self.payload);
-
-        bytes
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
     }
 }
 
+/// Convert a datagram length to the 16-bit Total Length field
+fn checked_total_length(length: usize) -> Result<u16, Error> {
+    u16::try_from(length).map_err(|_| Error::BadTotalLength {
+        layer: Layer::Ipv4,
+        length,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::network::qos::Dscp;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
     use options::Ipv4Option;
+    #[cfg(feature = "alloc")]
+    use options::{Ipv4Route, Ipv4Security};
 
     #[test]
     fn test_ipv4_packet_new() {
         let payload = b"Hello";
         let src_ip = [192, 168, 1, 1];
         let dst_ip = [192, 168, 1, 100];
-        let protocol = 6; // TCP
+        let protocol = IpProtocol::Tcp;
         let actual = Ipv4Packet::new(src_ip, dst_ip, protocol, payload);
 
         assert_eq!(src_ip, actual.header.source_address);
@@ -111,7 +305,7 @@ mod tests {
         let payload = b"Hello";
         let src_ip = [192, 168, 1, 1];
         let dst_ip = [192, 168, 1, 100];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 6, payload);
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Tcp, payload);
 
         // No options
         packet.update_ihl();
@@ -124,14 +318,15 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_packet_basic() {
         // Test basic IPv4 packet without options
         let payload = b"Test";
         let src_ip = [10, 0, 0, 1];
         let dst_ip = [10, 0, 0, 2];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 17, payload); // UDP protocol
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Udp, payload);
 
-        let actual = packet.to_bytes();
+        let actual = packet.to_bytes().unwrap();
 
         // Verify structure
         assert_eq!(24, actual.len()); // 20-byte header + 4-byte payload
@@ -161,18 +356,19 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_packet_with_options() {
         // Test IPv4 packet with options (EOL + padding)
         let payload = b"Data";
         let src_ip = [172, 16, 0, 1];
         let dst_ip = [172, 16, 0, 2];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 1, payload); // ICMP protocol
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Icmp, payload);
 
         // Add options
         packet.options.add(Ipv4Option::NoOperation);
         packet.options.add(Ipv4Option::EndOfOptionsList);
 
-        let actual = packet.to_bytes();
+        let actual = packet.to_bytes().unwrap();
 
         // Verify structure: 20-byte header + 4-byte options + 4-byte payload = 28 bytes
         assert_eq!(28, actual.len());
@@ -201,19 +397,20 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_packet_fragmentation_flags() {
         // Test IPv4 packet with fragmentation flags
         let payload = b"Fragment";
         let src_ip = [203, 0, 113, 1];
         let dst_ip = [203, 0, 113, 2];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 17, payload); // UDP
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Udp, payload);
 
         // Set fragmentation flags - this is a fragment with more fragments
         packet.header.flags.more_fragments = true;
         packet.header.fragment_offset = 185; // 185 * 8 = 1480 bytes offset
         packet.header.identification = 0xABCD;
 
-        let actual = packet.to_bytes();
+        let actual = packet.to_bytes().unwrap();
 
         // Verify structure
         assert_eq!(28, actual.len()); // 20-byte header + 8-byte payload
@@ -229,18 +426,19 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_packet_dont_fragment() {
         // Test IPv4 packet with Don't Fragment flag
         let payload = b"";
         let src_ip = [192, 0, 2, 1];
         let dst_ip = [192, 0, 2, 2];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 6, payload); // TCP
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Tcp, payload);
 
         packet.header.flags.dont_fragment = true;
         packet.header.identification = 0x1234;
-        packet.header.type_of_service = 0x04; // Reliability bit set
+        packet.header.set_dscp(Dscp::LE);
 
-        let actual = packet.to_bytes();
+        let actual = packet.to_bytes().unwrap();
 
         // Verify structure
         assert_eq!(20, actual.len()); // 20-byte header only
@@ -249,8 +447,8 @@ mod tests {
         assert_eq!(0x40, actual[6]); // DF flag set, no fragment offset
         assert_eq!(0x00, actual[7]);
 
-        // Verify ToS
-        assert_eq!(0x04, actual[1]); // Reliability bit
+        // Verify DSCP (LE is code point 1) and ECN (Not-ECT)
+        assert_eq!(0x04, actual[1]);
 
         // Verify other fields
         assert_eq!(0x12, actual[4]); // ID high
@@ -258,18 +456,133 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes() {
+        let mut expect = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        expect.header.identification = 0x1234;
+        let bytes = expect.to_bytes().unwrap();
+
+        let actual = Ipv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes_with_options() {
+        let mut expect =
+            Ipv4Packet::new([172, 16, 0, 1], [172, 16, 0, 2], IpProtocol::Icmp, b"Data");
+        expect.options.add(Ipv4Option::NoOperation);
+        expect.options.add(Ipv4Option::EndOfOptionsList);
+        let bytes = expect.to_bytes().unwrap();
+
+        let actual = Ipv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(6, actual.header.ihl);
+        assert_eq!(expect.options, actual.options);
+        assert_eq!(b"Data", actual.payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes_ignores_trailer() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Tcp, b"abc");
+        let mut bytes = packet.to_bytes().unwrap();
+        bytes.resize(46, 0); // Ethernet minimum payload padding
+
+        let actual = Ipv4Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(b"abc", actual.payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes_total_length_exceeds_buffer() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Tcp, b"abc");
+        let bytes = packet.to_bytes().unwrap();
+
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv4,
+            needed: 23,
+            got: 22,
+        });
+        let actual = Ipv4Packet::from_bytes(&bytes[..22]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes_total_length_smaller_than_header() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Tcp, b"abc");
+        let mut bytes = packet.to_bytes().unwrap();
+        bytes[2] = 0x00;
+        bytes[3] = 0x10; // Total length 16 < 20-byte header
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Ipv4,
+            length: 16,
+        });
+        let actual = Ipv4Packet::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_from_bytes_ihl_beyond_buffer() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Tcp, b"");
+        let mut bytes = packet.to_bytes().unwrap();
+        bytes[0] = 0x46; // IHL 6 (24 bytes), only 20 present
+
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv4,
+            needed: 24,
+            got: 20,
+        });
+        let actual = Ipv4Packet::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_write_to() {
+        let mut packet =
+            Ipv4Packet::new([172, 16, 0, 1], [172, 16, 0, 2], IpProtocol::Icmp, b"Data");
+        packet.options.add(Ipv4Option::NoOperation);
+
+        let mut buf = [0u8; 64];
+        let len = packet.write_to(&mut buf).unwrap();
+        assert_eq!(packet.serialized_len(), len);
+
+        // Derived fields are written without updating the packet itself
+        assert_eq!(0, packet.header.header_checksum);
+        let expect = packet.to_bytes().unwrap();
+        assert_eq!(expect[..], buf[..len]);
+    }
+
+    #[test]
+    fn test_ipv4_packet_write_to_buffer_too_small() {
+        let packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        let mut buf = [0u8; 23];
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 24,
+            got: 23,
+        });
+        let actual = packet.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_header_checksum_calculation() {
         // Test header checksum calculation with known values
         let payload = b"Test payload";
         let src_ip = [10, 0, 0, 1];
         let dst_ip = [10, 0, 0, 2];
-        let mut packet = Ipv4Packet::new(src_ip, dst_ip, 17, payload);
+        let mut packet = Ipv4Packet::new(src_ip, dst_ip, IpProtocol::Udp, payload);
 
         packet.header.identification = 0x1234;
         packet.header.time_to_live = 64;
 
         // Generate the packet bytes which will calculate and set the checksum
-        let bytes = packet.to_bytes();
+        let bytes = packet.to_bytes().unwrap();
         let calculated_checksum = u16::from_be_bytes([bytes[10], bytes[11]]);
 
         // Checksum should be non-zero and correctly calculated
@@ -278,4 +591,228 @@ mod tests {
         // Verify that the stored checksum matches what was calculated
         assert_eq!(packet.header.header_checksum, calculated_checksum);
     }
+
+    #[test]
+    fn test_ipv4_header_checksum_known_value() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        packet.header.identification = 0x1234;
+        packet.update_ihl();
+        packet.update_total_length().unwrap();
+
+        let expect = 0x549f;
+        let actual = packet.calculate_header_checksum().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_verify_checksum() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        let bytes = packet.to_bytes().unwrap();
+
+        let actual = Ipv4Packet::from_bytes(&bytes)
+            .unwrap()
+            .verify_checksum()
+            .unwrap();
+        assert_eq!(packet.header.header_checksum, actual.expected);
+        assert!(actual.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_verify_checksum_corrupted() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        let mut bytes = packet.to_bytes().unwrap();
+        bytes[8] = 1; // Rewrite TTL without updating the checksum
+
+        let actual = Ipv4Packet::from_bytes(&bytes)
+            .unwrap()
+            .verify_checksum()
+            .unwrap();
+        assert_eq!(packet.header.header_checksum, actual.stored);
+        assert_ne!(actual.stored, actual.expected);
+        assert!(!actual.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_checksum_oversized_option() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        packet.options.add(Ipv4Option::Unknown {
+            kind: 0x99,
+            data: vec![0; 300],
+        });
+        assert!(packet.calculate_header_checksum().is_err());
+        assert!(packet.verify_checksum().is_err());
+        assert!(packet.to_bytes().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_to_bytes_options_overflow_ihl() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        for _ in 0..5 {
+            packet.options.add(Ipv4Option::Unknown {
+                kind: 0x99,
+                data: vec![0; 253],
+            });
+        }
+
+        let expect = Err(Error::OptionSpaceExceeded {
+            layer: Layer::Ipv4,
+            length: 1276,
+            limit: 40,
+        });
+        let actual = packet.to_bytes();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment() {
+        let payload = [0xA5; 1000];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+        packet.header.identification = 0x4242;
+
+        let fragments = packet.fragment(576).unwrap();
+        assert_eq!(2, fragments.len());
+
+        let expect = [(552, 0, true), (448, 69, false)];
+        for (fragment, (len, offset, more)) in fragments.iter().zip(expect) {
+            assert_eq!(len, fragment.payload.len());
+            assert_eq!(offset, fragment.header.fragment_offset);
+            assert_eq!(more, fragment.header.flags.more_fragments);
+            assert_eq!(0x4242, fragment.header.identification);
+            assert_eq!((20 + len) as u16, fragment.header.total_length);
+            assert!(fragment.serialized_len() <= 576);
+            assert!(fragment.verify_checksum().unwrap().is_valid());
+        }
+        assert_eq!(&payload[..552], fragments[0].payload);
+        assert_eq!(&payload[552..], fragments[1].payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment_copies_options() {
+        let payload = [0; 100];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+        let security = Ipv4Option::Security(Ipv4Security {
+            security: 0x789A,
+            compartments: 0,
+            handling_restrictions: 0,
+            transmission_control_code: 0,
+        });
+        let record_route = Ipv4Option::RecordRoute(Ipv4Route::with_slots(2));
+        packet.options.add(security.clone());
+        packet.options.add(record_route.clone());
+
+        let fragments = packet.fragment(80).unwrap();
+
+        // 20 + 24 option bytes leaves 32 bytes of payload in the first fragment,
+        // 20 + 12 option bytes leaves 48 in the rest
+        let lengths: Vec<usize> = fragments.iter().map(|f| f.payload.len()).collect();
+        assert_eq!(vec![32, 48, 20], lengths);
+        assert_eq!(
+            vec![security.clone(), record_route],
+            fragments[0].options.options
+        );
+        for fragment in &fragments[1..] {
+            assert_eq!(vec![security.clone()], fragment.options.options);
+            assert_eq!(8, fragment.header.ihl);
+            assert!(fragment.verify_checksum().unwrap().is_valid());
+        }
+        assert_eq!(4, fragments[1].header.fragment_offset);
+        assert_eq!(10, fragments[2].header.fragment_offset);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment_fits() {
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, b"Test");
+        packet.header.flags.dont_fragment = true;
+
+        let mut fragments = packet.fragment(24).unwrap();
+        assert_eq!(1, fragments.len());
+        assert_eq!(packet.to_bytes().unwrap(), fragments[0].to_bytes().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment_dont_fragment() {
+        let payload = [0; 100];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+        packet.header.flags.dont_fragment = true;
+
+        let expect = Err(Error::DontFragment {
+            length: 120,
+            mtu: 68,
+        });
+        let actual = packet.fragment(68);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment_mtu_too_small() {
+        let payload = [0; 100];
+        let packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+
+        let expect = Err(Error::MtuTooSmall { mtu: 27 });
+        let actual = packet.fragment(27);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_refragment_keeps_more_fragments() {
+        let payload = [0; 64];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+        packet.header.flags.more_fragments = true;
+        packet.header.fragment_offset = 100;
+
+        let fragments = packet.fragment(52).unwrap();
+        assert_eq!(2, fragments.len());
+        for (fragment, offset) in fragments.iter().zip([100, 104]) {
+            assert_eq!(offset, fragment.header.fragment_offset);
+            assert!(fragment.header.flags.more_fragments);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_fragment_offset_overflow() {
+        let payload = [0; 64];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+        packet.header.flags.more_fragments = true;
+        packet.header.fragment_offset = header::MAX_FRAGMENT_OFFSET - 1;
+
+        // The second 32-byte fragment would start at offset 8194
+        let expect = Err(Error::BadFragment {
+            offset: 65552,
+            length: 32,
+        });
+        let actual = packet.fragment(52);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_packet_total_length_overflow() {
+        let payload = vec![0; 65516];
+        let mut packet = Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload);
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Ipv4,
+            length: 65536,
+        });
+        let mut buf = vec![0; packet.serialized_len()];
+        assert_eq!(expect, packet.write_to(&mut buf));
+        assert_eq!(expect, packet.to_bytes().map(|bytes| bytes.len()));
+
+        // One byte less is the largest datagram the field can express
+        let mut packet =
+            Ipv4Packet::new([10, 0, 0, 1], [10, 0, 0, 2], IpProtocol::Udp, &payload[1..]);
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!([0xFF, 0xFF], bytes[2..4]);
+    }
 }
@@ -1,7 +1,17 @@
-use alloc::vec;
 use alloc::vec::Vec;
 
-/// IPv4 option types as defined in RFC 791
+use crate::checksum::Checksum;
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Largest option representable with an 8-bit length field
+const MAX_OPTION_LEN: usize = u8::MAX as usize;
+
+/// Option space in bytes: the 60-byte maximum header (IHL 15) less the fixed 20
+///
+/// Reference: RFC 791 Section 3.1 - IHL
+pub const MAX_OPTIONS_LEN: usize = 40;
+
+/// IPv4 option types as defined in RFC 791 and later RFCs
 ///
 /// Reference: RFC 791 Section 3.1 - Options
 #[derive(Debug, PartialEq, Clone)]
@@ -13,6 +23,30 @@ pub enum Ipv4Option {
     /// No Operation (Type 1)
     /// Single byte option used for alignment
     NoOperation,
+
+    /// Security (Type 130, Length 11)
+    Security(Ipv4Security),
+
+    /// Loose Source and Record Route (Type 131)
+    LooseSourceRoute(Ipv4Route),
+
+    /// Record Route (Type 7)
+    RecordRoute(Ipv4Route),
+
+    /// Stream Identifier (Type 136, Length 4)
+    StreamId(u16),
+
+    /// Strict Source and Record Route (Type 137)
+    StrictSourceRoute(Ipv4Route),
+
+    /// Internet Timestamp (Type 68)
+    Timestamp(Ipv4Timestamp),
+
+    /// Router Alert (Type 148, Length 4) - RFC 2113
+    RouterAlert(u16),
+
+    /// Any other option, preserved as-is (`data` excludes the type and length octets)
+    Unknown { kind: u8, data: Vec<u8> },
 }
 
 impl Ipv4Option {
@@ -21,44 +55,430 @@ impl Ipv4Option {
         match self {
             Ipv4Option::EndOfOptionsList => 0,
             Ipv4Option::NoOperation => 1,
+            Ipv4Option::Security(_) => 130,
+            Ipv4Option::LooseSourceRoute(_) => 131,
+            Ipv4Option::RecordRoute(_) => 7,
+            Ipv4Option::StreamId(_) => 136,
+            Ipv4Option::StrictSourceRoute(_) => 137,
+            Ipv4Option::Timestamp(_) => 68,
+            Ipv4Option::RouterAlert(_) => 148,
+            Ipv4Option::Unknown { kind, .. } => *kind,
         }
     }
 
+    /// Whether the option is copied into every fragment (the copied flag)
+    ///
+    /// Reference: RFC 791 Section 3.1 - Options
+    pub fn is_copied(&self) -> bool {
+        self.option_type() & 0x80 != 0
+    }
+
     /// Get the length of this option in bytes
     pub fn length(&self) -> usize {
         match self {
             Ipv4Option::EndOfOptionsList => 1,
             Ipv4Option::NoOperation => 1,
+            Ipv4Option::Security(_) => 11,
+            Ipv4Option::LooseSourceRoute(route)
+            | Ipv4Option::RecordRoute(route)
+            | Ipv4Option::StrictSourceRoute(route) => 3 + route.addresses.len() * 4,
+            Ipv4Option::StreamId(_) => 4,
+            Ipv4Option::Timestamp(timestamp) => 4 + timestamp.data_len(),
+            Ipv4Option::RouterAlert(_) => 4,
+            Ipv4Option::Unknown { data, .. } => 2 + data.len(),
         }
     }
 
-    /// Serialize option to bytes
-    pub fn to_bytes(&self) -> Vec<u8> {
+    /// Serialize option into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the option is too long for its
+    /// 8-bit length field.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.length();
+        let length_field = u8::try_from(len).map_err(|_| Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: self.option_type(),
+            length: len,
+        })?;
+        ensure_capacity(buf, len)?;
+
+        // Byte 0: Type, Byte 1: Length (all but the single-byte options)
+        buf[0] = self.option_type();
+        if len > 1 {
+            buf[1] = length_field;
+        }
+
         match self {
-            Ipv4Option::EndOfOptionsList => vec![0],
-            Ipv4Option::NoOperation => vec![1],
+            Ipv4Option::EndOfOptionsList | Ipv4Option::NoOperation => {}
+            Ipv4Option::Security(security) => {
+                buf[2..4].copy_from_slice(&security.security.to_be_bytes());
+                buf[4..6].copy_from_slice(&security.compartments.to_be_bytes());
+                buf[6..8].copy_from_slice(&security.handling_restrictions.to_be_bytes());
+                buf[8..11].copy_from_slice(&security.transmission_control_code.to_be_bytes()[1..]);
+            }
+            Ipv4Option::LooseSourceRoute(route)
+            | Ipv4Option::RecordRoute(route)
+            | Ipv4Option::StrictSourceRoute(route) => {
+                buf[2] = route.pointer;
+                for (index, address) in route.addresses.iter().enumerate() {
+                    let start = 3 + index * 4;
+                    buf[start..start + 4].copy_from_slice(address);
+                }
+            }
+            Ipv4Option::StreamId(id) => buf[2..4].copy_from_slice(&id.to_be_bytes()),
+            Ipv4Option::Timestamp(timestamp) => timestamp.write_body(&mut buf[..len])?,
+            Ipv4Option::RouterAlert(value) => buf[2..4].copy_from_slice(&value.to_be_bytes()),
+            Ipv4Option::Unknown { data, .. } => buf[2..len].copy_from_slice(data),
         }
+        Ok(len)
+    }
+
+    /// Serialize option to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.length()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
     }
 
-    /// Deserialize option from bytes
-    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), &'static str> {
+    /// Deserialize option from bytes, returns (option, bytes_consumed)
+    ///
+    /// Unrecognized option types are returned as [`Ipv4Option::Unknown`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
         if bytes.is_empty() {
-            return Err("Empty option bytes");
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: 1,
+                got: 0,
+            });
         }
 
         let option_type = bytes[0];
         match option_type {
-            0 => Ok((Ipv4Option::EndOfOptionsList, 1)),
-            1 => Ok((Ipv4Option::NoOperation, 1)),
-            _ => Err("Unknown option type"),
+            0 => return Ok((Ipv4Option::EndOfOptionsList, 1)),
+            1 => return Ok((Ipv4Option::NoOperation, 1)),
+            _ => {}
+        }
+
+        // All other options carry a length octet covering type and length
+        if bytes.len() < 2 {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: 2,
+                got: bytes.len(),
+            });
+        }
+        let len = bytes[1] as usize;
+        let bad_length = Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: option_type,
+            length: len,
+        };
+        if len < 2 {
+            return Err(bad_length);
+        }
+        if bytes.len() < len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv4,
+                needed: len,
+                got: bytes.len(),
+            });
+        }
+        let bytes = &bytes[..len];
+
+        let option = match option_type {
+            130 => {
+                if len != 11 {
+                    return Err(bad_length);
+                }
+                Ipv4Option::Security(Ipv4Security {
+                    security: u16::from_be_bytes([bytes[2], bytes[3]]),
+                    compartments: u16::from_be_bytes([bytes[4], bytes[5]]),
+                    handling_restrictions: u16::from_be_bytes([bytes[6], bytes[7]]),
+                    transmission_control_code: u32::from_be_bytes([
+                        0, bytes[8], bytes[9], bytes[10],
+                    ]),
+                })
+            }
+            7 | 131 | 137 => {
+                let route = Ipv4Route::from_body(bytes).ok_or(bad_length)?;
+                match option_type {
+                    7 => Ipv4Option::RecordRoute(route),
+                    131 => Ipv4Option::LooseSourceRoute(route),
+                    _ => Ipv4Option::StrictSourceRoute(route),
+                }
+            }
+            136 | 148 => {
+                if len != 4 {
+                    return Err(bad_length);
+                }
+                let value = u16::from_be_bytes([bytes[2], bytes[3]]);
+                match option_type {
+                    136 => Ipv4Option::StreamId(value),
+                    _ => Ipv4Option::RouterAlert(value),
+                }
+            }
+            68 => match Ipv4Timestamp::from_body(bytes)? {
+                Some(timestamp) => Ipv4Option::Timestamp(timestamp),
+                None => Ipv4Option::Unknown {
+                    kind: option_type,
+                    data: bytes[2..].to_vec(),
+                },
+            },
+            kind => Ipv4Option::Unknown {
+                kind,
+                data: bytes[2..].to_vec(),
+            },
+        };
+        Ok((option, len))
+    }
+}
+
+/// Security option fields
+///
+/// Reference: RFC 791 Section 3.1 - Security
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ipv4Security {
+    pub security: u16,                  // 16 bits - Security level
+    pub compartments: u16,              // 16 bits - Compartments
+    pub handling_restrictions: u16,     // 16 bits - Handling restrictions
+    pub transmission_control_code: u32, // 24 bits - Transmission Control Code
+}
+
+/// Route data shared by the Record Route and Source Route options
+///
+/// `pointer` is the octet offset, counted from 1 at the option type, of the
+/// next address to process; the route is complete once it points past the
+/// last address.
+///
+/// Reference: RFC 791 Section 3.1 - Loose/Strict Source and Record Route, Record Route
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ipv4Route {
+    pub pointer: u8,
+    pub addresses: Vec<[u8; 4]>,
+}
+
+impl Ipv4Route {
+    /// Smallest legal pointer, addressing the first route slot
+    pub const FIRST_POINTER: u8 = 4;
+
+    /// Create a route over the given addresses, pointing at the first one
+    pub fn new(addresses: Vec<[u8; 4]>) -> Self {
+        Ipv4Route {
+            pointer: Self::FIRST_POINTER,
+            addresses,
+        }
+    }
+
+    /// Create an empty Record Route with room for `slots` addresses
+    pub fn with_slots(slots: usize) -> Self {
+        Self::new(alloc::vec![[0; 4]; slots])
+    }
+
+    /// Index into `addresses` referenced by the pointer, if it is in range
+    fn current_index(&self) -> Option<usize> {
+        let offset = (self.pointer as usize).checked_sub(Self::FIRST_POINTER as usize)?;
+        let index = offset / 4;
+        (offset.is_multiple_of(4) && index < self.addresses.len()).then_some(index)
+    }
+
+    /// Address referenced by the pointer (the next hop of a source route)
+    pub fn current(&self) -> Option<[u8; 4]> {
+        self.current_index().map(|index| self.addresses[index])
+    }
+
+    /// Check whether every slot of the route has been processed
+    pub fn is_complete(&self) -> bool {
+        self.pointer as usize > 3 + self.addresses.len() * 4
+    }
+
+    /// Record `address` in the slot referenced by the pointer and advance it
+    ///
+    /// Returns false, leaving the route unchanged, if no slot remains or the
+    /// pointer cannot advance past it.
+    pub fn record(&mut self, address: [u8; 4]) -> bool {
+        let (Some(index), Some(pointer)) = (self.current_index(), self.pointer.checked_add(4))
+        else {
+            return false;
+        };
+        self.addresses[index] = address;
+        self.pointer = pointer;
+        true
+    }
+
+    /// Parse the pointer and route data of a complete option, or `None` if the length is invalid
+    fn from_body(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 3 || !(bytes.len() - 3).is_multiple_of(4) {
+            return None;
+        }
+        let addresses = bytes[3..]
+            .chunks_exact(4)
+            .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]])
+            .collect();
+        Some(Ipv4Route {
+            pointer: bytes[2],
+            addresses,
+        })
+    }
+}
+
+/// Internet Timestamp option fields
+///
+/// `pointer` is the octet offset, counted from 1 at the option type, of the
+/// next free timestamp slot, and `overflow` counts the modules that could not
+/// register a timestamp because the option was full.
+///
+/// Reference: RFC 791 Section 3.1 - Internet Timestamp
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ipv4Timestamp {
+    pub pointer: u8,
+    pub overflow: u8, // 4 bits
+    pub data: TimestampData,
+}
+
+/// Timestamp slots, one variant per flag value
+#[derive(Debug, PartialEq, Clone)]
+pub enum TimestampData {
+    /// Flag 0 - timestamps only
+    TimestampsOnly(Vec<u32>),
+    /// Flag 1 - each timestamp preceded by the address of the registering module
+    WithAddresses(Vec<([u8; 4], u32)>),
+    /// Flag 3 - addresses prespecified by the sender, timestamped when matched
+    Prespecified(Vec<([u8; 4], u32)>),
+}
+
+impl Ipv4Timestamp {
+    /// Smallest legal pointer, addressing the first timestamp slot
+    pub const FIRST_POINTER: u8 = 5;
+
+    /// Create a timestamp option pointing at its first slot
+    pub fn new(data: TimestampData) -> Self {
+        Ipv4Timestamp {
+            pointer: Self::FIRST_POINTER,
+            overflow: 0,
+            data,
+        }
+    }
+
+    /// Get the flag value identifying the data format
+    pub fn flag(&self) -> u8 {
+        match self.data {
+            TimestampData::TimestampsOnly(_) => 0,
+            TimestampData::WithAddresses(_) => 1,
+            TimestampData::Prespecified(_) => 3,
+        }
+    }
+
+    /// Check whether every timestamp slot has been filled
+    pub fn is_full(&self) -> bool {
+        self.pointer as usize > 4 + self.data_len()
+    }
+
+    /// Length of the timestamp slots in bytes
+    fn data_len(&self) -> usize {
+        match &self.data {
+            TimestampData::TimestampsOnly(timestamps) => timestamps.len() * 4,
+            TimestampData::WithAddresses(entries) | TimestampData::Prespecified(entries) => {
+                entries.len() * 8
+            }
         }
     }
+
+    /// Write the pointer, overflow/flag octet and slots of a complete option
+    ///
+    /// An overflow count above 15 does not fit its 4 bits and fails with
+    /// [`Error::BadOptionLength`].
+    fn write_body(&self, buf: &mut [u8]) -> Result<(), Error> {
+        if self.overflow > 0x0F {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Ipv4,
+                kind: 68,
+                length: buf.len(),
+            });
+        }
+        buf[2] = self.pointer;
+        buf[3] = (self.overflow << 4) | self.flag();
+
+        let mut offset = 4;
+        match &self.data {
+            TimestampData::TimestampsOnly(timestamps) => {
+                for timestamp in timestamps {
+                    buf[offset..offset + 4].copy_from_slice(&timestamp.to_be_bytes());
+                    offset += 4;
+                }
+            }
+            TimestampData::WithAddresses(entries) | TimestampData::Prespecified(entries) => {
+                for (address, timestamp) in entries {
+                    buf[offset..offset + 4].copy_from_slice(address);
+                    buf[offset + 4..offset + 8].copy_from_slice(&timestamp.to_be_bytes());
+                    offset += 8;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a complete option, or `None` if the flag value is not defined
+    fn from_body(bytes: &[u8]) -> Result<Option<Self>, Error> {
+        let len = bytes.len();
+        let bad_length = Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: 68,
+            length: len,
+        };
+        if len < 4 {
+            return Err(bad_length);
+        }
+
+        let pointer = bytes[2];
+        let overflow = bytes[3] >> 4;
+        let slots = &bytes[4..];
+        let data = match bytes[3] & 0x0F {
+            0 => {
+                if !slots.len().is_multiple_of(4) {
+                    return Err(bad_length);
+                }
+                TimestampData::TimestampsOnly(
+                    slots
+                        .chunks_exact(4)
+                        .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                        .collect(),
+                )
+            }
+            flag @ (1 | 3) => {
+                if !slots.len().is_multiple_of(8) {
+                    return Err(bad_length);
+                }
+                let entries = slots
+                    .chunks_exact(8)
+                    .map(|chunk| {
+                        let address = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                        let timestamp =
+                            u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+                        (address, timestamp)
+                    })
+                    .collect();
+                if flag == 1 {
+                    TimestampData::WithAddresses(entries)
+                } else {
+                    TimestampData::Prespecified(entries)
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Ipv4Timestamp {
+            pointer,
+            overflow,
+            data,
+        }))
+    }
 }
 
 /// Collection of IPv4 options
 ///
 /// Handles proper padding to maintain 32-bit word alignment as required by RFC 791
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ipv4Options {
     pub options: Vec<Ipv4Option>,
 }
@@ -75,6 +495,20 @@ impl Ipv4Options {
         self.options.push(option);
     }
 
+    /// Options with the copied flag set, which are repeated in every fragment
+    ///
+    /// Reference: RFC 791 Section 3.2 - Fragmentation
+    pub fn copied(&self) -> Self {
+        Ipv4Options {
+            options: self
+                .options
+                .iter()
+                .filter(|option| option.is_copied())
+                .cloned()
+                .collect(),
+        }
+    }
+
     /// Get the total length of all options in bytes (including padding)
     pub fn total_length(&self) -> usize {
         let mut length = 0;
@@ -92,32 +526,79 @@ impl Ipv4Options {
     }
 
     /// Calculate number of 32-bit words needed for options (including padding)
+    ///
+    /// Saturates at `u8::MAX` for options far beyond [`MAX_OPTIONS_LEN`].
     pub fn words_needed(&self) -> u8 {
-        let total_bytes = self.total_length();
-        (total_bytes / 4) as u8
+        u8::try_from(self.total_length() / 4).unwrap_or(u8::MAX)
     }
 
-    /// Serialize options to bytes with proper padding
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+    /// Length of the serialized options in bytes (including padding)
+    pub fn serialized_len(&self) -> usize {
+        self.total_length()
+    }
+
+    /// Check that the padded options fit in [`MAX_OPTIONS_LEN`] bytes
+    fn check_space(&self) -> Result<(), Error> {
+        let length = self.serialized_len();
+        if length > MAX_OPTIONS_LEN {
+            return Err(Error::OptionSpaceExceeded {
+                layer: Layer::Ipv4,
+                length,
+                limit: MAX_OPTIONS_LEN,
+            });
+        }
+        Ok(())
+    }
+
+    /// Serialize options into `buf` with proper padding, returning the number of bytes written
+    ///
+    /// Fails with [`Error::OptionSpaceExceeded`] if the options do not fit in
+    /// the header, or if any option cannot be serialized.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.check_space()?;
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
 
         // Serialize all options
+        let mut len = 0;
         for option in &self.options {
-            bytes.extend_from_slice(&option.to_bytes());
+            len += option.write_to(&mut buf[len..])?;
         }
 
         // Add padding to reach 32-bit boundary
-        let remainder = bytes.len() % 4;
-        if remainder != 0 {
-            let padding_needed = 4 - remainder;
-            bytes.resize(bytes.len() + padding_needed, 0);
+        buf[len..total_len].fill(0);
+
+        Ok(total_len)
+    }
+
+    /// Serialize options to bytes with proper padding
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Add the serialized options, including padding, to a checksum without allocating
+    ///
+    /// Fails, like [`Ipv4Options::write_to`], if an option cannot be serialized.
+    pub(crate) fn add_to_checksum(&self, checksum: &mut Checksum) -> Result<(), Error> {
+        self.check_space()?;
+        let mut buf = [0u8; MAX_OPTION_LEN];
+        let mut len = 0;
+        for option in &self.options {
+            let option_len = option.write_to(&mut buf)?;
+            checksum.add_bytes(&buf[..option_len]);
+            len += option_len;
         }
 
-        bytes
+        // Padding keeps anything added afterwards 16-bit aligned
+        checksum.add_bytes(&[0; 3][..self.serialized_len() - len]);
+        Ok(())
     }
 
     /// Deserialize options from bytes
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         let mut options = Vec::new();
         let mut pos = 0;
 
@@ -145,21 +626,24 @@ impl Default for Ipv4Options {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_option_end_of_list() {
         let option = Ipv4Option::EndOfOptionsList;
         assert_eq!(0, option.option_type());
         assert_eq!(1, option.length());
-        assert_eq!(vec![0], option.to_bytes());
+        assert_eq!(vec![0], option.to_bytes().unwrap());
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_option_no_operation() {
         let option = Ipv4Option::NoOperation;
         assert_eq!(1, option.option_type());
         assert_eq!(1, option.length());
-        assert_eq!(vec![1], option.to_bytes());
+        assert_eq!(vec![1], option.to_bytes().unwrap());
     }
 
     #[test]
@@ -179,9 +663,299 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_option_from_bytes_unknown() {
-        let bytes = [255];
-        assert!(Ipv4Option::from_bytes(&bytes).is_err());
+        let bytes = [0x9E, 4, 0xAB, 0xCD, 1]; // Type 158, trailing byte not consumed
+        let (actual, consumed) = Ipv4Option::from_bytes(&bytes).unwrap();
+
+        let expect = Ipv4Option::Unknown {
+            kind: 0x9E,
+            data: vec![0xAB, 0xCD],
+        };
+        assert_eq!(expect, actual);
+        assert_eq!(4, consumed);
+        assert_eq!(bytes[..4], actual.to_bytes().unwrap()[..]);
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_missing_length() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv4,
+            needed: 2,
+            got: 1,
+        });
+        let actual = Ipv4Option::from_bytes(&[255]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_length_below_minimum() {
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: 0x9E,
+            length: 1,
+        });
+        let actual = Ipv4Option::from_bytes(&[0x9E, 1, 0, 0]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_security() {
+        let option = Ipv4Option::Security(Ipv4Security {
+            security: 0xF135, // Confidential
+            compartments: 0,
+            handling_restrictions: 0x4142,
+            transmission_control_code: 0x0043_4445,
+        });
+
+        let expect = vec![130, 11, 0xF1, 0x35, 0, 0, 0x41, 0x42, 0x43, 0x44, 0x45];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_record_route() {
+        let mut route = Ipv4Route::with_slots(2);
+        assert!(route.record([10, 0, 0, 1]));
+
+        let option = Ipv4Option::RecordRoute(route);
+        let expect = vec![
+            7, 11, 8, // Type, Length, Pointer at the second slot
+            10, 0, 0, 1, // Recorded address
+            0, 0, 0, 0, // Empty slot
+        ];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_route_pointer_handling() {
+        let mut route = Ipv4Route::new(vec![[10, 0, 0, 1], [10, 0, 0, 2]]);
+        assert_eq!(Some([10, 0, 0, 1]), route.current());
+        assert!(!route.is_complete());
+
+        // Each hop replaces the address it routed to with its own
+        assert!(route.record([192, 168, 0, 1]));
+        assert_eq!(Some([10, 0, 0, 2]), route.current());
+        assert!(route.record([192, 168, 0, 2]));
+
+        assert!(route.is_complete());
+        assert_eq!(None, route.current());
+        assert!(!route.record([192, 168, 0, 3]));
+        assert_eq!(12, route.pointer);
+        assert_eq!(vec![[192, 168, 0, 1], [192, 168, 0, 2]], route.addresses);
+    }
+
+    #[test]
+    fn test_ipv4_route_misaligned_pointer() {
+        let mut route = Ipv4Route::new(vec![[10, 0, 0, 1]]);
+        route.pointer = 5;
+        assert_eq!(None, route.current());
+        assert!(!route.record([10, 0, 0, 9]));
+    }
+
+    #[test]
+    fn test_ipv4_route_pointer_overflow() {
+        // 63 slots fill the 255-byte option, so the last pointer is 252
+        let mut route = Ipv4Route::with_slots(63);
+        route.pointer = 252;
+        assert_eq!(Some([0; 4]), route.current());
+        assert!(!route.record([10, 0, 0, 9]));
+        assert_eq!(252, route.pointer);
+    }
+
+    #[test]
+    fn test_ipv4_option_write_oversized() {
+        let option = Ipv4Option::Unknown {
+            kind: 0x99,
+            data: vec![0; 300],
+        };
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: 0x99,
+            length: 302,
+        });
+        let actual = option.write_to(&mut [0; 302]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_source_routes() {
+        let route = Ipv4Route::new(vec![[10, 0, 0, 1]]);
+
+        let loose = Ipv4Option::LooseSourceRoute(route.clone())
+            .to_bytes()
+            .unwrap();
+        assert_eq!(vec![131, 7, 4, 10, 0, 0, 1], loose);
+
+        let strict = Ipv4Option::StrictSourceRoute(route).to_bytes().unwrap();
+        assert_eq!(vec![137, 7, 4, 10, 0, 0, 1], strict);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_stream_id_and_router_alert() {
+        assert_eq!(
+            vec![136, 4, 0x12, 0x34],
+            Ipv4Option::StreamId(0x1234).to_bytes().unwrap()
+        );
+        assert_eq!(
+            vec![148, 4, 0, 0],
+            Ipv4Option::RouterAlert(0).to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_timestamp_flags() {
+        let mut timestamp = Ipv4Timestamp::new(TimestampData::TimestampsOnly(vec![1000, 0]));
+        timestamp.pointer = 9;
+        let expect = vec![68, 12, 9, 0x00, 0, 0, 0x03, 0xE8, 0, 0, 0, 0];
+        assert_eq!(expect, Ipv4Option::Timestamp(timestamp).to_bytes().unwrap());
+
+        let timestamp = Ipv4Timestamp::new(TimestampData::WithAddresses(vec![([10, 0, 0, 1], 7)]));
+        assert_eq!(1, timestamp.flag());
+        let expect = vec![68, 12, 5, 0x01, 10, 0, 0, 1, 0, 0, 0, 7];
+        assert_eq!(expect, Ipv4Option::Timestamp(timestamp).to_bytes().unwrap());
+
+        let mut timestamp =
+            Ipv4Timestamp::new(TimestampData::Prespecified(vec![([10, 0, 0, 2], 0)]));
+        timestamp.overflow = 15;
+        let expect = vec![68, 12, 5, 0xF3, 10, 0, 0, 2, 0, 0, 0, 0];
+        assert_eq!(expect, Ipv4Option::Timestamp(timestamp).to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_ipv4_timestamp_overflow_out_of_range() {
+        let mut timestamp = Ipv4Timestamp::new(TimestampData::TimestampsOnly(Vec::new()));
+        timestamp.overflow = 16;
+        let mut buf = [0u8; 4];
+
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv4,
+            kind: 68,
+            length: 4,
+        });
+        let actual = Ipv4Option::Timestamp(timestamp).write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_timestamp_is_full() {
+        let mut timestamp = Ipv4Timestamp::new(TimestampData::TimestampsOnly(vec![0]));
+        assert!(!timestamp.is_full());
+        timestamp.pointer = 9;
+        assert!(timestamp.is_full());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_option_from_bytes_round_trip() {
+        let mut overflowed = Ipv4Timestamp::new(TimestampData::WithAddresses(vec![
+            ([10, 0, 0, 1], 1),
+            ([10, 0, 0, 2], 2),
+        ]));
+        overflowed.pointer = 21;
+        overflowed.overflow = 3;
+
+        let options = [
+            Ipv4Option::Security(Ipv4Security {
+                security: 0x6BC5,
+                compartments: 1,
+                handling_restrictions: 2,
+                transmission_control_code: 0x00AB_CDEF,
+            }),
+            Ipv4Option::LooseSourceRoute(Ipv4Route::new(vec![[10, 0, 0, 1], [10, 0, 0, 2]])),
+            Ipv4Option::RecordRoute(Ipv4Route::with_slots(9)),
+            Ipv4Option::StreamId(0xBEEF),
+            Ipv4Option::StrictSourceRoute(Ipv4Route::new(vec![[172, 16, 0, 1]])),
+            Ipv4Option::Timestamp(Ipv4Timestamp::new(TimestampData::TimestampsOnly(vec![
+                0, 0, 0,
+            ]))),
+            Ipv4Option::Timestamp(overflowed),
+            Ipv4Option::Timestamp(Ipv4Timestamp::new(TimestampData::Prespecified(vec![(
+                [192, 0, 2, 1],
+                0,
+            )]))),
+            Ipv4Option::RouterAlert(0),
+            Ipv4Option::Unknown {
+                kind: 0x19,
+                data: vec![],
+            },
+        ];
+        for expect in options {
+            let bytes = expect.to_bytes().unwrap();
+            let (actual, consumed) = Ipv4Option::from_bytes(&bytes).unwrap();
+            assert_eq!(expect, actual);
+            assert_eq!(bytes.len(), consumed);
+        }
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_bad_lengths() {
+        let cases: [&[u8]; 4] = [
+            &[130, 10, 0, 0, 0, 0, 0, 0, 0, 0],    // Security must be 11
+            &[7, 6, 4, 10, 0, 0],                  // Route data not a multiple of 4
+            &[148, 3, 0],                          // Router Alert must be 4
+            &[68, 10, 5, 0x01, 10, 0, 0, 1, 0, 0], // Address/timestamp pair cut short
+        ];
+        for bytes in cases {
+            let expect = Err(Error::BadOptionLength {
+                layer: Layer::Ipv4,
+                kind: bytes[0],
+                length: bytes[1] as usize,
+            });
+            let actual = Ipv4Option::from_bytes(bytes);
+            assert_eq!(expect, actual);
+        }
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_timestamp_undefined_flag() {
+        let bytes = [68, 8, 5, 0x02, 0, 0, 0, 0];
+        let (actual, _) = Ipv4Option::from_bytes(&bytes).unwrap();
+
+        let expect = Ipv4Option::Unknown {
+            kind: 68,
+            data: vec![5, 0x02, 0, 0, 0, 0],
+        };
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_truncated_route() {
+        let bytes = [131, 11, 4, 10, 0, 0, 1];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv4,
+            needed: 11,
+            got: 7,
+        });
+        let actual = Ipv4Option::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv4_options_from_bytes_mixed() {
+        let mut expect = Ipv4Options::new();
+        expect.add(Ipv4Option::RouterAlert(0));
+        expect.add(Ipv4Option::NoOperation);
+        expect.add(Ipv4Option::RecordRoute(Ipv4Route::with_slots(1)));
+        expect.add(Ipv4Option::EndOfOptionsList);
+        let bytes = expect.to_bytes().unwrap();
+        assert_eq!(16, bytes.len());
+
+        let actual = Ipv4Options::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_option_from_bytes_empty() {
+        let actual = Ipv4Option::from_bytes(&[]);
+        assert!(matches!(actual, Err(Error::Truncated { .. })));
     }
 
     #[test]
@@ -215,13 +989,14 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_options_padding() {
         let mut options = Ipv4Options::new();
 
         // Add options that require padding
         options.add(Ipv4Option::EndOfOptionsList);
 
-        let bytes = options.to_bytes();
+        let bytes = options.to_bytes().unwrap();
         assert_eq!(4, bytes.len()); // 1 option byte + 3 padding bytes
         assert_eq!(0, bytes[0]); // EOL option
         assert_eq!(0, bytes[1]); // Padding
@@ -230,6 +1005,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_ipv4_options_no_padding_needed() {
         let mut options = Ipv4Options::new();
 
@@ -239,7 +1015,7 @@ mod tests {
         options.add(Ipv4Option::NoOperation);
         options.add(Ipv4Option::EndOfOptionsList);
 
-        let bytes = options.to_bytes();
+        let bytes = options.to_bytes().unwrap();
         assert_eq!(4, bytes.len()); // Exactly 4 bytes, no padding
         assert_eq!(1, bytes[0]); // NOP
         assert_eq!(1, bytes[1]); // NOP
@@ -247,6 +1023,40 @@ mod tests {
         assert_eq!(0, bytes[3]); // EOL
     }
 
+    #[test]
+    fn test_ipv4_options_write_to() {
+        let mut options = Ipv4Options::new();
+        options.add(Ipv4Option::NoOperation);
+        options.add(Ipv4Option::EndOfOptionsList);
+
+        let mut buf = [0xFFu8; 8];
+        let len = options.write_to(&mut buf).unwrap();
+        assert_eq!(4, len);
+        assert_eq!([1, 0, 0, 0], buf[..4]);
+
+        let expect = Err(Error::BufferTooSmall { needed: 4, got: 3 });
+        let actual = options.write_to(&mut buf[..3]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv4_options_write_exceeds_option_space() {
+        let mut options = Ipv4Options::new();
+        options.add(Ipv4Option::RecordRoute(Ipv4Route::with_slots(10)));
+
+        let expect = Err(Error::OptionSpaceExceeded {
+            layer: Layer::Ipv4,
+            length: 44,
+            limit: MAX_OPTIONS_LEN,
+        });
+        let actual = options.write_to(&mut [0; 44]);
+        assert_eq!(expect, actual);
+
+        let mut checksum = Checksum::new();
+        let actual = options.add_to_checksum(&mut checksum);
+        assert_eq!(expect.map(|_| ()), actual);
+    }
+
     #[test]
     fn test_ipv4_options_from_bytes() {
         // Test parsing options with padding
@@ -0,0 +1,627 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::Ipv4Packet;
+use super::header::{IPV4_HEADER_LEN, Ipv4Header};
+use super::options::Ipv4Options;
+use crate::error::Error;
+use crate::network::ip_protocol::IpProtocol;
+
+/// Largest payload a datagram without options can carry within the 16-bit total length
+const MAX_PAYLOAD_LEN: usize = u16::MAX as usize - IPV4_HEADER_LEN;
+
+/// How data in overlapping fragments is resolved
+///
+/// Hosts disagree on which copy of overlapping data to keep, which is what
+/// IDS evasion techniques exploit (RFC 1858, RFC 5722 for IPv6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Keep the data received first
+    FirstWins,
+    /// Overwrite with the data received last
+    LastWins,
+    /// Discard the whole datagram
+    Reject,
+}
+
+/// Fields identifying the fragments of one datagram
+///
+/// Reference: RFC 791 Section 3.2 - Fragmentation and Reassembly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FragmentKey {
+    pub source_address: [u8; 4],
+    pub destination_address: [u8; 4],
+    pub protocol: IpProtocol,
+    pub identification: u16,
+}
+
+impl FragmentKey {
+    pub fn new(header: &Ipv4Header) -> Self {
+        FragmentKey {
+            source_address: header.source_address,
+            destination_address: header.destination_address,
+            protocol: header.protocol,
+            identification: header.identification,
+        }
+    }
+}
+
+/// Datagram rebuilt from its fragments
+///
+/// The header and options are those of the first fragment, with MF and the
+/// fragment offset cleared and the IHL, total length, and checksum updated.
+#[derive(Debug, PartialEq)]
+pub struct ReassembledPacket {
+    pub header: Ipv4Header,
+    pub options: Ipv4Options,
+    pub payload: Vec<u8>,
+}
+
+impl ReassembledPacket {
+    /// Fails with [`Error::BadTotalLength`] if the header and payload exceed 65535 bytes
+    fn new(mut header: Ipv4Header, options: Ipv4Options, payload: Vec<u8>) -> Result<Self, Error> {
+        header.flags.more_fragments = false;
+        header.fragment_offset = 0;
+
+        let mut packet = Ipv4Packet {
+            header,
+            options,
+            payload: &payload,
+        };
+        packet.update_ihl();
+        packet.update_total_length()?;
+        packet.header.header_checksum = packet.calculate_header_checksum()?;
+
+        let Ipv4Packet {
+            header, options, ..
+        } = packet;
+        Ok(ReassembledPacket {
+            header,
+            options,
+            payload,
+        })
+    }
+
+    /// Borrow the datagram as an [`Ipv4Packet`]
+    pub fn packet(&self) -> Ipv4Packet<'_> {
+        Ipv4Packet {
+            header: self.header.clone(),
+            options: self.options.clone(),
+            payload: &self.payload,
+        }
+    }
+}
+
+/// Fragments received so far for one datagram
+#[derive(Debug)]
+struct FragmentBuffer {
+    /// Caller time at which the first fragment arrived
+    first_seen: u64,
+    /// Header and options of the fragment at offset 0
+    first: Option<(Ipv4Header, Ipv4Options)>,
+    /// Payload bytes, sized to the furthest fragment end seen
+    data: Vec<u8>,
+    /// Sorted, disjoint byte ranges of `data` that have been received
+    received: Vec<(usize, usize)>,
+    /// Payload length, known once the MF=0 fragment arrives
+    total_len: Option<usize>,
+}
+
+impl FragmentBuffer {
+    fn new(now: u64) -> Self {
+        FragmentBuffer {
+            first_seen: now,
+            first: None,
+            data: Vec::new(),
+            received: Vec::new(),
+            total_len: None,
+        }
+    }
+
+    /// Largest payload the total length allows behind the first fragment's header
+    fn max_payload_len(&self) -> usize {
+        match &self.first {
+            Some((_, options)) => u16::MAX as usize - IPV4_HEADER_LEN - options.serialized_len(),
+            None => MAX_PAYLOAD_LEN,
+        }
+    }
+
+    /// Check that a fragment agrees with the datagram length received so far
+    fn validate(&self, offset: usize, end: usize, more: bool) -> Result<(), Error> {
+        let consistent = end <= self.max_payload_len()
+            && match self.total_len {
+                Some(total) => end <= total && (more || end == total),
+                None => more || end >= self.data.len(),
+            };
+        if !consistent {
+            return Err(Error::BadFragment {
+                offset,
+                length: end - offset,
+            });
+        }
+        Ok(())
+    }
+
+    fn overlaps(&self, offset: usize, end: usize) -> bool {
+        self.received
+            .iter()
+            .any(|&(start, stop)| start < end && offset < stop)
+    }
+
+    fn insert(&mut self, packet: &Ipv4Packet<'_>, offset: usize, policy: OverlapPolicy) {
+        let end = offset + packet.payload.len();
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+
+        match policy {
+            OverlapPolicy::LastWins | OverlapPolicy::Reject => {
+                self.data[offset..end].copy_from_slice(packet.payload);
+            }
+            OverlapPolicy::FirstWins => {
+                // Copy only into the holes between received ranges
+                let mut cursor = offset;
+                for &(start, stop) in &self.received {
+                    if start >= end {
+                        break;
+                    }
+                    if start > cursor {
+                        self.data[cursor..start]
+                            .copy_from_slice(&packet.payload[cursor - offset..start - offset]);
+                    }
+                    cursor = cursor.max(stop);
+                }
+                if cursor < end {
+                    self.data[cursor..end].copy_from_slice(&packet.payload[cursor - offset..]);
+                }
+            }
+        }
+
+        if offset == 0 && (self.first.is_none() || policy == OverlapPolicy::LastWins) {
+            self.first = Some((packet.header.clone(), packet.options.clone()));
+        }
+        if !packet.header.flags.more_fragments {
+            self.total_len = Some(end);
+        }
+
+        self.received.push((offset, end));
+        self.received.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(self.received.len());
+        for &(start, stop) in &self.received {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(stop),
+                _ => merged.push((start, stop)),
+            }
+        }
+        self.received = merged;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.total_len
+            .is_some_and(|total| self.received.as_slice() == [(0, total)])
+    }
+}
+
+/// IPv4 fragment reassembler
+///
+/// Fragments are grouped by [`FragmentKey`] and may arrive in any order. The
+/// caller supplies the current time with every call, in any unit, and a
+/// datagram is dropped once `timeout` has elapsed since its first fragment.
+/// `memory_limit` bounds the payload bytes buffered across all datagrams.
+///
+/// Reference: RFC 791 Section 3.2 - Fragmentation and Reassembly
+#[derive(Debug)]
+pub struct Reassembler {
+    policy: OverlapPolicy,
+    memory_limit: usize,
+    timeout: u64,
+    buffers: BTreeMap<FragmentKey, FragmentBuffer>,
+    memory_used: usize,
+}
+
+impl Reassembler {
+    pub fn new(policy: OverlapPolicy, memory_limit: usize, timeout: u64) -> Self {
+        Reassembler {
+            policy,
+            memory_limit,
+            timeout,
+            buffers: BTreeMap::new(),
+            memory_used: 0,
+        }
+    }
+
+    /// Payload bytes currently buffered
+    pub fn memory_used(&self) -> usize {
+        self.memory_used
+    }
+
+    /// Number of datagrams waiting for more fragments
+    pub fn pending(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Drop datagrams whose timeout has elapsed, returning how many were dropped
+    pub fn expire(&mut self, now: u64) -> usize {
+        let before = self.buffers.len();
+        let timeout = self.timeout;
+        let mut freed = 0;
+        self.buffers.retain(|_, buffer| {
+            let alive = now.saturating_sub(buffer.first_seen) < timeout;
+            if !alive {
+                freed += buffer.data.len();
+            }
+            alive
+        });
+        self.memory_used -= freed;
+        before - self.buffers.len()
+    }
+
+    /// Add a fragment, returning the datagram once every fragment has arrived
+    ///
+    /// A packet that is not a fragment (MF clear, offset 0) is returned
+    /// immediately. Errors leave previously buffered fragments in place,
+    /// except for [`Error::FragmentOverlap`], which discards the datagram.
+    pub fn insert(
+        &mut self,
+        packet: &Ipv4Packet<'_>,
+        now: u64,
+    ) -> Result<Option<ReassembledPacket>, Error> {
+        self.expire(now);
+
+        let header = &packet.header;
+        let more = header.flags.more_fragments;
+        let offset = header.fragment_offset as usize * 8;
+        let length = packet.payload.len();
+        let end = offset + length;
+
+        if !more && offset == 0 {
+            return Ok(Some(ReassembledPacket::new(
+                header.clone(),
+                packet.options.clone(),
+                packet.payload.to_vec(),
+            )?));
+        }
+        // Every fragment but the last carries a non-empty multiple of 8 bytes
+        if end > MAX_PAYLOAD_LEN || (more && (length == 0 || !length.is_multiple_of(8))) {
+            return Err(Error::BadFragment { offset, length });
+        }
+
+        let key = FragmentKey::new(header);
+        // Options in the first fragment leave less room for the payload
+        if offset == 0 {
+            let furthest = self
+                .buffers
+                .get(&key)
+                .map_or(end, |buffer| buffer.data.len().max(end));
+            if furthest > u16::MAX as usize - packet.header_len() {
+                return Err(Error::BadFragment { offset, length });
+            }
+        }
+
+        let mut stored = 0;
+        if let Some(buffer) = self.buffers.get(&key) {
+            buffer.validate(offset, end, more)?;
+            if self.policy == OverlapPolicy::Reject && buffer.overlaps(offset, end) {
+                self.remove(&key);
+                return Err(Error::FragmentOverlap { offset, length });
+            }
+            stored = buffer.data.len();
+        }
+
+        let needed = self.memory_used + end.saturating_sub(stored);
+        if needed > self.memory_limit {
+            return Err(Error::ReassemblyLimit {
+                needed,
+                limit: self.memory_limit,
+            });
+        }
+        self.memory_used = needed;
+
+        let buffer = self
+            .buffers
+            .entry(key)
+            .or_insert_with(|| FragmentBuffer::new(now));
+        buffer.insert(packet, offset, self.policy);
+        if !buffer.is_complete() {
+            return Ok(None);
+        }
+
+        let buffer = self.remove(&key).expect("complete datagram is buffered");
+        let (header, options) = buffer.first.expect("complete datagram has offset 0");
+        ReassembledPacket::new(header, options, buffer.data).map(Some)
+    }
+
+    fn remove(&mut self, key: &FragmentKey) -> Option<FragmentBuffer> {
+        let buffer = self.buffers.remove(key)?;
+        self.memory_used -= buffer.data.len();
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::network::ipv4::options::{Ipv4Option, Ipv4Route, Ipv4Security};
+    use alloc::vec;
+
+    const SRC: [u8; 4] = [10, 0, 0, 1];
+    const DST: [u8; 4] = [10, 0, 0, 2];
+
+    /// Fragment with the given payload at a byte offset
+    fn fragment(id: u16, offset: usize, more: bool, payload: &[u8]) -> Ipv4Packet<'_> {
+        let mut packet = Ipv4Packet::new(SRC, DST, IpProtocol::Udp, payload);
+        packet.header.identification = id;
+        packet.header.fragment_offset = (offset / 8) as u16;
+        packet.header.flags.more_fragments = more;
+        packet
+    }
+
+    fn reassembler(policy: OverlapPolicy) -> Reassembler {
+        Reassembler::new(policy, 65536, 30)
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reassemble_in_order() {
+        let payload: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut packet = Ipv4Packet::new(SRC, DST, IpProtocol::Udp, &payload);
+        packet.header.identification = 7;
+        let fragments = packet.fragment(576).unwrap();
+
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        assert_eq!(None, reassembler.insert(&fragments[0], 0).unwrap());
+        assert_eq!(1, reassembler.pending());
+
+        let actual = reassembler.insert(&fragments[1], 1).unwrap().unwrap();
+        assert_eq!(payload, actual.payload);
+        assert_eq!(1020, actual.header.total_length);
+        assert!(!actual.header.flags.more_fragments);
+        assert!(actual.packet().verify_checksum().unwrap().is_valid());
+        assert_eq!(0, reassembler.pending());
+        assert_eq!(0, reassembler.memory_used());
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let payload: Vec<u8> = (0..100).collect();
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+
+        let last = fragment(1, 96, false, &payload[96..]);
+        let middle = fragment(1, 48, true, &payload[48..96]);
+        let first = fragment(1, 0, true, &payload[..48]);
+        assert_eq!(None, reassembler.insert(&last, 0).unwrap());
+        assert_eq!(None, reassembler.insert(&middle, 0).unwrap());
+
+        let actual = reassembler.insert(&first, 0).unwrap().unwrap();
+        assert_eq!(payload, actual.payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reassemble_keeps_first_fragment_options() {
+        let payload = [0x5A; 64];
+        let mut packet = Ipv4Packet::new(SRC, DST, IpProtocol::Udp, &payload);
+        packet.options.add(Ipv4Option::Security(Ipv4Security {
+            security: 0x789A,
+            compartments: 0,
+            handling_restrictions: 0,
+            transmission_control_code: 0,
+        }));
+        packet
+            .options
+            .add(Ipv4Option::RecordRoute(Ipv4Route::with_slots(1)));
+        let fragments = packet.fragment(60).unwrap();
+        assert!(fragments.len() > 1);
+
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        let mut actual = None;
+        for fragment in fragments.iter().rev() {
+            actual = reassembler.insert(fragment, 0).unwrap();
+        }
+        let actual = actual.unwrap();
+        assert_eq!(packet.options, actual.options);
+        assert_eq!(&payload[..], &actual.payload[..]);
+    }
+
+    #[test]
+    fn test_reassemble_not_fragmented() {
+        let packet = Ipv4Packet::new(SRC, DST, IpProtocol::Udp, b"Test");
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+
+        let actual = reassembler.insert(&packet, 0).unwrap().unwrap();
+        assert_eq!(b"Test", &actual.payload[..]);
+        assert_eq!(0, reassembler.pending());
+    }
+
+    #[test]
+    fn test_reassemble_separates_datagrams() {
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        assert_eq!(
+            None,
+            reassembler
+                .insert(&fragment(1, 0, true, &[1; 8]), 0)
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            reassembler
+                .insert(&fragment(2, 0, true, &[2; 8]), 0)
+                .unwrap()
+        );
+        assert_eq!(2, reassembler.pending());
+
+        let actual = reassembler
+            .insert(&fragment(2, 8, false, &[2; 4]), 0)
+            .unwrap();
+        assert_eq!(vec![2; 12], actual.unwrap().payload);
+        assert_eq!(1, reassembler.pending());
+    }
+
+    #[test]
+    fn test_overlap_first_wins() {
+        let mut reassembler = reassembler(OverlapPolicy::FirstWins);
+        reassembler
+            .insert(&fragment(1, 8, true, &[1; 8]), 0)
+            .unwrap();
+        reassembler
+            .insert(&fragment(1, 0, true, &[2; 16]), 0)
+            .unwrap();
+
+        let actual = reassembler
+            .insert(&fragment(1, 16, false, &[3; 4]), 0)
+            .unwrap();
+        let expect = [[2; 8].as_slice(), &[1; 8], &[3; 4]].concat();
+        assert_eq!(expect, actual.unwrap().payload);
+    }
+
+    #[test]
+    fn test_overlap_last_wins() {
+        let mut reassembler = reassembler(OverlapPolicy::LastWins);
+        reassembler
+            .insert(&fragment(1, 8, true, &[1; 8]), 0)
+            .unwrap();
+        reassembler
+            .insert(&fragment(1, 0, true, &[2; 16]), 0)
+            .unwrap();
+
+        let actual = reassembler
+            .insert(&fragment(1, 16, false, &[3; 4]), 0)
+            .unwrap();
+        let expect = [[2; 16].as_slice(), &[3; 4]].concat();
+        assert_eq!(expect, actual.unwrap().payload);
+    }
+
+    #[test]
+    fn test_overlap_reject() {
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        reassembler
+            .insert(&fragment(1, 8, true, &[1; 8]), 0)
+            .unwrap();
+
+        let expect = Err(Error::FragmentOverlap {
+            offset: 0,
+            length: 16,
+        });
+        let actual = reassembler.insert(&fragment(1, 0, true, &[2; 16]), 0);
+        assert_eq!(expect, actual);
+        assert_eq!(0, reassembler.pending());
+        assert_eq!(0, reassembler.memory_used());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let mut reassembler = Reassembler::new(OverlapPolicy::Reject, 24, 30);
+        reassembler
+            .insert(&fragment(1, 0, true, &[0; 16]), 0)
+            .unwrap();
+        assert_eq!(16, reassembler.memory_used());
+
+        let expect = Err(Error::ReassemblyLimit {
+            needed: 32,
+            limit: 24,
+        });
+        let actual = reassembler.insert(&fragment(2, 0, true, &[0; 16]), 0);
+        assert_eq!(expect, actual);
+        assert_eq!(1, reassembler.pending());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        reassembler
+            .insert(&fragment(1, 0, true, &[0; 8]), 100)
+            .unwrap();
+
+        assert_eq!(0, reassembler.expire(129));
+        assert_eq!(1, reassembler.pending());
+
+        // The final fragment arrives too late and starts a new datagram
+        let actual = reassembler
+            .insert(&fragment(1, 8, false, &[0; 8]), 130)
+            .unwrap();
+        assert_eq!(None, actual);
+        assert_eq!(16, reassembler.memory_used()); // Buffered up to the fragment end
+        assert_eq!(1, reassembler.expire(160));
+        assert_eq!(0, reassembler.memory_used());
+    }
+
+    #[test]
+    fn test_bad_fragment_unaligned() {
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+
+        let expect = Err(Error::BadFragment {
+            offset: 0,
+            length: 10,
+        });
+        let actual = reassembler.insert(&fragment(1, 0, true, &[0; 10]), 0);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_bad_fragment_beyond_end() {
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+        reassembler
+            .insert(&fragment(1, 8, false, &[0; 8]), 0)
+            .unwrap();
+
+        let expect = Err(Error::BadFragment {
+            offset: 16,
+            length: 8,
+        });
+        let actual = reassembler.insert(&fragment(1, 16, true, &[0; 8]), 0);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_bad_fragment_oversized() {
+        let payload = [0; 16];
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+
+        let actual = reassembler.insert(&fragment(1, 65528, false, &payload), 0);
+        assert!(matches!(actual, Err(Error::BadFragment { .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_bad_fragment_oversized_with_first_fragment_options() {
+        let payload = vec![0x5A; 65507];
+        let with_options = |id, offset, more, payload| {
+            let mut packet = fragment(id, offset, more, payload);
+            packet
+                .options
+                .add(Ipv4Option::RecordRoute(Ipv4Route::with_slots(1)));
+            packet
+        };
+        let mut reassembler = reassembler(OverlapPolicy::Reject);
+
+        // A 28-byte header leaves room for 65507 payload bytes
+        let first = with_options(1, 0, true, &payload[..65496]);
+        assert_eq!(28, first.header_len());
+        assert_eq!(None, reassembler.insert(&first, 0).unwrap());
+        let last = fragment(1, 65496, false, &payload[65496..]);
+        let actual = reassembler.insert(&last, 0).unwrap().unwrap();
+        assert_eq!(65535, actual.header.total_length);
+
+        // One more byte fits a bare header but not the first fragment's
+        let expect = Err(Error::BadFragment {
+            offset: 65496,
+            length: 12,
+        });
+        let first = with_options(2, 0, true, &payload[..8]);
+        reassembler.insert(&first, 0).unwrap();
+        let actual = reassembler.insert(&fragment(2, 65496, false, &[0; 12]), 0);
+        assert_eq!(expect, actual);
+
+        // The same holds when the first fragment arrives last
+        let expect = Err(Error::BadFragment {
+            offset: 0,
+            length: 8,
+        });
+        reassembler
+            .insert(&fragment(3, 65496, false, &[0; 12]), 0)
+            .unwrap();
+        let actual = reassembler.insert(&with_options(3, 0, true, &payload[..8]), 0);
+        assert_eq!(expect, actual);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::error::Error;
+use crate::network::ip_protocol::IpProtocol;
+
+use super::fragment::FragmentHeader;
+use super::options::Ipv6Options;
+use super::routing::RoutingHeader;
+
+/// IPv6 extension header
+///
+/// The Next Header field of each extension header is not stored; it is derived
+/// from the following header when the chain is serialized.
+///
+/// Reference: RFC 8200 Section 4 - IPv6 Extension Headers
+#[derive(Debug, PartialEq, Clone)]
+pub enum Ipv6ExtensionHeader {
+    HopByHop(Ipv6Options),
+    Routing(RoutingHeader),
+    Fragment(FragmentHeader),
+    DestinationOptions(Ipv6Options),
+}
+
+impl Ipv6ExtensionHeader {
+    /// Check whether a next header value is an extension header parsed by this module
+    ///
+    /// Only the headers with a variant here are supported; other extension
+    /// headers such as ESP and AH end the parsed header chain.
+    pub fn is_supported(next_header: IpProtocol) -> bool {
+        matches!(
+            next_header,
+            IpProtocol::HopByHop
+                | IpProtocol::Ipv6Routing
+                | IpProtocol::Ipv6Fragment
+                | IpProtocol::Ipv6DestinationOptions
+        )
+    }
+
+    /// Get the next header value identifying this extension header
+    pub fn header_type(&self) -> IpProtocol {
+        match self {
+            Ipv6ExtensionHeader::HopByHop(_) => IpProtocol::HopByHop,
+            Ipv6ExtensionHeader::Routing(_) => IpProtocol::Ipv6Routing,
+            Ipv6ExtensionHeader::Fragment(_) => IpProtocol::Ipv6Fragment,
+            Ipv6ExtensionHeader::DestinationOptions(_) => IpProtocol::Ipv6DestinationOptions,
+        }
+    }
+
+    /// Final destination of the packet if this is a routing header with segments left
+    pub fn final_destination(&self) -> Option<[u8; 16]> {
+        match self {
+            Ipv6ExtensionHeader::Routing(routing) => routing.final_destination(),
+            _ => None,
+        }
+    }
+
+    /// Length of the serialized header in bytes
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            Ipv6ExtensionHeader::HopByHop(options)
+            | Ipv6ExtensionHeader::DestinationOptions(options) => options.serialized_len(),
+            Ipv6ExtensionHeader::Routing(routing) => routing.serialized_len(),
+            Ipv6ExtensionHeader::Fragment(fragment) => fragment.serialized_len(),
+        }
+    }
+
+    /// Serialize the header into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8], next_header: IpProtocol) -> Result<usize, Error> {
+        match self {
+            Ipv6ExtensionHeader::HopByHop(options)
+            | Ipv6ExtensionHeader::DestinationOptions(options) => {
+                options.write_to(buf, next_header)
+            }
+            Ipv6ExtensionHeader::Routing(routing) => routing.write_to(buf, next_header),
+            Ipv6ExtensionHeader::Fragment(fragment) => fragment.write_to(buf, next_header),
+        }
+    }
+
+    /// Parse the extension header identified by `header_type`
+    ///
+    /// Returns (header, next_header, bytes_consumed), or `None` if `header_type`
+    /// is not a supported extension header.
+    pub fn from_bytes(
+        header_type: IpProtocol,
+        bytes: &[u8],
+    ) -> Result<Option<(Self, IpProtocol, usize)>, Error> {
+        let parsed = match header_type {
+            IpProtocol::HopByHop => {
+                let (options, next_header, len) = Ipv6Options::from_bytes(bytes)?;
+                (Ipv6ExtensionHeader::HopByHop(options), next_header, len)
+            }
+            IpProtocol::Ipv6Routing => {
+                let (routing, next_header, len) = RoutingHeader::from_bytes(bytes)?;
+                (Ipv6ExtensionHeader::Routing(routing), next_header, len)
+            }
+            IpProtocol::Ipv6Fragment => {
+                let (fragment, next_header, len) = FragmentHeader::from_bytes(bytes)?;
+                (Ipv6ExtensionHeader::Fragment(fragment), next_header, len)
+            }
+            IpProtocol::Ipv6DestinationOptions => {
+                let (options, next_header, len) = Ipv6Options::from_bytes(bytes)?;
+                (
+                    Ipv6ExtensionHeader::DestinationOptions(options),
+                    next_header,
+                    len,
+                )
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(parsed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::ipv6::options::Ipv6Option;
+
+    #[test]
+    fn test_extension_header_type() {
+        let fragment = Ipv6ExtensionHeader::Fragment(FragmentHeader::new(1, 0, true));
+        assert_eq!(IpProtocol::Ipv6Fragment, fragment.header_type());
+        assert_eq!(8, fragment.serialized_len());
+
+        let hop_by_hop = Ipv6ExtensionHeader::HopByHop(Ipv6Options::new());
+        assert_eq!(IpProtocol::HopByHop, hop_by_hop.header_type());
+    }
+
+    #[test]
+    fn test_extension_header_is_supported() {
+        assert!(Ipv6ExtensionHeader::is_supported(IpProtocol::HopByHop));
+        assert!(Ipv6ExtensionHeader::is_supported(IpProtocol::Ipv6Routing));
+        assert!(Ipv6ExtensionHeader::is_supported(IpProtocol::Ipv6Fragment));
+        assert!(Ipv6ExtensionHeader::is_supported(
+            IpProtocol::Ipv6DestinationOptions
+        ));
+        assert!(!Ipv6ExtensionHeader::is_supported(IpProtocol::Tcp));
+        assert!(!Ipv6ExtensionHeader::is_supported(
+            IpProtocol::Ipv6NoNextHeader
+        ));
+    }
+
+    #[test]
+    fn test_extension_header_is_supported_matches_from_bytes() {
+        for value in 0..=u8::MAX {
+            let header_type = IpProtocol::from(value);
+            let expect = !matches!(Ipv6ExtensionHeader::from_bytes(header_type, &[]), Ok(None));
+            let actual = Ipv6ExtensionHeader::is_supported(header_type);
+            assert_eq!(expect, actual, "next header {value}");
+        }
+    }
+
+    #[test]
+    fn test_extension_header_from_bytes() {
+        let mut options = Ipv6Options::new();
+        options.add(Ipv6Option::RouterAlert(0));
+        let expect = Ipv6ExtensionHeader::DestinationOptions(options);
+
+        let mut buf = [0u8; 8];
+        expect.write_to(&mut buf, IpProtocol::Udp).unwrap();
+
+        let actual =
+            Ipv6ExtensionHeader::from_bytes(IpProtocol::Ipv6DestinationOptions, &buf).unwrap();
+        assert_eq!(Some((expect, IpProtocol::Udp, 8)), actual);
+    }
+
+    #[test]
+    fn test_extension_header_from_bytes_unsupported() {
+        let actual = Ipv6ExtensionHeader::from_bytes(IpProtocol::Tcp, &[0; 8]).unwrap();
+        assert_eq!(None, actual);
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+
+/// Length of the Fragment header in bytes
+pub const FRAGMENT_HEADER_LEN: usize = 8;
+
+/// Largest value of the 13-bit Fragment Offset field, in units of 8 octets
+pub const MAX_FRAGMENT_OFFSET: u16 = 0x1FFF;
+
+/// Fragment extension header
+///
+/// Reference: RFC 8200 Section 4.5 - Fragment Header
+#[derive(Debug, PartialEq, Clone)]
+pub struct FragmentHeader {
+    pub fragment_offset: u16, // 13 bits - Offset in 8-octet units
+    pub more_fragments: bool, // 1 bit - M flag
+    pub identification: u32,  // 32 bits - Identification
+}
+
+impl FragmentHeader {
+    pub fn new(identification: u32, fragment_offset: u16, more_fragments: bool) -> Self {
+        FragmentHeader {
+            fragment_offset,
+            more_fragments,
+            identification,
+        }
+    }
+
+    /// Length of the serialized header in bytes
+    pub fn serialized_len(&self) -> usize {
+        FRAGMENT_HEADER_LEN
+    }
+
+    /// Serialize the fragment header into `buf`, returning the number of bytes written
+    ///
+    /// An offset above [`MAX_FRAGMENT_OFFSET`] fails with [`Error::BadFragment`],
+    /// whose length is 0 since the header does not know its fragment's length.
+    pub fn write_to(&self, buf: &mut [u8], next_header: IpProtocol) -> Result<usize, Error> {
+        if self.fragment_offset > MAX_FRAGMENT_OFFSET {
+            return Err(Error::BadFragment {
+                offset: self.fragment_offset as usize * 8,
+                length: 0,
+            });
+        }
+        ensure_capacity(buf, FRAGMENT_HEADER_LEN)?;
+
+        // Byte 0: Next Header, Byte 1: Reserved
+        buf[0] = next_header.to_u8();
+        buf[1] = 0;
+
+        // Bytes 2-3: Fragment Offset (13 bits) + Res (2 bits) + M flag (1 bit)
+        let offset_and_flags = (self.fragment_offset << 3) | self.more_fragments as u16;
+        buf[2..4].copy_from_slice(&offset_and_flags.to_be_bytes());
+
+        // Bytes 4-7: Identification
+        buf[4..8].copy_from_slice(&self.identification.to_be_bytes());
+
+        Ok(FRAGMENT_HEADER_LEN)
+    }
+
+    /// Serialize the fragment header to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self, next_header: IpProtocol) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; FRAGMENT_HEADER_LEN];
+        self.write_to(&mut bytes, next_header)?;
+        Ok(bytes)
+    }
+
+    /// Parse a fragment header, returns (header, next_header, bytes_consumed)
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, IpProtocol, usize), Error> {
+        if bytes.len() < FRAGMENT_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: FRAGMENT_HEADER_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        let offset_and_flags = u16::from_be_bytes([bytes[2], bytes[3]]);
+        let header = FragmentHeader {
+            fragment_offset: offset_and_flags >> 3,
+            more_fragments: (offset_and_flags & 0x0001) != 0,
+            identification: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        };
+
+        Ok((header, IpProtocol::from(bytes[0]), FRAGMENT_HEADER_LEN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_fragment_header_to_bytes() {
+        let header = FragmentHeader::new(0x12345678, 185, true); // Offset 1480 bytes
+
+        let expect = alloc::vec![
+            17, 0, // Next Header UDP, Reserved
+            0x05, 0xC9, // Offset 185 << 3 | M
+            0x12, 0x34, 0x56, 0x78, // Identification
+        ];
+        let actual = header.to_bytes(IpProtocol::Udp).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_fragment_header_from_bytes() {
+        let expect = FragmentHeader::new(0xDEADBEEF, MAX_FRAGMENT_OFFSET, false);
+        let bytes = expect.to_bytes(IpProtocol::Tcp).unwrap();
+
+        let (actual, next_header, consumed) = FragmentHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert_eq!(IpProtocol::Tcp, next_header);
+        assert_eq!(8, consumed);
+    }
+
+    #[test]
+    fn test_fragment_header_offset_overflow() {
+        let header = FragmentHeader::new(1, MAX_FRAGMENT_OFFSET + 1, false);
+        let mut buf = [0u8; FRAGMENT_HEADER_LEN];
+
+        let expect = Err(Error::BadFragment {
+            offset: 65536,
+            length: 0,
+        });
+        let actual = header.write_to(&mut buf, IpProtocol::Udp);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_fragment_header_from_bytes_truncated() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 8,
+            got: 4,
+        });
+        let actual = FragmentHeader::from_bytes(&[17, 0, 0, 1]);
+        assert_eq!(expect, actual);
+    }
+}
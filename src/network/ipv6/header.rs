@@ -0,0 +1,282 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use crate::network::qos::{Dscp, Ecn, traffic_class};
+
+/// Length of the fixed IPv6 header in bytes
+pub const IPV6_HEADER_LEN: usize = 40;
+
+/// IPv6 header structure based on RFC 8200
+///
+/// Reference: RFC 8200 Section 3 - IPv6 Header Format
+#[derive(Debug, PartialEq)]
+pub struct Ipv6Header {
+    pub version: u8,                   // 4 bits - IP version (always 6 for IPv6)
+    pub traffic_class: u8,             // 8 bits - Traffic Class (DSCP + ECN)
+    pub flow_label: u32,               // 20 bits - Flow Label
+    pub payload_length: u16,           // 16 bits - Length of everything after this header
+    pub next_header: IpProtocol,       // 8 bits - Type of the header following this one
+    pub hop_limit: u8,                 // 8 bits - Hop Limit
+    pub source_address: [u8; 16],      // 128 bits - Source IP address
+    pub destination_address: [u8; 16], // 128 bits - Destination IP address
+}
+
+impl Ipv6Header {
+    pub fn new(
+        source_address: [u8; 16],
+        destination_address: [u8; 16],
+        next_header: IpProtocol,
+    ) -> Self {
+        Ipv6Header {
+            version: 6,
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 0, // Will be calculated later
+            next_header,
+            hop_limit: 64, // Default hop limit
+            source_address,
+            destination_address,
+        }
+    }
+
+    /// Differentiated Services Code Point from the upper six bits of the Traffic Class
+    pub fn dscp(&self) -> Dscp {
+        Dscp::from_traffic_class(self.traffic_class)
+    }
+
+    /// Set the DSCP, leaving the ECN bits unchanged
+    pub fn set_dscp(&mut self, dscp: Dscp) {
+        self.traffic_class = traffic_class(dscp, self.ecn());
+    }
+
+    /// Explicit Congestion Notification from the lower two bits of the Traffic Class
+    pub fn ecn(&self) -> Ecn {
+        Ecn::from_traffic_class(self.traffic_class)
+    }
+
+    /// Set the ECN, leaving the DSCP bits unchanged
+    pub fn set_ecn(&mut self, ecn: Ecn) {
+        self.traffic_class = traffic_class(self.dscp(), ecn);
+    }
+
+    /// Length of the serialized fixed header in bytes (extension headers are separate)
+    pub fn serialized_len(&self) -> usize {
+        IPV6_HEADER_LEN
+    }
+
+    /// Serialize IPv6 header to bytes (network byte order)
+    ///
+    /// Reference: RFC 8200 Section 3 for field layout
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; IPV6_HEADER_LEN];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+
+    /// Serialize IPv6 header into `buf` (network byte order), returning the number of bytes written
+    ///
+    /// Reference: RFC 8200 Section 3 for field layout
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, IPV6_HEADER_LEN)?;
+        let bytes = &mut buf[..IPV6_HEADER_LEN];
+
+        // Bytes 0-3: Version (4 bits) + Traffic Class (8 bits) + Flow Label (20 bits)
+        let first_word = ((self.version as u32 & 0x0F) << 28)
+            | ((self.traffic_class as u32) << 20)
+            | (self.flow_label & 0x000F_FFFF);
+        bytes[0..4].copy_from_slice(&first_word.to_be_bytes());
+
+        // Bytes 4-5: Payload Length (big-endian)
+        bytes[4..6].copy_from_slice(&self.payload_length.to_be_bytes());
+
+        // Byte 6: Next Header
+        bytes[6] = self.next_header.to_u8();
+
+        // Byte 7: Hop Limit
+        bytes[7] = self.hop_limit;
+
+        // Bytes 8-23: Source Address
+        bytes[8..24].copy_from_slice(&self.source_address);
+
+        // Bytes 24-39: Destination Address
+        bytes[24..40].copy_from_slice(&self.destination_address);
+
+        Ok(IPV6_HEADER_LEN)
+    }
+
+    /// Deserialize IPv6 header from bytes (network byte order)
+    ///
+    /// Reference: RFC 8200 Section 3 for field layout
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < IPV6_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: IPV6_HEADER_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        // Bytes 0-3: Version + Traffic Class + Flow Label
+        let first_word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let version = (first_word >> 28) as u8;
+        if version != 6 {
+            return Err(Error::BadVersion {
+                layer: Layer::Ipv6,
+                version,
+            });
+        }
+        let traffic_class = (first_word >> 20) as u8;
+        let flow_label = first_word & 0x000F_FFFF;
+
+        // Bytes 4-5: Payload Length
+        let payload_length = u16::from_be_bytes([bytes[4], bytes[5]]);
+
+        // Byte 6: Next Header
+        let next_header = IpProtocol::from(bytes[6]);
+
+        // Byte 7: Hop Limit
+        let hop_limit = bytes[7];
+
+        // Bytes 8-23: Source Address
+        let mut source_address = [0u8; 16];
+        source_address.copy_from_slice(&bytes[8..24]);
+
+        // Bytes 24-39: Destination Address
+        let mut destination_address = [0u8; 16];
+        destination_address.copy_from_slice(&bytes[24..40]);
+
+        Ok(Ipv6Header {
+            version,
+            traffic_class,
+            flow_label,
+            payload_length,
+            next_header,
+            hop_limit,
+            source_address,
+            destination_address,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC_IP: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01,
+    ];
+    const DST_IP: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02,
+    ];
+
+    #[test]
+    fn test_ipv6_header_new() {
+        let header = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Tcp);
+
+        assert_eq!(6, header.version);
+        assert_eq!(0, header.traffic_class);
+        assert_eq!(0, header.flow_label);
+        assert_eq!(IpProtocol::Tcp, header.next_header);
+        assert_eq!(64, header.hop_limit);
+        assert_eq!(SRC_IP, header.source_address);
+        assert_eq!(DST_IP, header.destination_address);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_header_to_bytes() {
+        let mut header = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Udp);
+        header.set_dscp(Dscp::EF);
+        header.flow_label = 0x12345;
+        header.payload_length = 13;
+        header.hop_limit = 255;
+
+        let actual = header.to_bytes();
+        assert_eq!(40, actual.len());
+
+        assert_eq!([0x6B, 0x81, 0x23, 0x45], actual[0..4]); // Version, TC, Flow Label
+        assert_eq!([0x00, 0x0D], actual[4..6]); // Payload length
+        assert_eq!(17, actual[6]); // Next header
+        assert_eq!(255, actual[7]); // Hop limit
+        assert_eq!(SRC_IP, actual[8..24]);
+        assert_eq!(DST_IP, actual[24..40]);
+    }
+
+    #[test]
+    fn test_ipv6_header_dscp_ecn() {
+        let mut header = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Udp);
+        header.set_ecn(Ecn::Ce);
+        header.set_dscp(Dscp::AF41);
+
+        assert_eq!(0x8B, header.traffic_class);
+        assert_eq!(Dscp::AF41, header.dscp());
+        assert_eq!(Ecn::Ce, header.ecn());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_header_flow_label_masked() {
+        let mut header = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Udp);
+        header.flow_label = 0xFFF0_0001; // Only the low 20 bits are serialized
+
+        let actual = header.to_bytes();
+        assert_eq!([0x60, 0x00, 0x00, 0x01], actual[0..4]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_header_write_to() {
+        let header = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Tcp);
+        let expect = header.to_bytes();
+
+        let mut buf = [0u8; 48];
+        let len = header.write_to(&mut buf).unwrap();
+        assert_eq!(header.serialized_len(), len);
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 40,
+            got: 39,
+        });
+        let actual = header.write_to(&mut buf[..39]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_header_from_bytes() {
+        let mut expect = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Icmpv6);
+        expect.traffic_class = 0x02;
+        expect.flow_label = 0xABCDE;
+        expect.payload_length = 1280;
+        expect.hop_limit = 1;
+
+        let actual = Ipv6Header::from_bytes(&expect.to_bytes()).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_header_from_bytes_invalid() {
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 40,
+            got: 4,
+        });
+        assert_eq!(expect, Ipv6Header::from_bytes(&[0x60, 0, 0, 0]));
+
+        let mut bytes = Ipv6Header::new(SRC_IP, DST_IP, IpProtocol::Tcp).to_bytes();
+        bytes[0] = 0x45;
+        let expect = Err(Error::BadVersion {
+            layer: Layer::Ipv6,
+            version: 4,
+        });
+        assert_eq!(expect, Ipv6Header::from_bytes(&bytes));
+    }
+}
@@ -0,0 +1,405 @@
+use alloc::vec::Vec;
+
+pub mod extension;
+pub mod fragment;
+pub mod header;
+pub mod options;
+pub mod routing;
+
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use extension::Ipv6ExtensionHeader;
+use fragment::FragmentHeader;
+use header::{IPV6_HEADER_LEN, Ipv6Header};
+use options::Ipv6Options;
+
+/// IPv6 packet combining header, extension headers, and payload
+///
+/// `header.next_header` holds the protocol of the payload that follows the
+/// last extension header. The Next Header fields on the wire are derived from
+/// the `extensions` chain when the packet is serialized, and `from_bytes`
+/// stores the protocol found at the end of the chain.
+#[derive(Debug, PartialEq)]
+pub struct Ipv6Packet<'a> {
+    pub header: Ipv6Header,
+    pub extensions: Vec<Ipv6ExtensionHeader>,
+    pub payload: &'a [u8],
+}
+
+impl<'a> Ipv6Packet<'a> {
+    pub fn new(
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+        next_header: IpProtocol,
+        payload: &'a [u8],
+    ) -> Self {
+        Ipv6Packet {
+            header: Ipv6Header::new(src_ip, dst_ip, next_header),
+            extensions: Vec::new(),
+            payload,
+        }
+    }
+
+    /// Parse an IPv6 packet, borrowing the payload from `bytes`
+    ///
+    /// The payload length delimits the packet, so any link-layer padding after
+    /// it is ignored (RFC 8200 Section 3). A payload length of zero with a
+    /// Jumbo Payload option uses the jumbo length instead (RFC 2675).
+    ///
+    /// Supported extension headers are parsed until the first upper-layer or
+    /// unsupported header, whose bytes form the payload. Parsing also stops
+    /// after the Fragment header of a non-first fragment, since the rest of
+    /// the chain is not present.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut header = Ipv6Header::from_bytes(bytes)?;
+
+        let total_len = IPV6_HEADER_LEN + Self::payload_len(&header, bytes)?;
+        if bytes.len() < total_len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: total_len,
+                got: bytes.len(),
+            });
+        }
+        let bytes = &bytes[..total_len];
+
+        let mut extensions = Vec::new();
+        let mut offset = IPV6_HEADER_LEN;
+        while let Some((extension, next_header, consumed)) =
+            Ipv6ExtensionHeader::from_bytes(header.next_header, &bytes[offset..])?
+        {
+            header.next_header = next_header;
+            offset += consumed;
+
+            let non_first_fragment = matches!(
+                extension,
+                Ipv6ExtensionHeader::Fragment(FragmentHeader {
+                    fragment_offset: 1..,
+                    ..
+                })
+            );
+            extensions.push(extension);
+            if non_first_fragment {
+                break;
+            }
+        }
+
+        Ok(Ipv6Packet {
+            header,
+            extensions,
+            payload: &bytes[offset..],
+        })
+    }
+
+    /// Length of everything after the fixed header, honouring a Jumbo Payload option
+    fn payload_len(header: &Ipv6Header, bytes: &[u8]) -> Result<usize, Error> {
+        if header.payload_length != 0 || header.next_header != IpProtocol::HopByHop {
+            return Ok(header.payload_length as usize);
+        }
+
+        let (options, _, _) = Ipv6Options::from_bytes(&bytes[IPV6_HEADER_LEN..])?;
+        Ok(options.jumbo_payload().unwrap_or(0) as usize)
+    }
+
+    /// Total length of the extension headers in bytes
+    fn extensions_len(&self) -> usize {
+        self.extensions
+            .iter()
+            .map(Ipv6ExtensionHeader::serialized_len)
+            .sum()
+    }
+
+    /// Calculate and set the payload length field (extension headers and payload)
+    ///
+    /// Payloads too large for the 16-bit field are given a length of zero, and
+    /// must carry a Jumbo Payload option in a Hop-by-Hop header (RFC 2675).
+    pub fn update_payload_length(&mut self) {
+        let len = self.extensions_len() + self.payload.len();
+        self.header.payload_length = u16::try_from(len).unwrap_or(0);
+    }
+
+    /// Destination address used in upper-layer checksum pseudo-headers
+    ///
+    /// With a routing header that still has segments left this is the final
+    /// segment rather than the current destination address, so checksums
+    /// computed at the source match those verified at the final destination.
+    ///
+    /// Reference: RFC 8200 Section 8.1
+    pub fn final_destination(&self) -> [u8; 16] {
+        self.extensions
+            .iter()
+            .find_map(Ipv6ExtensionHeader::final_destination)
+            .unwrap_or(self.header.destination_address)
+    }
+
+    /// Length of the serialized packet in bytes (header, extension headers and payload)
+    pub fn serialized_len(&self) -> usize {
+        self.header.serialized_len() + self.extensions_len() + self.payload.len()
+    }
+
+    /// Serialize the complete packet into `buf`, returning the number of bytes written
+    ///
+    /// The payload length and the Next Header chain written to `buf` are
+    /// calculated from the extension headers and payload; unlike
+    /// [`Ipv6Packet::to_bytes`], the fields in `self.header` are left unchanged.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
+
+        let header_len = self.header.write_to(buf)?;
+
+        // Each header names the one after it; the last names the upper layer
+        let mut len = header_len;
+        for (index, extension) in self.extensions.iter().enumerate() {
+            let next_header = self
+                .extensions
+                .get(index + 1)
+                .map_or(self.header.next_header, Ipv6ExtensionHeader::header_type);
+            len += extension.write_to(&mut buf[len..], next_header)?;
+        }
+        buf[len..total_len].copy_from_slice(self.payload);
+
+        // Bytes 4-5: Payload Length, Byte 6: Next Header
+        let payload_len = u16::try_from(total_len - header_len).unwrap_or(0);
+        buf[4..6].copy_from_slice(&payload_len.to_be_bytes());
+        if let Some(first) = self.extensions.first() {
+            buf[6] = first.header_type().to_u8();
+        }
+
+        Ok(total_len)
+    }
+
+    /// Update the payload length, then serialize the complete packet
+    ///
+    /// Fails, like [`Ipv6Packet::write_to`], if an extension header cannot be serialized.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>, Error> {
+        self.update_payload_length();
+
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::transport::udp::UdpPacket;
+    #[cfg(feature = "alloc")]
+    use options::Ipv6Option;
+    use routing::{RoutingHeader, SegmentRoutingHeader};
+
+    const SRC_IP: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01,
+    ];
+    const DST_IP: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02,
+    ];
+
+    #[test]
+    fn test_ipv6_packet_new() {
+        let payload = b"Hello";
+        let actual = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Udp, payload);
+
+        assert_eq!(SRC_IP, actual.header.source_address);
+        assert_eq!(DST_IP, actual.header.destination_address);
+        assert_eq!(IpProtocol::Udp, actual.header.next_header);
+        assert_eq!(payload, actual.payload);
+    }
+
+    #[test]
+    fn test_ipv6_packet_update_payload_length() {
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Udp, b"Hello");
+        packet.update_payload_length();
+        assert_eq!(5, packet.header.payload_length);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_to_bytes_udp() {
+        let udp_bytes = UdpPacket::new(53, 12345, b"hello")
+            .to_bytes_ipv6(SRC_IP, DST_IP)
+            .unwrap();
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Udp, &udp_bytes);
+        let actual = packet.to_bytes().unwrap();
+
+        let mut expect = alloc::vec![
+            0x60, 0x00, 0x00, 0x00, // Version, Traffic Class, Flow Label
+            0x00, 0x0d, 0x11, 0x40, // Payload Length 13, Next Header UDP, Hop Limit 64
+        ];
+        expect.extend_from_slice(&SRC_IP);
+        expect.extend_from_slice(&DST_IP);
+        expect.extend_from_slice(&[
+            0x00, 0x35, 0x30, 0x39, // Ports 53 -> 12345
+            0x00, 0x0d, 0x30, 0x1f, // Length 13, Checksum
+            b'h', b'e', b'l', b'l', b'o',
+        ]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_write_to() {
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Ipv6NoNextHeader, b"Data");
+
+        let mut buf = [0u8; 64];
+        let len = packet.write_to(&mut buf).unwrap();
+        assert_eq!(packet.serialized_len(), len);
+        assert_eq!(0, packet.header.payload_length); // Header left unchanged
+
+        let expect = packet.to_bytes().unwrap();
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 44,
+            got: 43,
+        });
+        let actual = packet.write_to(&mut buf[..43]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_round_trip() {
+        let mut expect = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Udp, b"Test");
+        expect.header.flow_label = 0x54321;
+        let bytes = expect.to_bytes().unwrap();
+
+        let actual = Ipv6Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_ignores_padding() {
+        let mut bytes = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Ipv6NoNextHeader, b"Hi")
+            .to_bytes()
+            .unwrap();
+        bytes.extend_from_slice(&[0; 10]); // Link-layer padding
+
+        let actual = Ipv6Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(b"Hi", actual.payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_truncated() {
+        let bytes = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Ipv6NoNextHeader, b"Data")
+            .to_bytes()
+            .unwrap();
+
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 44,
+            got: 42,
+        });
+        let actual = Ipv6Packet::from_bytes(&bytes[..42]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_extension_chain() {
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Udp, b"Data");
+        let mut hop_by_hop = Ipv6Options::new();
+        hop_by_hop.add(Ipv6Option::RouterAlert(0));
+        packet
+            .extensions
+            .push(Ipv6ExtensionHeader::HopByHop(hop_by_hop));
+        packet
+            .extensions
+            .push(Ipv6ExtensionHeader::Fragment(FragmentHeader::new(
+                7, 0, true,
+            )));
+
+        let actual = packet.to_bytes().unwrap();
+        assert_eq!(60, actual.len());
+        assert_eq!(20, packet.header.payload_length); // 8 + 8 + 4
+        assert_eq!(IpProtocol::Udp, packet.header.next_header); // Upper layer unchanged
+
+        assert_eq!(0, actual[6]); // IPv6 header -> Hop-by-Hop
+        assert_eq!(44, actual[40]); // Hop-by-Hop -> Fragment
+        assert_eq!(17, actual[48]); // Fragment -> UDP
+        assert_eq!(b"Data", &actual[56..]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_extension_chain() {
+        let mut expect = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Tcp, b"Segment");
+        expect
+            .extensions
+            .push(Ipv6ExtensionHeader::HopByHop(Ipv6Options::new()));
+        expect
+            .extensions
+            .push(Ipv6ExtensionHeader::Routing(RoutingHeader::SegmentRouting(
+                SegmentRoutingHeader::new(alloc::vec![DST_IP, SRC_IP]),
+            )));
+        expect
+            .extensions
+            .push(Ipv6ExtensionHeader::DestinationOptions(Ipv6Options::new()));
+        let bytes = expect.to_bytes().unwrap();
+
+        let actual = Ipv6Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_non_first_fragment() {
+        // The bytes after a non-first fragment header are not a header chain
+        let mut expect = Ipv6Packet::new(
+            SRC_IP,
+            DST_IP,
+            IpProtocol::Ipv6DestinationOptions,
+            &[0, 0, 0, 0, 0, 0, 0, 0],
+        );
+        expect
+            .extensions
+            .push(Ipv6ExtensionHeader::Fragment(FragmentHeader::new(
+                7, 10, false,
+            )));
+        let bytes = expect.to_bytes().unwrap();
+
+        let actual = Ipv6Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_packet_from_bytes_jumbo_payload() {
+        let payload = alloc::vec![0xAB; 70_000];
+        let mut hop_by_hop = Ipv6Options::new();
+        hop_by_hop.add(Ipv6Option::JumboPayload(8 + 70_000));
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Ipv6NoNextHeader, &payload);
+        packet
+            .extensions
+            .push(Ipv6ExtensionHeader::HopByHop(hop_by_hop));
+
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(0, packet.header.payload_length);
+        assert_eq!([0, 0], bytes[4..6]);
+
+        let actual = Ipv6Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(70_000, actual.payload.len());
+    }
+
+    #[test]
+    fn test_ipv6_packet_final_destination() {
+        let mut final_ip = DST_IP;
+        final_ip[15] = 0x99;
+        let mut packet = Ipv6Packet::new(SRC_IP, DST_IP, IpProtocol::Tcp, &[]);
+        assert_eq!(DST_IP, packet.final_destination());
+
+        packet
+            .extensions
+            .push(Ipv6ExtensionHeader::Routing(RoutingHeader::SegmentRouting(
+                SegmentRoutingHeader::new(alloc::vec![final_ip, DST_IP]),
+            )));
+        assert_eq!(final_ip, packet.final_destination());
+    }
+}
@@ -0,0 +1,494 @@
+use alloc::vec::Vec;
+
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+
+/// Options carried in Hop-by-Hop and Destination Options headers
+///
+/// Reference: RFC 8200 Section 4.2 - Options
+#[derive(Debug, PartialEq, Clone)]
+pub enum Ipv6Option {
+    /// Pad1 (Type 0) - single byte of padding
+    Pad1,
+    /// PadN (Type 1) - padding with the given number of zero data bytes
+    PadN(u8),
+    /// Router Alert (Type 5, Length 2) - RFC 2711
+    RouterAlert(u16),
+    /// Jumbo Payload (Type 0xC2, Length 4) - RFC 2675
+    JumboPayload(u32),
+    /// Any other option, preserved as-is
+    Unknown { option_type: u8, data: Vec<u8> },
+}
+
+impl Ipv6Option {
+    /// Get the option type code
+    pub fn option_type(&self) -> u8 {
+        match self {
+            Ipv6Option::Pad1 => 0,
+            Ipv6Option::PadN(_) => 1,
+            Ipv6Option::RouterAlert(_) => 5,
+            Ipv6Option::JumboPayload(_) => 0xC2,
+            Ipv6Option::Unknown { option_type, .. } => *option_type,
+        }
+    }
+
+    /// Get the length of this option in bytes (including type and length fields)
+    pub fn length(&self) -> usize {
+        match self {
+            Ipv6Option::Pad1 => 1,
+            Ipv6Option::PadN(len) => 2 + *len as usize,
+            Ipv6Option::RouterAlert(_) => 4,
+            Ipv6Option::JumboPayload(_) => 6,
+            Ipv6Option::Unknown { data, .. } => 2 + data.len(),
+        }
+    }
+
+    /// Serialize option into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the data does not fit the
+    /// 8-bit Opt Data Len field.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.length();
+        // Opt Data Len excludes type and length
+        let data_len = u8::try_from(len.saturating_sub(2)).map_err(|_| Error::BadOptionLength {
+            layer: Layer::Ipv6,
+            kind: self.option_type(),
+            length: len,
+        })?;
+        ensure_capacity(buf, len)?;
+
+        buf[0] = self.option_type();
+        if len > 1 {
+            buf[1] = data_len;
+        }
+        match self {
+            Ipv6Option::Pad1 => {}
+            Ipv6Option::PadN(_) => buf[2..len].fill(0),
+            Ipv6Option::RouterAlert(value) => buf[2..4].copy_from_slice(&value.to_be_bytes()),
+            Ipv6Option::JumboPayload(length) => buf[2..6].copy_from_slice(&length.to_be_bytes()),
+            Ipv6Option::Unknown { data, .. } => buf[2..len].copy_from_slice(data),
+        }
+        Ok(len)
+    }
+
+    /// Serialize option to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.length()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Parse a single option from bytes, returns (option, bytes_consumed)
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        if bytes.is_empty() {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: 1,
+                got: 0,
+            });
+        }
+
+        let option_type = bytes[0];
+        if option_type == 0 {
+            return Ok((Ipv6Option::Pad1, 1));
+        }
+
+        if bytes.len() < 2 {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: 2,
+                got: bytes.len(),
+            });
+        }
+        let data_len = bytes[1] as usize;
+        let len = 2 + data_len;
+        if bytes.len() < len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: len,
+                got: bytes.len(),
+            });
+        }
+        let data = &bytes[2..len];
+
+        let expected_len = match option_type {
+            5 => Some(2),
+            0xC2 => Some(4),
+            _ => None,
+        };
+        if expected_len.is_some_and(|expected| expected != data_len) {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Ipv6,
+                kind: option_type,
+                length: data_len,
+            });
+        }
+
+        let option = match option_type {
+            1 => Ipv6Option::PadN(data_len as u8),
+            5 => Ipv6Option::RouterAlert(u16::from_be_bytes([data[0], data[1]])),
+            0xC2 => {
+                Ipv6Option::JumboPayload(u32::from_be_bytes([data[0], data[1], data[2], data[3]]))
+            }
+            _ => Ipv6Option::Unknown {
+                option_type,
+                data: data.to_vec(),
+            },
+        };
+        Ok((option, len))
+    }
+}
+
+/// Hop-by-Hop or Destination Options extension header
+///
+/// Options are padded with Pad1/PadN so the header is a multiple of 8 octets;
+/// alignment of individual options is left to the caller, who can add explicit
+/// padding options where needed.
+///
+/// Reference: RFC 8200 Sections 4.3 and 4.6
+#[derive(Debug, PartialEq, Clone)]
+pub struct Ipv6Options {
+    pub options: Vec<Ipv6Option>,
+}
+
+impl Ipv6Options {
+    pub fn new() -> Self {
+        Ipv6Options {
+            options: Vec::new(),
+        }
+    }
+
+    /// Add an option to the collection
+    pub fn add(&mut self, option: Ipv6Option) {
+        self.options.push(option);
+    }
+
+    /// Calculate the total length of options in bytes (excluding padding)
+    pub fn total_length(&self) -> usize {
+        self.options.iter().map(Ipv6Option::length).sum()
+    }
+
+    /// Length of the serialized extension header in bytes
+    ///
+    /// Includes the Next Header and Hdr Ext Len fields and the trailing padding.
+    pub fn serialized_len(&self) -> usize {
+        (2 + self.total_length()).div_ceil(8) * 8
+    }
+
+    /// Get the Jumbo Payload length, if the option is present
+    pub fn jumbo_payload(&self) -> Option<u32> {
+        self.options.iter().find_map(|option| match option {
+            Ipv6Option::JumboPayload(length) => Some(*length),
+            _ => None,
+        })
+    }
+
+    /// Serialize the extension header into `buf`, returning the number of bytes written
+    ///
+    /// Fails if an option or the whole header is too long for its length field.
+    pub fn write_to(&self, buf: &mut [u8], next_header: IpProtocol) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        let ext_len = hdr_ext_len(total_len)?;
+        ensure_capacity(buf, total_len)?;
+
+        // Byte 0: Next Header, Byte 1: Hdr Ext Len in 8-octet units, not including the first 8
+        buf[0] = next_header.to_u8();
+        buf[1] = ext_len;
+
+        let mut len = 2;
+        for option in &self.options {
+            len += option.write_to(&mut buf[len..])?;
+        }
+
+        // Pad to an 8-octet boundary
+        match total_len - len {
+            0 => {}
+            1 => len += Ipv6Option::Pad1.write_to(&mut buf[len..])?,
+            padding => len += Ipv6Option::PadN(padding as u8 - 2).write_to(&mut buf[len..])?,
+        }
+
+        Ok(len)
+    }
+
+    /// Serialize the extension header to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self, next_header: IpProtocol) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes, next_header)?;
+        Ok(bytes)
+    }
+
+    /// Parse the extension header, returns (options, next_header, bytes_consumed)
+    ///
+    /// Trailing Pad1/PadN options are dropped since they are regenerated on
+    /// serialization.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, IpProtocol, usize), Error> {
+        let (next_header, header_len) = extension_header_len(bytes)?;
+
+        let mut options = Vec::new();
+        let mut offset = 2;
+        while offset < header_len {
+            let (option, consumed) = Ipv6Option::from_bytes(&bytes[offset..header_len])?;
+            options.push(option);
+            offset += consumed;
+        }
+
+        while matches!(options.last(), Some(Ipv6Option::Pad1 | Ipv6Option::PadN(_))) {
+            options.pop();
+        }
+
+        Ok((Ipv6Options { options }, next_header, header_len))
+    }
+}
+
+impl Default for Ipv6Options {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the Next Header and Hdr Ext Len fields shared by most extension headers
+///
+/// Returns the next header and the total header length in bytes, checking that
+/// `bytes` holds the whole header.
+pub(crate) fn extension_header_len(bytes: &[u8]) -> Result<(IpProtocol, usize), Error> {
+    if bytes.len() < 8 {
+        return Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 8,
+            got: bytes.len(),
+        });
+    }
+
+    let header_len = (bytes[1] as usize + 1) * 8;
+    if bytes.len() < header_len {
+        return Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: header_len,
+            got: bytes.len(),
+        });
+    }
+
+    Ok((IpProtocol::from(bytes[0]), header_len))
+}
+
+/// Hdr Ext Len field for a header of `total_len` bytes, a multiple of 8
+///
+/// Counts 8-octet units not including the first 8, so headers longer than
+/// 2048 bytes cannot be represented.
+pub(crate) fn hdr_ext_len(total_len: usize) -> Result<u8, Error> {
+    u8::try_from(total_len / 8 - 1).map_err(|_| Error::BadHeaderLength {
+        layer: Layer::Ipv6,
+        length: total_len,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_option_pad1() {
+        let option = Ipv6Option::Pad1;
+        assert_eq!(0, option.option_type());
+        assert_eq!(1, option.length());
+        assert_eq!(alloc::vec![0], option.to_bytes().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_option_padn() {
+        let option = Ipv6Option::PadN(3);
+        assert_eq!(1, option.option_type());
+        assert_eq!(5, option.length());
+        assert_eq!(alloc::vec![1, 3, 0, 0, 0], option.to_bytes().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_option_router_alert() {
+        let option = Ipv6Option::RouterAlert(0); // MLD
+        let expect = alloc::vec![5, 2, 0, 0];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_option_jumbo_payload() {
+        let option = Ipv6Option::JumboPayload(100_000);
+        let expect = alloc::vec![0xC2, 4, 0x00, 0x01, 0x86, 0xA0];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv6_option_write_oversized() {
+        let option = Ipv6Option::Unknown {
+            option_type: 0x1E,
+            data: alloc::vec![0; 256],
+        };
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv6,
+            kind: 0x1E,
+            length: 258,
+        });
+        let actual = option.write_to(&mut [0; 258]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv6_options_write_oversized_header() {
+        let mut options = Ipv6Options::new();
+        for _ in 0..9 {
+            options.add(Ipv6Option::Unknown {
+                option_type: 0x1E,
+                data: alloc::vec![0; 255],
+            });
+        }
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Ipv6,
+            length: 2320,
+        });
+        let actual = options.write_to(&mut [0; 2320], IpProtocol::Tcp);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_option_from_bytes_round_trip() {
+        let options = [
+            Ipv6Option::Pad1,
+            Ipv6Option::PadN(0),
+            Ipv6Option::PadN(4),
+            Ipv6Option::RouterAlert(2),
+            Ipv6Option::JumboPayload(70_000),
+            Ipv6Option::Unknown {
+                option_type: 0x3E,
+                data: alloc::vec![1, 2, 3],
+            },
+        ];
+        for expect in options {
+            let bytes = expect.to_bytes().unwrap();
+            let (actual, consumed) = Ipv6Option::from_bytes(&bytes).unwrap();
+            assert_eq!(expect, actual);
+            assert_eq!(bytes.len(), consumed);
+        }
+    }
+
+    #[test]
+    fn test_ipv6_option_from_bytes_bad_length() {
+        let bytes = [5, 3, 0, 0, 0];
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv6,
+            kind: 5,
+            length: 3,
+        });
+        let actual = Ipv6Option::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_ipv6_option_from_bytes_truncated() {
+        let bytes = [1, 4, 0, 0];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 6,
+            got: 4,
+        });
+        let actual = Ipv6Option::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_options_router_alert_padded() {
+        let mut options = Ipv6Options::new();
+        options.add(Ipv6Option::RouterAlert(0));
+        assert_eq!(8, options.serialized_len());
+
+        let expect = alloc::vec![
+            58, 0, // Next Header ICMPv6, Hdr Ext Len 0
+            5, 2, 0, 0, // Router Alert: MLD
+            1, 0, // PadN with no data
+        ];
+        let actual = options.to_bytes(IpProtocol::Icmpv6).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_options_pad1_padding() {
+        let mut options = Ipv6Options::new();
+        options.add(Ipv6Option::Unknown {
+            option_type: 0x1E,
+            data: alloc::vec![0xAA; 3],
+        });
+
+        let expect = alloc::vec![17, 0, 0x1E, 3, 0xAA, 0xAA, 0xAA, 0];
+        let actual = options.to_bytes(IpProtocol::Udp).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_options_empty() {
+        let options = Ipv6Options::new();
+
+        let expect = alloc::vec![6, 0, 1, 4, 0, 0, 0, 0];
+        let actual = options.to_bytes(IpProtocol::Tcp).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_options_multiple_words() {
+        let mut options = Ipv6Options::new();
+        options.add(Ipv6Option::JumboPayload(100_000));
+        options.add(Ipv6Option::RouterAlert(0));
+        assert_eq!(16, options.serialized_len());
+
+        let bytes = options.to_bytes(IpProtocol::Tcp).unwrap();
+        assert_eq!(1, bytes[1]); // Hdr Ext Len: one 8-octet unit beyond the first
+        assert_eq!([1, 2, 0, 0], bytes[12..16]); // PadN covering the last 4 bytes
+    }
+
+    #[test]
+    fn test_ipv6_options_write_to_buffer_too_small() {
+        let options = Ipv6Options::new();
+        let mut buf = [0u8; 7];
+
+        let expect = Err(Error::BufferTooSmall { needed: 8, got: 7 });
+        let actual = options.write_to(&mut buf, IpProtocol::Ipv6NoNextHeader);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_ipv6_options_from_bytes() {
+        let mut expect = Ipv6Options::new();
+        expect.add(Ipv6Option::JumboPayload(100_000));
+        expect.add(Ipv6Option::RouterAlert(0));
+        let bytes = expect.to_bytes(IpProtocol::Tcp).unwrap();
+
+        let (actual, next_header, consumed) = Ipv6Options::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert_eq!(IpProtocol::Tcp, next_header);
+        assert_eq!(16, consumed);
+        assert_eq!(Some(100_000), actual.jumbo_payload());
+    }
+
+    #[test]
+    fn test_ipv6_options_from_bytes_truncated() {
+        let bytes = [6, 1, 1, 4, 0, 0, 0, 0]; // Claims 16 bytes
+        let expect = Err(Error::Truncated {
+            layer: Layer::Ipv6,
+            needed: 16,
+            got: 8,
+        });
+        let actual = Ipv6Options::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+}
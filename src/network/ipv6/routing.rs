@@ -0,0 +1,497 @@
+use alloc::vec::Vec;
+
+use super::options::{extension_header_len, hdr_ext_len};
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+
+/// Routing type of the Segment Routing Header
+///
+/// Reference: RFC 8754 Section 2
+pub const ROUTING_TYPE_SEGMENT_ROUTING: u8 = 4;
+
+/// Length of the fixed part of a routing header in bytes
+const ROUTING_HEADER_FIXED_LEN: usize = 4;
+
+/// Length of the fixed part of a Segment Routing Header in bytes
+const SRH_FIXED_LEN: usize = 8;
+
+/// Routing extension header
+///
+/// Reference: RFC 8200 Section 4.4 - Routing Header
+#[derive(Debug, PartialEq, Clone)]
+pub enum RoutingHeader {
+    /// Segment Routing Header (Routing Type 4)
+    SegmentRouting(SegmentRoutingHeader),
+    /// Any other routing type, with its type-specific data preserved as-is
+    Other {
+        routing_type: u8,
+        segments_left: u8,
+        data: Vec<u8>,
+    },
+}
+
+impl RoutingHeader {
+    /// Get the routing type field
+    pub fn routing_type(&self) -> u8 {
+        match self {
+            RoutingHeader::SegmentRouting(_) => ROUTING_TYPE_SEGMENT_ROUTING,
+            RoutingHeader::Other { routing_type, .. } => *routing_type,
+        }
+    }
+
+    /// Get the segments left field
+    pub fn segments_left(&self) -> u8 {
+        match self {
+            RoutingHeader::SegmentRouting(srh) => srh.segments_left,
+            RoutingHeader::Other { segments_left, .. } => *segments_left,
+        }
+    }
+
+    /// Final destination of the packet, if this header still has segments to visit
+    ///
+    /// Only known for the Segment Routing Header, where it is `Segment List[0]`.
+    /// Reference: RFC 8200 Section 8.1, RFC 8754 Section 2
+    pub fn final_destination(&self) -> Option<[u8; 16]> {
+        match self {
+            RoutingHeader::SegmentRouting(srh) if srh.segments_left > 0 => {
+                srh.segments.first().copied()
+            }
+            _ => None,
+        }
+    }
+
+    /// Length of the serialized header in bytes (padded to 8 octets)
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            RoutingHeader::SegmentRouting(srh) => srh.serialized_len(),
+            RoutingHeader::Other { data, .. } => {
+                (ROUTING_HEADER_FIXED_LEN + data.len()).div_ceil(8) * 8
+            }
+        }
+    }
+
+    /// Serialize the routing header into `buf`, returning the number of bytes written
+    ///
+    /// Fails if the header is too long for its length field, or if a Segment
+    /// Routing Header has an empty segment list.
+    pub fn write_to(&self, buf: &mut [u8], next_header: IpProtocol) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        let ext_len = hdr_ext_len(total_len)?;
+        if let RoutingHeader::SegmentRouting(srh) = self
+            && srh.segments.is_empty()
+        {
+            // Last Entry cannot describe an empty list
+            return Err(Error::BadHeaderLength {
+                layer: Layer::Ipv6,
+                length: total_len,
+            });
+        }
+        ensure_capacity(buf, total_len)?;
+
+        // Bytes 0-3: Next Header, Hdr Ext Len, Routing Type, Segments Left
+        buf[0] = next_header.to_u8();
+        buf[1] = ext_len;
+        buf[2] = self.routing_type();
+        buf[3] = self.segments_left();
+
+        match self {
+            RoutingHeader::SegmentRouting(srh) => srh.write_body(&mut buf[..total_len])?,
+            RoutingHeader::Other { data, .. } => {
+                let data_end = ROUTING_HEADER_FIXED_LEN + data.len();
+                buf[ROUTING_HEADER_FIXED_LEN..data_end].copy_from_slice(data);
+                buf[data_end..total_len].fill(0);
+            }
+        }
+
+        Ok(total_len)
+    }
+
+    /// Serialize the routing header to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self, next_header: IpProtocol) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes, next_header)?;
+        Ok(bytes)
+    }
+
+    /// Parse a routing header, returns (header, next_header, bytes_consumed)
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, IpProtocol, usize), Error> {
+        let (next_header, header_len) = extension_header_len(bytes)?;
+        let bytes = &bytes[..header_len];
+
+        let routing_type = bytes[2];
+        let segments_left = bytes[3];
+        let header = match routing_type {
+            ROUTING_TYPE_SEGMENT_ROUTING => {
+                RoutingHeader::SegmentRouting(SegmentRoutingHeader::from_body(bytes)?)
+            }
+            _ => RoutingHeader::Other {
+                routing_type,
+                segments_left,
+                data: bytes[ROUTING_HEADER_FIXED_LEN..].to_vec(),
+            },
+        };
+
+        Ok((header, next_header, header_len))
+    }
+}
+
+/// Segment Routing Header (SRH)
+///
+/// The Last Entry field is derived from the segment list, and TLVs are padded
+/// to an 8-octet boundary with Pad1/PadN TLVs.
+///
+/// Reference: RFC 8754 Section 2
+#[derive(Debug, PartialEq, Clone)]
+pub struct SegmentRoutingHeader {
+    pub segments_left: u8,
+    pub flags: u8,
+    pub tag: u16,
+    /// Segment List, encoded in reverse order: `segments[0]` is the last segment
+    pub segments: Vec<[u8; 16]>,
+    pub tlvs: Vec<SrhTlv>,
+}
+
+impl SegmentRoutingHeader {
+    /// Create an SRH for a path given in reverse order (final segment first)
+    ///
+    /// Segments Left is set so the first segment to visit is `segments[n - 1]`.
+    /// The list must not be empty; serializing an SRH without segments fails.
+    pub fn new(segments: Vec<[u8; 16]>) -> Self {
+        SegmentRoutingHeader {
+            segments_left: segments.len().saturating_sub(1) as u8,
+            flags: 0,
+            tag: 0,
+            segments,
+            tlvs: Vec::new(),
+        }
+    }
+
+    /// Length of the serialized header in bytes (padded to 8 octets)
+    pub fn serialized_len(&self) -> usize {
+        let tlv_len: usize = self.tlvs.iter().map(SrhTlv::length).sum();
+        (SRH_FIXED_LEN + self.segments.len() * 16 + tlv_len).div_ceil(8) * 8
+    }
+
+    /// Write everything after the common routing header fields
+    fn write_body(&self, buf: &mut [u8]) -> Result<(), Error> {
+        // Byte 4: Last Entry, Byte 5: Flags, Bytes 6-7: Tag
+        buf[4] = self.segments.len().saturating_sub(1) as u8;
+        buf[5] = self.flags;
+        buf[6..8].copy_from_slice(&self.tag.to_be_bytes());
+
+        let mut len = SRH_FIXED_LEN;
+        for segment in &self.segments {
+            buf[len..len + 16].copy_from_slice(segment);
+            len += 16;
+        }
+        for tlv in &self.tlvs {
+            len += tlv.write_to(&mut buf[len..])?;
+        }
+
+        // Pad to an 8-octet boundary
+        match buf.len() - len {
+            0 => {}
+            1 => {
+                SrhTlv::Pad1.write_to(&mut buf[len..])?;
+            }
+            padding => {
+                SrhTlv::PadN(padding as u8 - 2).write_to(&mut buf[len..])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the SRH from a complete routing header
+    fn from_body(bytes: &[u8]) -> Result<Self, Error> {
+        let segments_left = bytes[3];
+        let last_entry = bytes[4] as usize;
+        let flags = bytes[5];
+        let tag = u16::from_be_bytes([bytes[6], bytes[7]]);
+
+        let segments_end = SRH_FIXED_LEN + (last_entry + 1) * 16;
+        if bytes.len() < segments_end {
+            return Err(Error::BadHeaderLength {
+                layer: Layer::Ipv6,
+                length: bytes.len(),
+            });
+        }
+        let segments = bytes[SRH_FIXED_LEN..segments_end]
+            .chunks_exact(16)
+            .map(|chunk| {
+                let mut segment = [0u8; 16];
+                segment.copy_from_slice(chunk);
+                segment
+            })
+            .collect();
+
+        let mut tlvs = Vec::new();
+        let mut offset = segments_end;
+        while offset < bytes.len() {
+            let (tlv, consumed) = SrhTlv::from_bytes(&bytes[offset..])?;
+            tlvs.push(tlv);
+            offset += consumed;
+        }
+        while matches!(tlvs.last(), Some(SrhTlv::Pad1 | SrhTlv::PadN(_))) {
+            tlvs.pop();
+        }
+
+        Ok(SegmentRoutingHeader {
+            segments_left,
+            flags,
+            tag,
+            segments,
+            tlvs,
+        })
+    }
+}
+
+/// TLV objects carried after the SRH segment list
+///
+/// Reference: RFC 8754 Section 2.1
+#[derive(Debug, PartialEq, Clone)]
+pub enum SrhTlv {
+    /// Pad1 (Type 0) - single byte of padding
+    Pad1,
+    /// PadN (Type 4) - padding with the given number of zero data bytes
+    PadN(u8),
+    /// Any other TLV (e.g. HMAC, Type 5), preserved as-is
+    Unknown { tlv_type: u8, data: Vec<u8> },
+}
+
+impl SrhTlv {
+    /// Get the TLV type code
+    pub fn tlv_type(&self) -> u8 {
+        match self {
+            SrhTlv::Pad1 => 0,
+            SrhTlv::PadN(_) => 4,
+            SrhTlv::Unknown { tlv_type, .. } => *tlv_type,
+        }
+    }
+
+    /// Get the length of this TLV in bytes (including type and length fields)
+    pub fn length(&self) -> usize {
+        match self {
+            SrhTlv::Pad1 => 1,
+            SrhTlv::PadN(len) => 2 + *len as usize,
+            SrhTlv::Unknown { data, .. } => 2 + data.len(),
+        }
+    }
+
+    /// Serialize TLV into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the data does not fit the
+    /// 8-bit Length field.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.length();
+        let data_len = u8::try_from(len.saturating_sub(2)).map_err(|_| Error::BadOptionLength {
+            layer: Layer::Ipv6,
+            kind: self.tlv_type(),
+            length: len,
+        })?;
+        ensure_capacity(buf, len)?;
+
+        buf[0] = self.tlv_type();
+        match self {
+            SrhTlv::Pad1 => {}
+            SrhTlv::PadN(_) => {
+                buf[1] = data_len;
+                buf[2..len].fill(0);
+            }
+            SrhTlv::Unknown { data, .. } => {
+                buf[1] = data_len;
+                buf[2..len].copy_from_slice(data);
+            }
+        }
+        Ok(len)
+    }
+
+    /// Parse a single TLV from bytes, returns (tlv, bytes_consumed)
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        if bytes.is_empty() {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: 1,
+                got: 0,
+            });
+        }
+
+        let tlv_type = bytes[0];
+        if tlv_type == 0 {
+            return Ok((SrhTlv::Pad1, 1));
+        }
+
+        if bytes.len() < 2 {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: 2,
+                got: bytes.len(),
+            });
+        }
+        let len = 2 + bytes[1] as usize;
+        if bytes.len() < len {
+            return Err(Error::Truncated {
+                layer: Layer::Ipv6,
+                needed: len,
+                got: bytes.len(),
+            });
+        }
+
+        let tlv = match tlv_type {
+            4 => SrhTlv::PadN(bytes[1]),
+            _ => SrhTlv::Unknown {
+                tlv_type,
+                data: bytes[2..len].to_vec(),
+            },
+        };
+        Ok((tlv, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(last: u8) -> [u8; 16] {
+        let mut address = [0u8; 16];
+        address[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        address[15] = last;
+        address
+    }
+
+    #[test]
+    fn test_srh_new() {
+        let srh = SegmentRoutingHeader::new(alloc::vec![segment(3), segment(2), segment(1)]);
+        assert_eq!(2, srh.segments_left);
+        assert_eq!(56, srh.serialized_len()); // 8 + 3 * 16
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_srh_to_bytes() {
+        let mut srh = SegmentRoutingHeader::new(alloc::vec![segment(2), segment(1)]);
+        srh.tag = 0x1234;
+        let header = RoutingHeader::SegmentRouting(srh);
+
+        let actual = header.to_bytes(IpProtocol::Ipv6).unwrap();
+        assert_eq!(40, actual.len());
+
+        let expect_fixed = [
+            41, 4, // Next Header IPv6, Hdr Ext Len 4 (40 bytes)
+            4, 1, // Routing Type SRH, Segments Left 1
+            1, 0, // Last Entry 1, Flags
+            0x12, 0x34, // Tag
+        ];
+        assert_eq!(expect_fixed, actual[..8]);
+        assert_eq!(segment(2), actual[8..24]);
+        assert_eq!(segment(1), actual[24..40]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_srh_tlv_padding() {
+        let mut srh = SegmentRoutingHeader::new(alloc::vec![segment(1)]);
+        srh.tlvs.push(SrhTlv::Unknown {
+            tlv_type: 0x80,
+            data: alloc::vec![0xAA; 3],
+        });
+        let header = RoutingHeader::SegmentRouting(srh);
+
+        let actual = header.to_bytes(IpProtocol::Tcp).unwrap();
+        assert_eq!(32, actual.len()); // 8 + 16 + 5 bytes of TLV, padded
+        assert_eq!([0x80, 3, 0xAA, 0xAA, 0xAA], actual[24..29]);
+        assert_eq!([4, 1, 0], actual[29..32]); // PadN with one byte of data
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_srh_from_bytes_round_trip() {
+        let mut srh = SegmentRoutingHeader::new(alloc::vec![segment(3), segment(2), segment(1)]);
+        srh.flags = 0x80;
+        srh.tlvs.push(SrhTlv::Unknown {
+            tlv_type: 5,
+            data: alloc::vec![0x80, 0, 0, 0, 0, 1],
+        });
+        let expect = RoutingHeader::SegmentRouting(srh);
+        let bytes = expect.to_bytes(IpProtocol::Tcp).unwrap();
+
+        let (actual, next_header, consumed) = RoutingHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert_eq!(IpProtocol::Tcp, next_header);
+        assert_eq!(bytes.len(), consumed);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_srh_from_bytes_bad_last_entry() {
+        let mut bytes =
+            RoutingHeader::SegmentRouting(SegmentRoutingHeader::new(alloc::vec![segment(1)]))
+                .to_bytes(IpProtocol::Tcp)
+                .unwrap();
+        bytes[4] = 1; // Claims two segments in a 24-byte header
+
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Ipv6,
+            length: 24,
+        });
+        let actual = RoutingHeader::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_routing_header_final_destination() {
+        let mut srh = SegmentRoutingHeader::new(alloc::vec![segment(9), segment(1)]);
+        let header = RoutingHeader::SegmentRouting(srh.clone());
+        assert_eq!(Some(segment(9)), header.final_destination());
+
+        srh.segments_left = 0;
+        let header = RoutingHeader::SegmentRouting(srh);
+        assert_eq!(None, header.final_destination());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_routing_header_other_round_trip() {
+        // Type 2 routing header (Mobile IPv6) carrying a home address
+        let mut data = alloc::vec![0; 4];
+        data.extend_from_slice(&segment(7));
+        let expect = RoutingHeader::Other {
+            routing_type: 2,
+            segments_left: 1,
+            data,
+        };
+        let bytes = expect.to_bytes(IpProtocol::Tcp).unwrap();
+        assert_eq!(24, bytes.len());
+        assert_eq!(2, bytes[1]);
+
+        let (actual, _, _) = RoutingHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+        assert_eq!(None, actual.final_destination());
+    }
+
+    #[test]
+    fn test_srh_empty_segment_list() {
+        let header = RoutingHeader::SegmentRouting(SegmentRoutingHeader::new(Vec::new()));
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Ipv6,
+            length: 8,
+        });
+        let actual = header.write_to(&mut [0; 8], IpProtocol::Tcp);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_srh_tlv_write_oversized() {
+        let tlv = SrhTlv::Unknown {
+            tlv_type: 5,
+            data: alloc::vec![0; 300],
+        };
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Ipv6,
+            kind: 5,
+            length: 302,
+        });
+        let actual = tlv.write_to(&mut [0; 302]);
+        assert_eq!(expect, actual);
+    }
+}
@@ -1 +1,7 @@
+pub mod arp;
+pub mod icmpv4;
+pub mod icmpv6;
+pub mod ip_protocol;
 pub mod ipv4;
+pub mod ipv6;
+pub mod qos;
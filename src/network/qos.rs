@@ -0,0 +1,233 @@
+use core::fmt;
+
+/// Differentiated Services Code Point, the upper six bits of the IPv4 Type of
+/// Service octet and the IPv6 Traffic Class
+///
+/// Reference: RFC 2474 Section 3, RFC 3260 Section 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Dscp(u8);
+
+impl Dscp {
+    /// Class Selector 0, default forwarding (RFC 2474)
+    pub const CS0: Dscp = Dscp(0);
+    pub const CS1: Dscp = Dscp(8);
+    pub const CS2: Dscp = Dscp(16);
+    pub const CS3: Dscp = Dscp(24);
+    pub const CS4: Dscp = Dscp(32);
+    pub const CS5: Dscp = Dscp(40);
+    pub const CS6: Dscp = Dscp(48);
+    pub const CS7: Dscp = Dscp(56);
+    /// Assured Forwarding class 1-4, drop precedence 1-3 (RFC 2597)
+    pub const AF11: Dscp = Dscp(10);
+    pub const AF12: Dscp = Dscp(12);
+    pub const AF13: Dscp = Dscp(14);
+    pub const AF21: Dscp = Dscp(18);
+    pub const AF22: Dscp = Dscp(20);
+    pub const AF23: Dscp = Dscp(22);
+    pub const AF31: Dscp = Dscp(26);
+    pub const AF32: Dscp = Dscp(28);
+    pub const AF33: Dscp = Dscp(30);
+    pub const AF41: Dscp = Dscp(34);
+    pub const AF42: Dscp = Dscp(36);
+    pub const AF43: Dscp = Dscp(38);
+    /// Expedited Forwarding (RFC 3246)
+    pub const EF: Dscp = Dscp(46);
+    /// Capacity-admitted traffic (RFC 5865)
+    pub const VOICE_ADMIT: Dscp = Dscp(44);
+    /// Lower-Effort (RFC 8622)
+    pub const LE: Dscp = Dscp(1);
+
+    /// Every named code point, in IANA registry order
+    pub const NAMED: [Dscp; 23] = [
+        Dscp::CS0,
+        Dscp::LE,
+        Dscp::CS1,
+        Dscp::AF11,
+        Dscp::AF12,
+        Dscp::AF13,
+        Dscp::CS2,
+        Dscp::AF21,
+        Dscp::AF22,
+        Dscp::AF23,
+        Dscp::CS3,
+        Dscp::AF31,
+        Dscp::AF32,
+        Dscp::AF33,
+        Dscp::CS4,
+        Dscp::AF41,
+        Dscp::AF42,
+        Dscp::AF43,
+        Dscp::CS5,
+        Dscp::VOICE_ADMIT,
+        Dscp::EF,
+        Dscp::CS6,
+        Dscp::CS7,
+    ];
+
+    /// Create a code point from its 6-bit value (upper bits are ignored)
+    pub const fn new(value: u8) -> Self {
+        Dscp(value & 0x3F)
+    }
+
+    pub const fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Extract the code point from a Type of Service or Traffic Class octet
+    pub const fn from_traffic_class(traffic_class: u8) -> Self {
+        Dscp(traffic_class >> 2)
+    }
+
+    /// Registry name of the code point, if it has one
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match *self {
+            Dscp::CS0 => "CS0",
+            Dscp::CS1 => "CS1",
+            Dscp::CS2 => "CS2",
+            Dscp::CS3 => "CS3",
+            Dscp::CS4 => "CS4",
+            Dscp::CS5 => "CS5",
+            Dscp::CS6 => "CS6",
+            Dscp::CS7 => "CS7",
+            Dscp::AF11 => "AF11",
+            Dscp::AF12 => "AF12",
+            Dscp::AF13 => "AF13",
+            Dscp::AF21 => "AF21",
+            Dscp::AF22 => "AF22",
+            Dscp::AF23 => "AF23",
+            Dscp::AF31 => "AF31",
+            Dscp::AF32 => "AF32",
+            Dscp::AF33 => "AF33",
+            Dscp::AF41 => "AF41",
+            Dscp::AF42 => "AF42",
+            Dscp::AF43 => "AF43",
+            Dscp::EF => "EF",
+            Dscp::VOICE_ADMIT => "VOICE-ADMIT",
+            Dscp::LE => "LE",
+            _ => return None,
+        };
+        Some(name)
+    }
+}
+
+impl fmt::Display for Dscp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl From<u8> for Dscp {
+    fn from(value: u8) -> Self {
+        Dscp::new(value)
+    }
+}
+
+impl From<Dscp> for u8 {
+    fn from(dscp: Dscp) -> Self {
+        dscp.value()
+    }
+}
+
+/// Explicit Congestion Notification, the lower two bits of the IPv4 Type of
+/// Service octet and the IPv6 Traffic Class
+///
+/// Reference: RFC 3168 Section 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecn {
+    /// Not ECN-Capable Transport (00)
+    NotEct,
+    /// ECN-Capable Transport, ECT(1) (01)
+    Ect1,
+    /// ECN-Capable Transport, ECT(0) (10)
+    Ect0,
+    /// Congestion Experienced (11)
+    Ce,
+}
+
+impl Ecn {
+    /// Every code point, in value order
+    pub const ALL: [Ecn; 4] = [Ecn::NotEct, Ecn::Ect1, Ecn::Ect0, Ecn::Ce];
+
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Ecn::NotEct => 0b00,
+            Ecn::Ect1 => 0b01,
+            Ecn::Ect0 => 0b10,
+            Ecn::Ce => 0b11,
+        }
+    }
+
+    /// Extract the code point from a Type of Service or Traffic Class octet
+    pub fn from_traffic_class(traffic_class: u8) -> Self {
+        match traffic_class & 0b11 {
+            0b00 => Ecn::NotEct,
+            0b01 => Ecn::Ect1,
+            0b10 => Ecn::Ect0,
+            _ => Ecn::Ce,
+        }
+    }
+}
+
+/// Combine a DSCP and ECN into a Type of Service or Traffic Class octet
+pub fn traffic_class(dscp: Dscp, ecn: Ecn) -> u8 {
+    (dscp.value() << 2) | ecn.to_u8()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_dscp_values() {
+        assert_eq!(0, Dscp::CS0.value());
+        assert_eq!(56, Dscp::CS7.value());
+        assert_eq!(10, Dscp::AF11.value());
+        assert_eq!(38, Dscp::AF43.value());
+        assert_eq!(46, Dscp::EF.value());
+        assert_eq!(44, Dscp::VOICE_ADMIT.value());
+        assert_eq!(1, Dscp::LE.value());
+    }
+
+    #[test]
+    fn test_dscp_named_are_distinct() {
+        for (index, dscp) in Dscp::NAMED.iter().enumerate() {
+            assert!(dscp.name().is_some());
+            assert!(!Dscp::NAMED[index + 1..].contains(dscp));
+        }
+    }
+
+    #[test]
+    fn test_dscp_display() {
+        assert_eq!("AF21", Dscp::AF21.to_string());
+        assert_eq!("VOICE-ADMIT", Dscp::VOICE_ADMIT.to_string());
+        assert_eq!("5", Dscp::new(5).to_string());
+    }
+
+    #[test]
+    fn test_dscp_new_masks_upper_bits() {
+        let expect = Dscp::EF;
+        let actual = Dscp::new(0xC0 | 46);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_traffic_class() {
+        // EF with ECT(0) is 0xBA
+        assert_eq!(0xBA, traffic_class(Dscp::EF, Ecn::Ect0));
+        assert_eq!(Dscp::EF, Dscp::from_traffic_class(0xBA));
+        assert_eq!(Ecn::Ect0, Ecn::from_traffic_class(0xBA));
+    }
+
+    #[test]
+    fn test_traffic_class_roundtrip() {
+        for value in 0..=u8::MAX {
+            let dscp = Dscp::from_traffic_class(value);
+            let ecn = Ecn::from_traffic_class(value);
+            assert_eq!(value, traffic_class(dscp, ecn));
+        }
+    }
+}
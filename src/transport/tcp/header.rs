@@ -1,5 +1,11 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Length of the fixed TCP header in bytes (data offset of 5 words)
+pub const TCP_HEADER_LEN: usize = 20;
+
 #[derive(Debug, PartialEq)]
 pub struct TcpHeader {
     pub source_port: u16,
@@ -7,28 +13,34 @@ pub struct TcpHeader {
     pub sequence_number: u32,
     pub acknowledgment_number: u32,
     pub data_offset: u8, // 4 bits - number of 32-bit words in header
-    pub reserved: u8,    // 3 bits - must be zero
-    pub flags: TcpFlags, // 9 bits - control flags
+    pub flags: TcpFlags, // 12 bits - reserved bits and control flags
     pub window: u16,
     pub checksum: u16,
     pub urgent_pointer: u16,
 }
 
+/// Control bits following the data offset
+///
+/// Reference: RFC 9293 Section 3.1, RFC 3168 (CWR, ECE), RFC 3540 / AccECN (AE)
 #[derive(Debug, PartialEq)]
 pub struct TcpFlags {
-    pub cwr: bool, // Congestion Window Reduced
-    pub ece: bool, // ECN-Echo
-    pub urg: bool, // Urgent pointer field is significant
-    pub ack: bool, // Acknowledgment field is significant
-    pub psh: bool, // Push function
-    pub rst: bool, // Reset the connection
-    pub syn: bool, // Synchronize sequence numbers
-    pub fin: bool, // No more data from sender
+    pub reserved: u8, // 3 bits - must be zero
+    pub ae: bool,     // Accurate ECN (formerly NS, Nonce Sum)
+    pub cwr: bool,    // Congestion Window Reduced
+    pub ece: bool,    // ECN-Echo
+    pub urg: bool,    // Urgent pointer field is significant
+    pub ack: bool,    // Acknowledgment field is significant
+    pub psh: bool,    // Push function
+    pub rst: bool,    // Reset the connection
+    pub syn: bool,    // Synchronize sequence numbers
+    pub fin: bool,    // No more data from sender
 }
 
 impl TcpFlags {
     pub fn new() -> Self {
         TcpFlags {
+            reserved: 0,
+            ae: false,
             cwr: false,
             ece: false,
             urg: false,
@@ -40,10 +52,11 @@ impl TcpFlags {
         }
     }
 
-    /// Convert flags to a u16 value for serialization
-    /// Bits: [Reserved(3)] [CWR][ECE][URG][ACK][PSH][RST][SYN][FIN]
+    /// Convert flags to the 12-bit control field for serialization
+    ///
+    /// Bits: `[Reserved(3)] [AE] [CWR] [ECE] [URG] [ACK] [PSH] [RST] [SYN] [FIN]`
     pub fn to_u16(&self) -> u16 {
-        let mut flags = 0u16;
+        let mut flags = ((self.reserved & 0x07) as u16) << 9;
         if self.fin {
             flags |= 0x01;
         }
@@ -68,12 +81,19 @@ impl TcpFlags {
         if self.cwr {
             flags |= 0x80;
         }
+        if self.ae {
+            flags |= 0x100;
+        }
         flags
     }
 
-    /// Create flags from u16 value for deserialization
+    /// Create flags from the 12-bit control field for deserialization
+    ///
+    /// Bits above the low 12 belong to the data offset and are ignored.
     pub fn from_u16(value: u16) -> Self {
         TcpFlags {
+            reserved: ((value >> 9) & 0x07) as u8,
+            ae: (value & 0x100) != 0,
             fin: (value & 0x01) != 0,
             syn: (value & 0x02) != 0,
             rst: (value & 0x04) != 0,
@@ -100,7 +120,6 @@ impl TcpHeader {
             sequence_number: 0,
             acknowledgment_number: 0,
             data_offset: 5, // Minimum header size is 20 bytes (5 * 32-bit words)
-            reserved: 0,
             flags: TcpFlags::new(),
             window: 0,
             checksum: 0,
@@ -108,43 +127,61 @@ impl TcpHeader {
         }
     }
 
+    /// Length of the serialized fixed header in bytes (options are separate)
+    pub fn serialized_len(&self) -> usize {
+        TCP_HEADER_LEN
+    }
+
     /// Serialize TCP header to bytes
+    #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(20);
+        let mut bytes = alloc::vec![0; TCP_HEADER_LEN];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+
+    /// Serialize TCP header into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, TCP_HEADER_LEN)?;
+        let bytes = &mut buf[..TCP_HEADER_LEN];
 
         // Source port (16 bits)
-        bytes.extend_from_slice(&self.source_port.to_be_bytes());
+        bytes[0..2].copy_from_slice(&self.source_port.to_be_bytes());
 
         // Destination port (16 bits)
-        bytes.extend_from_slice(&self.destination_port.to_be_bytes());
+        bytes[2..4].copy_from_slice(&self.destination_port.to_be_bytes());
 
         // Sequence number (32 bits)
-        bytes.extend_from_slice(&self.sequence_number.to_be_bytes());
+        bytes[4..8].copy_from_slice(&self.sequence_number.to_be_bytes());
 
         // Acknowledgment number (32 bits)
-        bytes.extend_from_slice(&self.acknowledgment_number.to_be_bytes());
+        bytes[8..12].copy_from_slice(&self.acknowledgment_number.to_be_bytes());
 
         // Data offset (4 bits) + Reserved (3 bits) + Flags (9 bits)
-        let data_offset_and_reserved = (self.data_offset << 4) | (self.reserved & 0x07);
-        bytes.push(data_offset_and_reserved);
-        bytes.push(self.flags.to_u16() as u8);
+        let offset_and_flags = ((self.data_offset as u16) << 12) | self.flags.to_u16();
+        bytes[12..14].copy_from_slice(&offset_and_flags.to_be_bytes());
 
         // Window (16 bits)
-        bytes.extend_from_slice(&self.window.to_be_bytes());
+        bytes[14..16].copy_from_slice(&self.window.to_be_bytes());
 
         // Checksum (16 bits)
-        bytes.extend_from_slice(&self.checksum.to_be_bytes());
+        bytes[16..18].copy_from_slice(&self.checksum.to_be_bytes());
 
         // Urgent pointer (16 bits)
-        bytes.extend_from_slice(&self.urgent_pointer.to_be_bytes());
+        bytes[18..20].copy_from_slice(&self.urgent_pointer.to_be_bytes());
 
-        bytes
+        Ok(TCP_HEADER_LEN)
     }
 
     /// Parse TCP header from bytes
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        if bytes.len() < 20 {
-            panic!("TCP header must be at least 20 bytes");
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < TCP_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Tcp,
+                needed: TCP_HEADER_LEN,
+                got: bytes.len(),
+            });
         }
 
         let source_port = u16::from_be_bytes([bytes[0], bytes[1]]);
@@ -152,26 +189,25 @@ impl TcpHeader {
         let sequence_number = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
         let acknowledgment_number = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
 
-        let data_offset = (bytes[12] & 0xF0) >> 4;
-        let reserved = (bytes[12] & 0x0E) >> 1;
-        let flags = TcpFlags::from_u16(bytes[13] as u16);
+        let offset_and_flags = u16::from_be_bytes([bytes[12], bytes[13]]);
+        let data_offset = (offset_and_flags >> 12) as u8;
+        let flags = TcpFlags::from_u16(offset_and_flags);
 
         let window = u16::from_be_bytes([bytes[14], bytes[15]]);
         let checksum = u16::from_be_bytes([bytes[16], bytes[17]]);
         let urgent_pointer = u16::from_be_bytes([bytes[18], bytes[19]]);
 
-        TcpHeader {
+        Ok(TcpHeader {
             source_port,
             destination_port,
             sequence_number,
             acknowledgment_number,
             data_offset,
-            reserved,
             flags,
             window,
             checksum,
             urgent_pointer,
-        }
+        })
     }
 }
 
@@ -182,6 +218,8 @@ mod tests {
     #[test]
     fn test_tcp_flags_new() {
         let flags = TcpFlags::new();
+        assert_eq!(0, flags.reserved);
+        assert!(!flags.ae);
         assert!(!flags.cwr);
         assert!(!flags.ece);
         assert!(!flags.urg);
@@ -218,6 +256,48 @@ mod tests {
         assert!(!actual.fin);
     }
 
+    #[test]
+    fn test_tcp_flags_ae_and_reserved() {
+        let mut flags = TcpFlags::new();
+        flags.ae = true;
+        assert_eq!(0x100, flags.to_u16());
+
+        flags.reserved = 0b101;
+        assert_eq!(0xB00, flags.to_u16());
+        assert_eq!(0x0FFF, TcpFlags::from_u16(0xFFFF).to_u16());
+
+        let actual = TcpFlags::from_u16(0xFFFF); // Data offset bits ignored
+        assert_eq!(0x07, actual.reserved);
+        assert!(actual.ae);
+        assert!(actual.cwr);
+        assert!(actual.fin);
+    }
+
+    #[test]
+    fn test_tcp_flags_round_trip_all_bits() {
+        for expect in 0..0x1000u16 {
+            let actual = TcpFlags::from_u16(expect).to_u16();
+            assert_eq!(expect, actual);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_header_round_trip_all_control_bits() {
+        for bits in 0..0x1000u16 {
+            let mut expect = TcpHeader::new(80, 8080);
+            expect.data_offset = 6;
+            expect.flags = TcpFlags::from_u16(bits);
+
+            let bytes = expect.to_bytes();
+            assert_eq!(0x60 | (bits >> 8) as u8, bytes[12]);
+            assert_eq!(bits as u8, bytes[13]);
+
+            let actual = TcpHeader::from_bytes(&bytes).unwrap();
+            assert_eq!(expect, actual);
+        }
+    }
+
     #[test]
     fn test_tcp_header_new() {
         let actual = TcpHeader::new(80, 8080);
@@ -227,7 +307,6 @@ mod tests {
         assert_eq!(0, actual.sequence_number);
         assert_eq!(0, actual.acknowledgment_number);
         assert_eq!(5, actual.data_offset);
-        assert_eq!(0, actual.reserved);
         assert_eq!(TcpFlags::new(), actual.flags);
         assert_eq!(0, actual.window);
         assert_eq!(0, actual.checksum);
@@ -235,6 +314,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_header_to_bytes() {
         let mut header = TcpHeader::new(80, 8080);
         header.sequence_number = 0x12345678;
@@ -257,6 +337,26 @@ mod tests {
         assert_eq!(expect, actual);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_header_write_to() {
+        let mut header = TcpHeader::new(80, 8080);
+        header.flags.ack = true;
+        let expect = header.to_bytes();
+
+        let mut buf = [0u8; 32];
+        let len = header.write_to(&mut buf).unwrap();
+        assert_eq!(header.serialized_len(), len);
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 20,
+            got: 10,
+        });
+        let actual = header.write_to(&mut buf[..10]);
+        assert_eq!(expect, actual);
+    }
+
     #[test]
     fn test_tcp_header_from_bytes() {
         let bytes = alloc::vec![
@@ -271,18 +371,30 @@ mod tests {
             0x00, 0x00, // Urgent pointer: 0
         ];
 
-        let actual = TcpHeader::from_bytes(&bytes);
+        let actual = TcpHeader::from_bytes(&bytes).unwrap();
 
         assert_eq!(80, actual.source_port);
         assert_eq!(8080, actual.destination_port);
         assert_eq!(0x12345678, actual.sequence_number);
         assert_eq!(0x87654321, actual.acknowledgment_number);
         assert_eq!(5, actual.data_offset);
-        assert_eq!(0, actual.reserved);
+        assert_eq!(0, actual.flags.reserved);
         assert!(actual.flags.syn);
         assert!(!actual.flags.ack);
         assert_eq!(65535, actual.window);
         assert_eq!(0x1234, actual.checksum);
         assert_eq!(0, actual.urgent_pointer);
     }
+
+    #[test]
+    fn test_tcp_header_from_bytes_truncated() {
+        let bytes = [0u8; 19];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Tcp,
+            needed: 20,
+            got: 19,
+        });
+        let actual = TcpHeader::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
 }
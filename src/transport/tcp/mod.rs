@@ -1,9 +1,13 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 pub mod header;
 pub mod options;
 
-use header::TcpHeader;
+use crate::checksum::{Checksum, ChecksumVerification};
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use header::{TCP_HEADER_LEN, TcpHeader};
 use options::TcpOptions;
 
 /// TCP packet combining header, options, and payload
@@ -23,148 +27,233 @@ impl<'a> TcpPacket<'a> {
         }
     }
 
+    /// Parse a TCP segment, borrowing the payload from `bytes`
+    ///
+    /// The data offset determines where the options end and the payload
+    /// begins (RFC 9293 Section 3.1).
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = TcpHeader::from_bytes(bytes)?;
+
+        let header_len = header.data_offset as usize * 4;
+        if header_len < TCP_HEADER_LEN {
+            return Err(Error::BadHeaderLength {
+                layer: Layer::Tcp,
+                length: header_len,
+            });
+        }
+        if bytes.len() < header_len {
+            return Err(Error::Truncated {
+                layer: Layer::Tcp,
+                needed: header_len,
+                got: bytes.len(),
+            });
+        }
+
+        let options = TcpOptions::from_bytes(&bytes[TCP_HEADER_LEN..header_len])?;
+        let payload = &bytes[header_len..];
+
+        Ok(TcpPacket {
+            header,
+            options,
+            payload,
+        })
+    }
+
     /// Calculate and set the correct data_offset based on header and options
     pub fn update_data_offset(&mut self) {
         let header_words = 5; // Minimum header is 20 bytes = 5 words
         let option_words = self.options.words_needed();
-        self.header.data_offset = header_words + option_words;
+        self.header.data_offset = option_words.saturating_add(header_words);
     }
 
     /// Calculate TCP checksum including pseudo-header
-    pub fn calculate_checksum_ipv4(&self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> u16 {
-        let mut sum = 0u32;
+    ///
+    /// Fails if the options cannot be serialized, or with
+    /// [`Error::BadTotalLength`] if the segment is longer than the 16-bit TCP
+    /// length of the IPv4 pseudo-header.
+    pub fn calculate_checksum_ipv4(&self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> Result<u16, Error> {
+        let tcp_length = ipv4_tcp_length(self.tcp_length())?;
+        let mut checksum = Checksum::new();
 
         // IPv4 pseudo-header
-        sum += u16::from_be_bytes([src_ip[0], src_ip[1]]) as u32;
-        sum += u16::from_be_bytes([src_ip[2], src_ip[3]]) as u32;
-        sum += u16::from_be_bytes([dst_ip[0], dst_ip[1]]) as u32;
-        sum += u16::from_be_bytes([dst_ip[2], dst_ip[3]]) as u32;
-        sum += 6u32; // Protocol number for TCP
+        checksum.add_ipv4_pseudo_header(src_ip, dst_ip, IpProtocol::Tcp, tcp_length);
 
-        let tcp_length = (self.header.data_offset as u32 * 4) + self.payload.len() as u32;
-        sum += tcp_length;
+        self.add_segment_to_checksum(&mut checksum)?;
+        Ok(checksum.finish())
+    }
 
-        // TCP header (with checksum field set to 0)
-        let mut header_bytes = self.header.to_bytes();
-        header_bytes[16] = 0; // Clear checksum field
-        header_bytes[17] = 0;
+    /// Calculate TCP checksum including IPv6 pseudo-header
+    ///
+    /// With a routing header, `dst_ip` is the final destination; see
+    /// [`Ipv6Packet::final_destination`](crate::network::ipv6::Ipv6Packet::final_destination).
+    pub fn calculate_checksum_ipv6(
+        &self,
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+    ) -> Result<u16, Error> {
+        let mut checksum = Checksum::new();
 
-        // Add options
-        let option_bytes = self.options.to_bytes();
-        header_bytes.extend_from_slice(&option_bytes);
-
-        // Process header + options in 16-bit chunks
-        for chunk in header_bytes.chunks(2) {
-            if chunk.len() == 2 {
-                sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-            } else {
-                sum += (chunk[0] as u32) << 8; // Pad with zero
-            }
-        }
+        // IPv6 pseudo-header
+        checksum.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Tcp, self.tcp_length() as u32);
 
-        // Add payload
-        for chunk in self.payload.chunks(2) {
-            if chunk.len() == 2 {
-                sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-            } else {
-                sum += (chunk[0] as u32) << 8; // Pad with zero
-            }
-        }
+        self.add_segment_to_checksum(&mut checksum)?;
+        Ok(checksum.finish())
+    }
 
-        // Fold carry bits
-        while (sum >> 16) != 0 {
-            sum = (sum & 0xFFFF) + (sum >> 16);
-        }
+    /// TCP length for the pseudo-header: header, options and payload in bytes
+    fn tcp_length(&self) -> usize {
+        self.header.data_offset as usize * 4 + self.payload.len()
+    }
 
-        // One's complement
-        !sum as u16
+    /// Add header (with checksum field set to 0), options and payload
+    fn add_segment_to_checksum(&self, checksum: &mut Checksum) -> Result<(), Error> {
+        let mut header_bytes = [0u8; TCP_HEADER_LEN];
+        self.header
+            .write_to(&mut header_bytes)
+            .expect("buffer sized for the TCP header");
+        header_bytes[16] = 0; // Clear checksum field
+        header_bytes[17] = 0;
+        checksum.add_bytes(&header_bytes);
+        self.options.add_to_checksum(checksum)?;
+        checksum.add_bytes(self.payload);
+        Ok(())
     }
 
-    /// Calculate TCP checksum including IPv6 pseudo-header
-    pub fn calculate_checksum_ipv6(&self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> u16 {
-        let mut sum = 0u32;
+    /// Verify the stored checksum using the IPv4 pseudo-header
+    ///
+    /// Reference: RFC 9293 Section 3.1 - Checksum
+    pub fn verify_checksum_ipv4(
+        &self,
+        src_ip: [u8; 4],
+        dst_ip: [u8; 4],
+    ) -> Result<ChecksumVerification, Error> {
+        Ok(ChecksumVerification {
+            stored: self.header.checksum,
+            expected: self.calculate_checksum_ipv4(src_ip, dst_ip)?,
+        })
+    }
 
-        // IPv6 pseudo-header
-        for chunk in src_ip.chunks(2) {
-            sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-        }
-        for chunk in dst_ip.chunks(2) {
-            sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-        }
+    /// Verify the stored checksum using the IPv6 pseudo-header
+    ///
+    /// Reference: RFC 8200 Section 8.1 - Upper-Layer Checksums
+    pub fn verify_checksum_ipv6(
+        &self,
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+    ) -> Result<ChecksumVerification, Error> {
+        Ok(ChecksumVerification {
+            stored: self.header.checksum,
+            expected: self.calculate_checksum_ipv6(src_ip, dst_ip)?,
+        })
+    }
 
-        let tcp_length = (self.header.data_offset as u32 * 4) + self.payload.len() as u32;
-        sum += tcp_length;
-        sum += 6u32; // Next header (TCP)
+    /// Length of the serialized segment in bytes (header, padded options and payload)
+    pub fn serialized_len(&self) -> usize {
+        self.header.serialized_len() + self.options.serialized_len() + self.payload.len()
+    }
 
-        // TCP header (with checksum field set to 0)
-        let mut header_bytes = self.header.to_bytes();
-        header_bytes[16] = 0; // Clear checksum field
-        header_bytes[17] = 0;
+    /// Write header, options and payload with the derived data offset and a zero checksum
+    fn write_segment(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        ensure_capacity(buf, total_len)?;
 
-        // Add options
-        let option_bytes = self.options.to_bytes();
-        header_bytes.extend_from_slice(&option_bytes);
-
-        // Process header + options in 16-bit chunks
-        for chunk in header_bytes.chunks(2) {
-            if chunk.len() == 2 {
-                sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-            } else {
-                sum += (chunk[0] as u32) << 8;
-            }
-        }
+        let mut header_len = self.header.write_to(buf)?;
+        header_len += self.options.write_to(&mut buf[header_len..])?;
+        buf[header_len..total_len].copy_from_slice(self.payload);
 
-        // Add payload
-        for chunk in self.payload.chunks(2) {
-            if chunk.len() == 2 {
-                sum += u16::from_be_bytes([chunk[0], chunk[1]]) as u32;
-            } else {
-                sum += (chunk[0] as u32) << 8;
-            }
-        }
+        // Data offset (upper 4 bits of byte 12), at most 15 words since the
+        // options fit the option space; checksum cleared
+        buf[12] = (((header_len / 4) as u8) << 4) | (buf[12] & 0x0F);
+        buf[16..18].fill(0);
 
-        // Fold carry bits
-        while (sum >> 16) != 0 {
-            sum = (sum & 0xFFFF) + (sum >> 16);
-        }
+        Ok(total_len)
+    }
 
-        // One's complement
-        !sum as u16
+    /// Serialize the complete segment into `buf`, returning the number of bytes written
+    ///
+    /// The data offset and checksum written to `buf` are calculated from the
+    /// options, payload and IPv4 pseudo-header; unlike
+    /// [`TcpPacket::to_bytes_ipv4`], the fields in `self.header` are left unchanged.
+    /// A segment longer than 65535 bytes fails with [`Error::BadTotalLength`].
+    pub fn write_to_ipv4(
+        &self,
+        buf: &mut [u8],
+        src_ip: [u8; 4],
+        dst_ip: [u8; 4],
+    ) -> Result<usize, Error> {
+        let tcp_length = ipv4_tcp_length(self.serialized_len())?;
+        let len = self.write_segment(buf)?;
+
+        let mut checksum = Checksum::new();
+        checksum.add_ipv4_pseudo_header(src_ip, dst_ip, IpProtocol::Tcp, tcp_length);
+        checksum.add_bytes(&buf[..len]);
+        buf[16..18].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        Ok(len)
+    }
+
+    /// Serialize the complete segment into `buf`, returning the number of bytes written
+    ///
+    /// Same as [`TcpPacket::write_to_ipv4`] but with the IPv6 pseudo-header.
+    pub fn write_to_ipv6(
+        &self,
+        buf: &mut [u8],
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+    ) -> Result<usize, Error> {
+        let len = self.write_segment(buf)?;
+
+        let mut checksum = Checksum::new();
+        checksum.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Tcp, len as u32);
+        checksum.add_bytes(&buf[..len]);
+        buf[16..18].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        Ok(len)
     }
 
     /// Update checksum and data_offset, then serialize the complete packet
-    pub fn to_bytes_ipv4(&mut self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> Vec<u8> {
+    ///
+    /// Fails, like [`TcpPacket::write_to_ipv4`], if the options cannot be serialized.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes_ipv4(&mut self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> Result<Vec<u8>, Error> {
         // Update data offset
         self.update_data_offset();
 
         // Calculate and set checksum
-        self.header.checksum = self.calculate_checksum_ipv4(src_ip, dst_ip);
+        self.header.checksum = self.calculate_checksum_ipv4(src_ip, dst_ip)?;
 
         // Serialize
-        let mut bytes = self.header.to_bytes();
-        bytes.extend_from_slice(&self.options.to_bytes());
-        bytes.extend_from_slice(self.payload);
-
-        bytes
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to_ipv4(&mut bytes, src_ip, dst_ip)?;
+        Ok(bytes)
     }
 
     /// Update checksum and data_offset, then serialize the complete packet
-    pub fn to_bytes_ipv6(&mut self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> Vec<u8> {
+    ///
+    /// Fails, like [`TcpPacket::write_to_ipv6`], if the options cannot be serialized.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes_ipv6(&mut self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> Result<Vec<u8>, Error> {
         // Update data offset
         self.update_data_offset();
 
         // Calculate and set checksum
-        self.header.checksum = self.calculate_checksum_ipv6(src_ip, dst_ip);
+        self.header.checksum = self.calculate_checksum_ipv6(src_ip, dst_ip)?;
 
         // Serialize
-        let mut bytes = self.header.to_bytes();
-        bytes.extend_from_slice(&self.options.to_bytes());
-        bytes.extend_from_slice(self.payload);
-
-        bytes
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to_ipv6(&mut bytes, src_ip, dst_ip)?;
+        Ok(bytes)
     }
 }
 
+/// Convert a segment length to the 16-bit TCP length of the IPv4 pseudo-header
+fn ipv4_tcp_length(length: usize) -> Result<u16, Error> {
+    u16::try_from(length).map_err(|_| Error::BadTotalLength {
+        layer: Layer::Tcp,
+        length,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +286,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_packet_syn_with_mss() {
         // Test a common scenario: SYN packet with MSS option
         let payload = b"";
@@ -213,7 +303,7 @@ mod tests {
         // Generate packet for IPv4
         let src_ip = [192, 168, 1, 100];
         let dst_ip = [192, 168, 1, 1];
-        let actual = packet.to_bytes_ipv4(src_ip, dst_ip);
+        let actual = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
 
         // Verify structure
         assert_eq!(24, actual.len()); // 20-byte header + 4-byte option
@@ -235,6 +325,125 @@ mod tests {
         assert_eq!(0x04, actual[21]); // MSS option length
         assert_eq!(0x05, actual[22]); // MSS value high byte (1460 = 0x05B4)
         assert_eq!(0xb4, actual[23]); // MSS value low byte
+
+        // Verify checksum against an independently computed value
+        assert_eq!(0x7b, actual[16]);
+        assert_eq!(0x3b, actual[17]);
+    }
+
+    #[test]
+    fn test_tcp_packet_from_bytes() {
+        let bytes = [
+            0x30, 0x39, 0x00, 0x50, // Source port 12345, destination port 80
+            0x12, 0x34, 0x56, 0x78, // Sequence number
+            0x00, 0x00, 0x00, 0x00, // Acknowledgment number
+            0x60, 0x18, // Data offset 6, flags PSH+ACK
+            0xff, 0xff, 0xab, 0xcd, // Window, checksum
+            0x00, 0x00, // Urgent pointer
+            0x02, 0x04, 0x05, 0xb4, // MSS=1460
+            b'h', b'i', // Payload
+        ];
+
+        let actual = TcpPacket::from_bytes(&bytes).unwrap();
+
+        assert_eq!(12345, actual.header.source_port);
+        assert_eq!(80, actual.header.destination_port);
+        assert_eq!(6, actual.header.data_offset);
+        assert!(actual.header.flags.psh);
+        assert!(actual.header.flags.ack);
+        assert_eq!(0xabcd, actual.header.checksum);
+        assert_eq!(
+            alloc::vec![TcpOption::MaximumSegmentSize(1460)],
+            actual.options.options
+        );
+        assert_eq!(b"hi", actual.payload);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_from_bytes_round_trip() {
+        let mut expect = TcpPacket::new(443, 50000, b"payload");
+        expect.header.flags.ack = true;
+        expect.header.acknowledgment_number = 1;
+        expect.options.add(TcpOption::NoOperation);
+        expect.options.add(TcpOption::MaximumSegmentSize(536));
+
+        let bytes = expect.to_bytes_ipv4([10, 0, 0, 1], [10, 0, 0, 2]).unwrap();
+        let actual = TcpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_from_bytes_data_offset_too_small() {
+        let mut bytes = TcpPacket::new(80, 8080, b"")
+            .to_bytes_ipv4([0; 4], [0; 4])
+            .unwrap();
+        bytes[12] = 0x40; // Data offset 4 (16 bytes)
+
+        let expect = Err(Error::BadHeaderLength {
+            layer: Layer::Tcp,
+            length: 16,
+        });
+        let actual = TcpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_from_bytes_data_offset_beyond_buffer() {
+        let mut bytes = TcpPacket::new(80, 8080, b"")
+            .to_bytes_ipv4([0; 4], [0; 4])
+            .unwrap();
+        bytes[12] = 0x70; // Data offset 7 (28 bytes), only 20 present
+
+        let expect = Err(Error::Truncated {
+            layer: Layer::Tcp,
+            needed: 28,
+            got: 20,
+        });
+        let actual = TcpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_write_to_ipv4() {
+        let mut packet = TcpPacket::new(12345, 80, b"data");
+        packet.header.flags.syn = true;
+        packet.options.add(TcpOption::MaximumSegmentSize(1460));
+        let src_ip = [192, 168, 1, 100];
+        let dst_ip = [192, 168, 1, 1];
+
+        let mut buf = [0u8; 64];
+        let len = packet.write_to_ipv4(&mut buf, src_ip, dst_ip).unwrap();
+        assert_eq!(packet.serialized_len(), len);
+
+        // Derived fields are written without updating the packet itself
+        assert_eq!(5, packet.header.data_offset);
+        let expect = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+        assert_eq!(expect[..], buf[..len]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_write_to_ipv6() {
+        let mut packet = TcpPacket::new(80, 443, b"test");
+        let src_ip = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let dst_ip = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+        let mut buf = [0u8; 24];
+        let len = packet.write_to_ipv6(&mut buf, src_ip, dst_ip).unwrap();
+
+        let expect = packet.to_bytes_ipv6(src_ip, dst_ip).unwrap();
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 24,
+            got: 23,
+        });
+        let actual = packet.write_to_ipv6(&mut buf[..23], src_ip, dst_ip);
+        assert_eq!(expect, actual);
     }
 
     #[test]
@@ -248,7 +457,7 @@ mod tests {
         let src_ip = [192, 168, 1, 1];
         let dst_ip = [192, 168, 1, 100];
 
-        let actual = packet.calculate_checksum_ipv4(src_ip, dst_ip);
+        let actual = packet.calculate_checksum_ipv4(src_ip, dst_ip).unwrap();
         // Checksum should be non-zero
         assert_ne!(0, actual);
     }
@@ -270,8 +479,124 @@ mod tests {
             0x00, 0x02,
         ];
 
-        let actual = packet.calculate_checksum_ipv6(src_ip, dst_ip);
+        let actual = packet.calculate_checksum_ipv6(src_ip, dst_ip).unwrap();
         // Checksum should be non-zero
         assert_ne!(0, actual);
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_verify_checksum_ipv4() {
+        let src_ip = [192, 168, 1, 1];
+        let dst_ip = [192, 168, 1, 100];
+        let bytes = TcpPacket::new(80, 8080, b"data")
+            .to_bytes_ipv4(src_ip, dst_ip)
+            .unwrap();
+        let packet = TcpPacket::from_bytes(&bytes).unwrap();
+
+        assert!(
+            packet
+                .verify_checksum_ipv4(src_ip, dst_ip)
+                .unwrap()
+                .is_valid()
+        );
+
+        // A different pseudo-header must not verify
+        let actual = packet
+            .verify_checksum_ipv4(src_ip, [192, 168, 1, 101])
+            .unwrap();
+        assert!(!actual.is_valid());
+        assert_eq!(packet.header.checksum, actual.stored);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_verify_checksum_ipv6() {
+        let mut src_ip = [0u8; 16];
+        let mut dst_ip = [0u8; 16];
+        src_ip[15] = 1;
+        dst_ip[15] = 2;
+        let mut bytes = TcpPacket::new(80, 8080, b"data")
+            .to_bytes_ipv6(src_ip, dst_ip)
+            .unwrap();
+
+        let packet = TcpPacket::from_bytes(&bytes).unwrap();
+        assert!(
+            packet
+                .verify_checksum_ipv6(src_ip, dst_ip)
+                .unwrap()
+                .is_valid()
+        );
+
+        bytes[20] ^= 0xFF; // Corrupt the payload
+        let packet = TcpPacket::from_bytes(&bytes).unwrap();
+        let actual = packet.verify_checksum_ipv6(src_ip, dst_ip).unwrap();
+        assert!(!actual.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_options_exceed_option_space() {
+        let mut packet = TcpPacket::new(80, 8080, b"data");
+        packet
+            .options
+            .add(TcpOption::FastOpenCookie(alloc::vec![0xAA; 16]));
+        packet
+            .options
+            .add(TcpOption::Sack(alloc::vec![(1000, 2000); 3]));
+
+        let expect = Err(Error::OptionSpaceExceeded {
+            layer: Layer::Tcp,
+            length: 44,
+            limit: 40,
+        });
+        let actual = packet.to_bytes_ipv4([10, 0, 0, 1], [10, 0, 0, 2]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_full_option_space() {
+        let mut packet = TcpPacket::new(80, 8080, b"data");
+        packet.options.add(TcpOption::Unknown {
+            kind: 99,
+            data: alloc::vec![0; 38],
+        });
+
+        let bytes = packet.to_bytes_ipv4([10, 0, 0, 1], [10, 0, 0, 2]).unwrap();
+        assert_eq!(0xF0, bytes[12]); // Data offset 15
+        assert_eq!(15, packet.header.data_offset);
+        assert_eq!(packet, TcpPacket::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_packet_ipv4_segment_too_long() {
+        let payload = alloc::vec![0; 65516];
+        let mut packet = TcpPacket::new(80, 8080, &payload);
+        let src_ip = [192, 168, 1, 100];
+        let dst_ip = [192, 168, 1, 1];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Tcp,
+            length: 65536,
+        });
+        assert_eq!(
+            expect,
+            packet
+                .calculate_checksum_ipv4(src_ip, dst_ip)
+                .map(usize::from)
+        );
+        let mut buf = alloc::vec![0; packet.serialized_len()];
+        assert_eq!(expect, packet.write_to_ipv4(&mut buf, src_ip, dst_ip));
+        assert_eq!(
+            expect,
+            packet
+                .to_bytes_ipv4(src_ip, dst_ip)
+                .map(|bytes| bytes.len())
+        );
+
+        // The IPv6 pseudo-header has a 32-bit length
+        assert!(packet.to_bytes_ipv6([0; 16], [0; 16]).is_ok());
+    }
 }
@@ -1,13 +1,50 @@
 use alloc::vec::Vec;
 
+use crate::checksum::Checksum;
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Largest option representable with an 8-bit length field
+const MAX_OPTION_LEN: usize = u8::MAX as usize;
+
+/// Maximum number of SACK blocks that fit in the option space
+///
+/// Reference: RFC 2018 Section 3
+pub const MAX_SACK_BLOCKS: usize = 4;
+
+/// Option space in bytes: the 15-word maximum data offset less the 5-word fixed header
+///
+/// Reference: RFC 9293 Section 3.1 - Data Offset
+pub const MAX_OPTIONS_LEN: usize = 40;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TcpOption {
     /// End of Option List (Kind=0)
+    ///
+    /// On the wire it cannot be told apart from the zero padding after the
+    /// last option, so [`TcpOptions`] never holds it; see [`TcpOptions::add`].
     EndOfOptionList,
     /// No Operation (Kind=1) - used for padding
     NoOperation,
     /// Maximum Segment Size (Kind=2, Length=4)
     MaximumSegmentSize(u16),
+    /// Window Scale shift count (Kind=3, Length=3) - RFC 7323
+    WindowScale(u8),
+    /// SACK Permitted (Kind=4, Length=2) - RFC 2018
+    SackPermitted,
+    /// SACK left/right edge pairs (Kind=5, 1-4 blocks) - RFC 2018
+    Sack(Vec<(u32, u32)>),
+    /// Timestamps (Kind=8, Length=10) - RFC 7323
+    Timestamps { value: u32, echo_reply: u32 },
+    /// User Timeout (Kind=28, Length=4) - RFC 5482
+    ///
+    /// `granularity` selects minutes instead of seconds; `timeout` is 15 bits.
+    UserTimeout { granularity: bool, timeout: u16 },
+    /// TCP Fast Open cookie (Kind=34) - RFC 7413; an empty cookie requests one
+    FastOpenCookie(Vec<u8>),
+    /// Experimental option (Kind=253/254) with a 16-bit ExID - RFC 6994
+    Experimental { kind: u8, exid: u16, data: Vec<u8> },
+    /// Any other option, preserved as-is (`data` excludes the kind and length octets)
+    Unknown { kind: u8, data: Vec<u8> },
 }
 
 impl TcpOption {
@@ -17,52 +54,221 @@ impl TcpOption {
             TcpOption::EndOfOptionList => 0,
             TcpOption::NoOperation => 1,
             TcpOption::MaximumSegmentSize(_) => 2,
+            TcpOption::WindowScale(_) => 3,
+            TcpOption::SackPermitted => 4,
+            TcpOption::Sack(_) => 5,
+            TcpOption::Timestamps { .. } => 8,
+            TcpOption::UserTimeout { .. } => 28,
+            TcpOption::FastOpenCookie(_) => 34,
+            TcpOption::Experimental { kind, .. } | TcpOption::Unknown { kind, .. } => *kind,
         }
     }
 
     /// Get the length field for this option (including kind and length fields)
-    pub fn length(&self) -> u8 {
+    pub fn length(&self) -> usize {
         match self {
             TcpOption::EndOfOptionList => 1,
             TcpOption::NoOperation => 1,
             TcpOption::MaximumSegmentSize(_) => 4,
+            TcpOption::WindowScale(_) => 3,
+            TcpOption::SackPermitted => 2,
+            TcpOption::Sack(blocks) => 2 + blocks.len() * 8,
+            TcpOption::Timestamps { .. } => 10,
+            TcpOption::UserTimeout { .. } => 4,
+            TcpOption::FastOpenCookie(cookie) => 2 + cookie.len(),
+            TcpOption::Experimental { data, .. } => 4 + data.len(),
+            TcpOption::Unknown { data, .. } => 2 + data.len(),
         }
     }
 
-    /// Serialize this option to bytes
-    pub fn to_bytes(&self) -> Vec<u8> {
+    /// Length of the option, checked against the limits enforced when parsing
+    ///
+    /// SACK carries 1 to [`MAX_SACK_BLOCKS`] blocks, a Fast Open cookie is empty
+    /// or 4 to 16 bytes, and every option fits its 8-bit length field.
+    fn checked_length(&self) -> Result<usize, Error> {
+        let len = self.length();
+        let valid = match self {
+            TcpOption::Sack(blocks) => (1..=MAX_SACK_BLOCKS).contains(&blocks.len()),
+            TcpOption::FastOpenCookie(cookie) => {
+                cookie.is_empty() || (4..=16).contains(&cookie.len())
+            }
+            _ => len <= MAX_OPTION_LEN,
+        };
+        if !valid {
+            return Err(Error::BadOptionLength {
+                layer: Layer::Tcp,
+                kind: self.kind(),
+                length: len,
+            });
+        }
+        Ok(len)
+    }
+
+    /// Serialize this option into `buf`, returning the number of bytes written
+    ///
+    /// Fails with [`Error::BadOptionLength`] if the option would be rejected
+    /// by [`TcpOption::from_bytes`].
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = self.checked_length()?;
+        ensure_capacity(buf, len)?;
+
+        // Kind, then Length for everything but the single-byte options
+        buf[0] = self.kind();
+        if len > 1 {
+            buf[1] = len as u8;
+        }
+
         match self {
-            TcpOption::EndOfOptionList => alloc::vec![0],
-            TcpOption::NoOperation => alloc::vec![1],
-            TcpOption::MaximumSegmentSize(mss) => {
-                let mut bytes = alloc::vec![2, 4]; // Kind=2, Length=4
-                bytes.extend_from_slice(&mss.to_be_bytes());
-                bytes
+            TcpOption::EndOfOptionList | TcpOption::NoOperation | TcpOption::SackPermitted => {}
+            TcpOption::MaximumSegmentSize(mss) => buf[2..4].copy_from_slice(&mss.to_be_bytes()),
+            TcpOption::WindowScale(shift) => buf[2] = *shift,
+            TcpOption::Sack(blocks) => {
+                for (index, (left, right)) in blocks.iter().enumerate() {
+                    let start = 2 + index * 8;
+                    buf[start..start + 4].copy_from_slice(&left.to_be_bytes());
+                    buf[start + 4..start + 8].copy_from_slice(&right.to_be_bytes());
+                }
+            }
+            TcpOption::Timestamps { value, echo_reply } => {
+                buf[2..6].copy_from_slice(&value.to_be_bytes());
+                buf[6..10].copy_from_slice(&echo_reply.to_be_bytes());
+            }
+            TcpOption::UserTimeout {
+                granularity,
+                timeout,
+            } => {
+                let field = ((*granularity as u16) << 15) | (timeout & 0x7FFF);
+                buf[2..4].copy_from_slice(&field.to_be_bytes());
+            }
+            TcpOption::FastOpenCookie(data) | TcpOption::Unknown { data, .. } => {
+                buf[2..len].copy_from_slice(data)
+            }
+            TcpOption::Experimental { exid, data, .. } => {
+                buf[2..4].copy_from_slice(&exid.to_be_bytes());
+                buf[4..len].copy_from_slice(data);
             }
         }
+        Ok(len)
+    }
+
+    /// Serialize this option to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.length()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
     }
 
     /// Parse a single option from bytes, returns (option, bytes_consumed)
-    pub fn from_bytes(bytes: &[u8]) -> Result<(TcpOption, usize), &'static str> {
+    ///
+    /// Unrecognized kinds are returned as [`TcpOption::Unknown`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<(TcpOption, usize), Error> {
         if bytes.is_empty() {
-            return Err("Empty bytes for TCP option");
+            return Err(Error::Truncated {
+                layer: Layer::Tcp,
+                needed: 1,
+                got: 0,
+            });
         }
 
-        match bytes[0] {
-            0 => Ok((TcpOption::EndOfOptionList, 1)),
-            1 => Ok((TcpOption::NoOperation, 1)),
-            2 => {
-                if bytes.len() < 4 {
-                    return Err("MSS option requires 4 bytes");
+        let kind = bytes[0];
+        match kind {
+            0 => return Ok((TcpOption::EndOfOptionList, 1)),
+            1 => return Ok((TcpOption::NoOperation, 1)),
+            _ => {}
+        }
+
+        // All other options carry a length octet covering kind and length
+        if bytes.len() < 2 {
+            return Err(Error::Truncated {
+                layer: Layer::Tcp,
+                needed: 2,
+                got: bytes.len(),
+            });
+        }
+        let len = bytes[1] as usize;
+        let bad_length = Error::BadOptionLength {
+            layer: Layer::Tcp,
+            kind,
+            length: len,
+        };
+
+        // Fixed-length options report a short buffer against their fixed size
+        let fixed_len = match kind {
+            2 => Some(4),
+            3 => Some(3),
+            4 => Some(2),
+            8 => Some(10),
+            28 => Some(4),
+            _ => None,
+        };
+        let needed = fixed_len.unwrap_or(len);
+        if bytes.len() < needed {
+            return Err(Error::Truncated {
+                layer: Layer::Tcp,
+                needed,
+                got: bytes.len(),
+            });
+        }
+        if fixed_len.is_some_and(|fixed| fixed != len) || len < 2 {
+            return Err(bad_length);
+        }
+        let data = &bytes[2..len];
+
+        let option = match kind {
+            2 => TcpOption::MaximumSegmentSize(u16::from_be_bytes([data[0], data[1]])),
+            3 => TcpOption::WindowScale(data[0]),
+            4 => TcpOption::SackPermitted,
+            5 => {
+                let blocks = data.len() / 8;
+                if !data.len().is_multiple_of(8) || !(1..=MAX_SACK_BLOCKS).contains(&blocks) {
+                    return Err(bad_length);
+                }
+                TcpOption::Sack(
+                    data.chunks_exact(8)
+                        .map(|chunk| {
+                            let left = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                            let right =
+                                u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+                            (left, right)
+                        })
+                        .collect(),
+                )
+            }
+            8 => TcpOption::Timestamps {
+                value: u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+                echo_reply: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            },
+            28 => {
+                let field = u16::from_be_bytes([data[0], data[1]]);
+                TcpOption::UserTimeout {
+                    granularity: field & 0x8000 != 0,
+                    timeout: field & 0x7FFF,
                 }
-                if bytes[1] != 4 {
-                    return Err("MSS option length must be 4");
+            }
+            34 => {
+                // Cookies are 4 to 16 bytes; an empty cookie is a request
+                if !data.is_empty() && !(4..=16).contains(&data.len()) {
+                    return Err(bad_length);
                 }
-                let mss = u16::from_be_bytes([bytes[2], bytes[3]]);
-                Ok((TcpOption::MaximumSegmentSize(mss), 4))
+                TcpOption::FastOpenCookie(data.to_vec())
             }
-            _ => Err("Unknown TCP option kind"),
-        }
+            253 | 254 => {
+                if data.len() < 2 {
+                    return Err(bad_length);
+                }
+                TcpOption::Experimental {
+                    kind,
+                    exid: u16::from_be_bytes([data[0], data[1]]),
+                    data: data[2..].to_vec(),
+                }
+            }
+            _ => TcpOption::Unknown {
+                kind,
+                data: data.to_vec(),
+            },
+        };
+        Ok((option, len))
     }
 }
 
@@ -80,61 +286,106 @@ impl TcpOptions {
     }
 
     /// Add an option to the collection
+    ///
+    /// [`TcpOption::EndOfOptionList`] is dropped: the padding written after the
+    /// last option already ends the list, and parsing stops at the first zero.
     pub fn add(&mut self, option: TcpOption) {
-        self.options.push(option);
+        if option != TcpOption::EndOfOptionList {
+            self.options.push(option);
+        }
     }
 
     /// Calculate the total length of options in bytes
     pub fn total_length(&self) -> usize {
-        self.options.iter().map(|opt| opt.length() as usize).sum()
+        self.options.iter().map(TcpOption::length).sum()
     }
 
     /// Calculate how many 32-bit words are needed for options (including padding)
-    #[allow(clippy::manual_div_ceil)]
+    ///
+    /// Saturates at `u8::MAX` for options far beyond [`MAX_OPTIONS_LEN`].
     pub fn words_needed(&self) -> u8 {
-        let byte_length = self.total_length();
-        ((byte_length + 3) / 4) as u8 // Round up to nearest 32-bit word
+        u8::try_from(self.serialized_len() / 4).unwrap_or(u8::MAX)
     }
 
-    /// Serialize options to bytes with proper padding
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+    /// Length of the serialized options in bytes (including padding)
+    pub fn serialized_len(&self) -> usize {
+        self.total_length().div_ceil(4) * 4
+    }
+
+    /// Check that the padded options fit in [`MAX_OPTIONS_LEN`] bytes
+    fn check_space(&self) -> Result<(), Error> {
+        let length = self.serialized_len();
+        if length > MAX_OPTIONS_LEN {
+            return Err(Error::OptionSpaceExceeded {
+                layer: Layer::Tcp,
+                length,
+                limit: MAX_OPTIONS_LEN,
+            });
+        }
+        Ok(())
+    }
+
+    /// Serialize options into `buf` with proper padding, returning the number of bytes written
+    ///
+    /// Fails with [`Error::OptionSpaceExceeded`] if the options do not fit in
+    /// the header, or if any option cannot be serialized.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.check_space()?;
+        let target_length = self.serialized_len();
+        ensure_capacity(buf, target_length)?;
 
         // Add all options
+        let mut len = 0;
         for option in &self.options {
-            bytes.extend_from_slice(&option.to_bytes());
+            len += option.write_to(&mut buf[len..])?;
         }
 
         // Add padding to align to 32-bit word boundary
-        let words_needed = self.words_needed() as usize;
-        let target_length = words_needed * 4;
+        buf[len..target_length].fill(0);
 
-        while bytes.len() < target_length {
-            bytes.push(0); // Pad with zeros
+        Ok(target_length)
+    }
+
+    /// Serialize options to bytes with proper padding
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Add the serialized options, including padding, to a checksum without allocating
+    ///
+    /// Fails, like [`TcpOptions::write_to`], if an option cannot be serialized.
+    pub(crate) fn add_to_checksum(&self, checksum: &mut Checksum) -> Result<(), Error> {
+        self.check_space()?;
+        let mut buf = [0u8; MAX_OPTION_LEN];
+        let mut len = 0;
+        for option in &self.options {
+            let option_len = option.write_to(&mut buf)?;
+            checksum.add_bytes(&buf[..option_len]);
+            len += option_len;
         }
 
-        bytes
+        // Padding keeps anything added afterwards 16-bit aligned
+        checksum.add_bytes(&[0; 3][..self.serialized_len() - len]);
+        Ok(())
     }
 
     /// Parse options from bytes
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         let mut options = Vec::new();
         let mut offset = 0;
 
         while offset < bytes.len() {
-            // Skip padding zeros at the end
+            // End of option list, or the padding that follows it, terminates parsing
             if bytes[offset] == 0 {
                 break;
             }
 
             let (option, consumed) = TcpOption::from_bytes(&bytes[offset..])?;
-            options.push(option.clone());
+            options.push(option);
             offset += consumed;
-
-            // End of option list terminates parsing
-            if let TcpOption::EndOfOptionList = option {
-                break;
-            }
         }
 
         Ok(TcpOptions { options })
@@ -152,35 +403,38 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_option_end_of_list() {
         let option = TcpOption::EndOfOptionList;
         assert_eq!(0, option.kind());
         assert_eq!(1, option.length());
 
         let expect = alloc::vec![0];
-        let actual = option.to_bytes();
+        let actual = option.to_bytes().unwrap();
         assert_eq!(expect, actual);
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_option_no_operation() {
         let option = TcpOption::NoOperation;
         assert_eq!(1, option.kind());
         assert_eq!(1, option.length());
 
         let expect = alloc::vec![1];
-        let actual = option.to_bytes();
+        let actual = option.to_bytes().unwrap();
         assert_eq!(expect, actual);
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_option_mss() {
         let option = TcpOption::MaximumSegmentSize(1460);
         assert_eq!(2, option.kind());
         assert_eq!(4, option.length());
 
         let expect = alloc::vec![2, 4, 0x05, 0xb4]; // Kind=2, Length=4, MSS=1460
-        let actual = option.to_bytes();
+        let actual = option.to_bytes().unwrap();
         assert_eq!(expect, actual);
     }
 
@@ -193,6 +447,23 @@ mod tests {
         assert_eq!(1, consumed);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_options_end_of_list_dropped() {
+        let mut options = TcpOptions::new();
+        options.add(TcpOption::NoOperation);
+        options.add(TcpOption::EndOfOptionList);
+        options.add(TcpOption::MaximumSegmentSize(1460));
+
+        let expect = TcpOptions {
+            options: alloc::vec![TcpOption::NoOperation, TcpOption::MaximumSegmentSize(1460)],
+        };
+        assert_eq!(expect, options);
+
+        let actual = TcpOptions::from_bytes(&options.to_bytes().unwrap()).unwrap();
+        assert_eq!(expect, actual);
+    }
+
     #[test]
     fn test_tcp_option_from_bytes_nop() {
         let bytes = alloc::vec![1];
@@ -211,6 +482,276 @@ mod tests {
         assert_eq!(4, consumed);
     }
 
+    #[test]
+    fn test_tcp_option_from_bytes_mss_truncated() {
+        let bytes = alloc::vec![2, 4, 0x05];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Tcp,
+            needed: 4,
+            got: 3,
+        });
+        let actual = TcpOption::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_tcp_option_from_bytes_mss_bad_length() {
+        let bytes = alloc::vec![2, 3, 0x05, 0xb4];
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Tcp,
+            kind: 2,
+            length: 3,
+        });
+        let actual = TcpOption::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_window_scale() {
+        let option = TcpOption::WindowScale(7);
+        assert_eq!(3, option.kind());
+        assert_eq!(alloc::vec![3, 3, 7], option.to_bytes().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_sack_permitted() {
+        let option = TcpOption::SackPermitted;
+        assert_eq!(4, option.kind());
+        assert_eq!(alloc::vec![4, 2], option.to_bytes().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_sack() {
+        let option = TcpOption::Sack(alloc::vec![(1000, 2000), (3000, 4000)]);
+        assert_eq!(18, option.length());
+
+        let expect = alloc::vec![
+            5, 18, // Kind=5, Length=18
+            0x00, 0x00, 0x03, 0xE8, 0x00, 0x00, 0x07, 0xD0, // 1000-2000
+            0x00, 0x00, 0x0B, 0xB8, 0x00, 0x00, 0x0F, 0xA0, // 3000-4000
+        ];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_tcp_option_sack_block_count() {
+        for blocks in [0, MAX_SACK_BLOCKS + 1] {
+            let option = TcpOption::Sack(alloc::vec![(1000, 2000); blocks]);
+            let expect = Err(Error::BadOptionLength {
+                layer: Layer::Tcp,
+                kind: 5,
+                length: 2 + blocks * 8,
+            });
+            let actual = option.write_to(&mut [0; 64]);
+            assert_eq!(expect, actual);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_timestamps() {
+        let option = TcpOption::Timestamps {
+            value: 0x01020304,
+            echo_reply: 0,
+        };
+        let expect = alloc::vec![8, 10, 1, 2, 3, 4, 0, 0, 0, 0];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_user_timeout() {
+        let option = TcpOption::UserTimeout {
+            granularity: true,
+            timeout: 10,
+        };
+        let expect = alloc::vec![28, 4, 0x80, 0x0A]; // 10 minutes
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_fast_open_cookie() {
+        let request = TcpOption::FastOpenCookie(alloc::vec![]);
+        assert_eq!(alloc::vec![34, 2], request.to_bytes().unwrap());
+
+        let cookie = TcpOption::FastOpenCookie(alloc::vec![0xAA; 8]);
+        assert_eq!(10, cookie.length());
+    }
+
+    #[test]
+    fn test_tcp_option_fast_open_cookie_bad_length() {
+        let cookie = TcpOption::FastOpenCookie(alloc::vec![0xAA; 40]);
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Tcp,
+            kind: 34,
+            length: 42,
+        });
+        let actual = cookie.write_to(&mut [0; 42]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_tcp_option_write_oversized() {
+        let option = TcpOption::Unknown {
+            kind: 99,
+            data: alloc::vec![0; 300],
+        };
+        assert_eq!(302, option.length());
+
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Tcp,
+            kind: 99,
+            length: 302,
+        });
+        let actual = option.write_to(&mut [0; 302]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_experimental() {
+        let option = TcpOption::Experimental {
+            kind: 254,
+            exid: 0xF989, // Fast Open before kind 34 was assigned
+            data: alloc::vec![1, 2, 3, 4],
+        };
+        let expect = alloc::vec![254, 8, 0xF9, 0x89, 1, 2, 3, 4];
+        let actual = option.to_bytes().unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_option_from_bytes_round_trip() {
+        let options = [
+            TcpOption::WindowScale(14),
+            TcpOption::SackPermitted,
+            TcpOption::Sack(alloc::vec![(1, 2)]),
+            TcpOption::Sack(alloc::vec![(1, 2), (3, 4), (5, 6), (7, 8)]),
+            TcpOption::Timestamps {
+                value: 0xDEADBEEF,
+                echo_reply: 0x12345678,
+            },
+            TcpOption::UserTimeout {
+                granularity: false,
+                timeout: 0x7FFF,
+            },
+            TcpOption::FastOpenCookie(alloc::vec![]),
+            TcpOption::FastOpenCookie(alloc::vec![9; 16]),
+            TcpOption::Experimental {
+                kind: 253,
+                exid: 0x0348,
+                data: alloc::vec![],
+            },
+            TcpOption::Unknown {
+                kind: 30, // MPTCP
+                data: alloc::vec![0x00, 0x81, 1, 2, 3, 4, 5, 6, 7, 8],
+            },
+        ];
+        for expect in options {
+            let bytes = expect.to_bytes().unwrap();
+            let (actual, consumed) = TcpOption::from_bytes(&bytes).unwrap();
+            assert_eq!(expect, actual);
+            assert_eq!(bytes.len(), consumed);
+        }
+    }
+
+    #[test]
+    fn test_tcp_option_from_bytes_bad_lengths() {
+        let cases: [&[u8]; 6] = [
+            &[3, 4, 7, 0],       // Window Scale must be 3
+            &[5, 2],             // SACK with no blocks
+            &[5, 6, 0, 0, 0, 1], // SACK block cut short
+            &[28, 3, 0, 0],      // User Timeout must be 4
+            &[34, 4, 1, 2],      // Cookie shorter than 4 bytes
+            &[254, 3, 0xF9],     // Experimental without full ExID
+        ];
+        for bytes in cases {
+            let expect = Err(Error::BadOptionLength {
+                layer: Layer::Tcp,
+                kind: bytes[0],
+                length: bytes[1] as usize,
+            });
+            let actual = TcpOption::from_bytes(bytes);
+            assert_eq!(expect, actual);
+        }
+    }
+
+    #[test]
+    fn test_tcp_option_from_bytes_sack_too_many_blocks() {
+        let mut bytes = alloc::vec![5, 42];
+        bytes.resize(42, 0);
+        let expect = Err(Error::BadOptionLength {
+            layer: Layer::Tcp,
+            kind: 5,
+            length: 42,
+        });
+        let actual = TcpOption::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_tcp_option_from_bytes_unknown() {
+        let bytes = [0x45, 3, 0xAB]; // Kind 69
+        let (actual, consumed) = TcpOption::from_bytes(&bytes).unwrap();
+
+        let expect = TcpOption::Unknown {
+            kind: 0x45,
+            data: alloc::vec![0xAB],
+        };
+        assert_eq!(expect, actual);
+        assert_eq!(3, consumed);
+    }
+
+    #[test]
+    fn test_tcp_option_from_bytes_unknown_truncated() {
+        let bytes = [0x45, 6, 0xAB];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Tcp,
+            needed: 6,
+            got: 3,
+        });
+        let actual = TcpOption::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_tcp_options_linux_syn() {
+        // Option layout of a Linux SYN: MSS, SACK-permitted, Timestamps, NOP, Window Scale
+        let mut options = TcpOptions::new();
+        options.add(TcpOption::MaximumSegmentSize(1460));
+        options.add(TcpOption::SackPermitted);
+        options.add(TcpOption::Timestamps {
+            value: 0x0A0B0C0D,
+            echo_reply: 0,
+        });
+        options.add(TcpOption::NoOperation);
+        options.add(TcpOption::WindowScale(7));
+        assert_eq!(20, options.serialized_len());
+        assert_eq!(5, options.words_needed());
+
+        let expect = alloc::vec![
+            2, 4, 0x05, 0xb4, // MSS=1460
+            4, 2, // SACK permitted
+            8, 10, 0x0A, 0x0B, 0x0C, 0x0D, 0, 0, 0, 0, // Timestamps
+            1, // NOP
+            3, 3, 7, // Window scale
+        ];
+        let bytes = options.to_bytes().unwrap();
+        assert_eq!(expect, bytes);
+
+        let actual = TcpOptions::from_bytes(&bytes).unwrap();
+        assert_eq!(options, actual);
+    }
+
     #[test]
     fn test_tcp_options_new() {
         let options = TcpOptions::new();
@@ -230,6 +771,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_options_padding() {
         let mut options = TcpOptions::new();
         options.add(TcpOption::NoOperation); // 1 byte
@@ -239,7 +781,7 @@ mod tests {
         assert_eq!(5, options.total_length());
         assert_eq!(2, options.words_needed()); // 5 bytes -> 2 words (8 bytes)
 
-        let actual = options.to_bytes();
+        let actual = options.to_bytes().unwrap();
         let expect = alloc::vec![
             1, // NOP
             2, 4, 0x05, 0xb4, // MSS=1460
@@ -249,16 +791,52 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_tcp_options_syn_with_mss() {
         // Common case: SYN packet with MSS option
         let mut options = TcpOptions::new();
         options.add(TcpOption::MaximumSegmentSize(1460));
 
-        let actual = options.to_bytes();
+        let actual = options.to_bytes().unwrap();
         let expect = alloc::vec![2, 4, 0x05, 0xb4]; // Exactly 4 bytes, no padding needed
         assert_eq!(expect, actual);
     }
 
+    #[test]
+    fn test_tcp_options_write_to() {
+        let mut options = TcpOptions::new();
+        options.add(TcpOption::NoOperation);
+        options.add(TcpOption::MaximumSegmentSize(1460));
+
+        let mut buf = [0xFFu8; 10];
+        let len = options.write_to(&mut buf).unwrap();
+        assert_eq!(options.serialized_len(), len);
+        assert_eq!([1, 2, 4, 0x05, 0xb4, 0, 0, 0], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall { needed: 8, got: 7 });
+        let actual = options.write_to(&mut buf[..7]);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_tcp_options_option_space() {
+        let mut options = TcpOptions::new();
+        options.add(TcpOption::Unknown {
+            kind: 99,
+            data: alloc::vec![0; 38],
+        });
+        assert_eq!(Ok(MAX_OPTIONS_LEN), options.write_to(&mut [0; 64]));
+
+        options.add(TcpOption::NoOperation);
+        let expect = Err(Error::OptionSpaceExceeded {
+            layer: Layer::Tcp,
+            length: 44,
+            limit: MAX_OPTIONS_LEN,
+        });
+        let actual = options.write_to(&mut [0; 64]);
+        assert_eq!(expect, actual);
+    }
+
     #[test]
     fn test_tcp_options_from_bytes() {
         let bytes = alloc::vec![
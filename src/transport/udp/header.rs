@@ -0,0 +1,146 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::error::{Error, Layer, ensure_capacity};
+
+/// Length of the UDP header in bytes
+pub const UDP_HEADER_LEN: usize = 8;
+
+/// UDP header structure based on RFC 768
+///
+/// Reference: RFC 768 - Format
+#[derive(Debug, PartialEq)]
+pub struct UdpHeader {
+    pub source_port: u16,      // 16 bits - Source port (0 if unused)
+    pub destination_port: u16, // 16 bits - Destination port
+    pub length: u16,           // 16 bits - Length of header and data in bytes
+    pub checksum: u16,         // 16 bits - Checksum (0 = none, IPv4 only)
+}
+
+impl UdpHeader {
+    pub fn new(source_port: u16, destination_port: u16) -> Self {
+        UdpHeader {
+            source_port,
+            destination_port,
+            length: UDP_HEADER_LEN as u16, // Will be calculated later
+            checksum: 0,                   // Will be calculated later
+        }
+    }
+
+    /// Length of the serialized header in bytes
+    pub fn serialized_len(&self) -> usize {
+        UDP_HEADER_LEN
+    }
+
+    /// Serialize UDP header into `buf`, returning the number of bytes written
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        ensure_capacity(buf, UDP_HEADER_LEN)?;
+
+        // Bytes 0-1: Source Port
+        buf[0..2].copy_from_slice(&self.source_port.to_be_bytes());
+
+        // Bytes 2-3: Destination Port
+        buf[2..4].copy_from_slice(&self.destination_port.to_be_bytes());
+
+        // Bytes 4-5: Length
+        buf[4..6].copy_from_slice(&self.length.to_be_bytes());
+
+        // Bytes 6-7: Checksum
+        buf[6..8].copy_from_slice(&self.checksum.to_be_bytes());
+
+        Ok(UDP_HEADER_LEN)
+    }
+
+    /// Serialize UDP header to bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![0; UDP_HEADER_LEN];
+        self.write_to(&mut bytes)
+            .expect("buffer sized by serialized_len");
+        bytes
+    }
+
+    /// Parse UDP header from bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < UDP_HEADER_LEN {
+            return Err(Error::Truncated {
+                layer: Layer::Udp,
+                needed: UDP_HEADER_LEN,
+                got: bytes.len(),
+            });
+        }
+
+        Ok(UdpHeader {
+            source_port: u16::from_be_bytes([bytes[0], bytes[1]]),
+            destination_port: u16::from_be_bytes([bytes[2], bytes[3]]),
+            length: u16::from_be_bytes([bytes[4], bytes[5]]),
+            checksum: u16::from_be_bytes([bytes[6], bytes[7]]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_udp_header_new() {
+        let actual = UdpHeader::new(53, 12345);
+
+        assert_eq!(53, actual.source_port);
+        assert_eq!(12345, actual.destination_port);
+        assert_eq!(8, actual.length);
+        assert_eq!(0, actual.checksum);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_header_to_bytes() {
+        let mut header = UdpHeader::new(53, 12345);
+        header.length = 13;
+        header.checksum = 0x0840;
+
+        let expect = alloc::vec![
+            0x00, 0x35, // Source port: 53
+            0x30, 0x39, // Destination port: 12345
+            0x00, 0x0d, // Length: 13
+            0x08, 0x40, // Checksum
+        ];
+        let actual = header.to_bytes();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_udp_header_from_bytes() {
+        let bytes = [0x00, 0x35, 0x30, 0x39, 0x00, 0x0d, 0x08, 0x40];
+        let expect = UdpHeader {
+            source_port: 53,
+            destination_port: 12345,
+            length: 13,
+            checksum: 0x0840,
+        };
+        let actual = UdpHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_udp_header_from_bytes_truncated() {
+        let bytes = [0x00, 0x35, 0x30, 0x39];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Udp,
+            needed: 8,
+            got: 4,
+        });
+        let actual = UdpHeader::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_udp_header_write_to_buffer_too_small() {
+        let header = UdpHeader::new(53, 12345);
+        let mut buf = [0u8; 7];
+        let expect = Err(Error::BufferTooSmall { needed: 8, got: 7 });
+        let actual = header.write_to(&mut buf);
+        assert_eq!(expect, actual);
+    }
+}
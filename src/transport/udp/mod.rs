@@ -0,0 +1,445 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod header;
+
+use crate::checksum::{Checksum, ChecksumVerification};
+use crate::error::{Error, Layer, ensure_capacity};
+use crate::network::ip_protocol::IpProtocol;
+use header::{UDP_HEADER_LEN, UdpHeader};
+
+/// UDP datagram combining header and payload
+///
+/// Reference: RFC 768
+#[derive(Debug, PartialEq)]
+pub struct UdpPacket<'a> {
+    pub header: UdpHeader,
+    pub payload: &'a [u8],
+}
+
+impl<'a> UdpPacket<'a> {
+    pub fn new(source_port: u16, destination_port: u16, payload: &'a [u8]) -> Self {
+        UdpPacket {
+            header: UdpHeader::new(source_port, destination_port),
+            payload,
+        }
+    }
+
+    /// Parse a UDP datagram, borrowing the payload from `bytes`
+    ///
+    /// The length field delimits the payload, so any trailing bytes after the
+    /// datagram are ignored.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = UdpHeader::from_bytes(bytes)?;
+
+        let length = header.length as usize;
+        if length < UDP_HEADER_LEN {
+            return Err(Error::BadTotalLength {
+                layer: Layer::Udp,
+                length,
+            });
+        }
+        if bytes.len() < length {
+            return Err(Error::Truncated {
+                layer: Layer::Udp,
+                needed: length,
+                got: bytes.len(),
+            });
+        }
+
+        let payload = &bytes[UDP_HEADER_LEN..length];
+        Ok(UdpPacket { header, payload })
+    }
+
+    /// Calculate and set the length field (header plus payload)
+    ///
+    /// Fails with [`Error::BadTotalLength`] if the datagram would be longer
+    /// than 65535 bytes.
+    pub fn update_length(&mut self) -> Result<(), Error> {
+        self.header.length = checked_length(self.serialized_len())?;
+        Ok(())
+    }
+
+    /// Calculate UDP checksum including IPv4 pseudo-header
+    ///
+    /// A computed checksum of zero is returned as all ones, since zero means
+    /// "no checksum" on the wire (RFC 768).
+    pub fn calculate_checksum_ipv4(&self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> u16 {
+        let mut checksum = Checksum::new();
+
+        // IPv4 pseudo-header
+        checksum.add_ipv4_pseudo_header(src_ip, dst_ip, IpProtocol::Udp, self.header.length);
+
+        self.add_datagram_to_checksum(&mut checksum);
+        substitute_zero(checksum.finish())
+    }
+
+    /// Calculate UDP checksum including IPv6 pseudo-header
+    ///
+    /// A computed checksum of zero is returned as all ones; the checksum is
+    /// mandatory over IPv6 (RFC 8200 Section 8.1). With a routing header,
+    /// `dst_ip` is the final destination; see
+    /// [`Ipv6Packet::final_destination`](crate::network::ipv6::Ipv6Packet::final_destination).
+    pub fn calculate_checksum_ipv6(&self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> u16 {
+        let mut checksum = Checksum::new();
+
+        // IPv6 pseudo-header
+        checksum.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Udp, self.header.length as u32);
+
+        self.add_datagram_to_checksum(&mut checksum);
+        substitute_zero(checksum.finish())
+    }
+
+    /// Add header (with checksum field set to 0) and payload
+    fn add_datagram_to_checksum(&self, checksum: &mut Checksum) {
+        checksum.add_u16(self.header.source_port);
+        checksum.add_u16(self.header.destination_port);
+        checksum.add_u16(self.header.length);
+        checksum.add_u16(0); // Checksum field
+        checksum.add_bytes(self.payload);
+    }
+
+    /// Verify the stored checksum using the IPv4 pseudo-header
+    ///
+    /// A stored value of zero means the sender did not compute a checksum,
+    /// which is permitted over IPv4 (RFC 768). It never verifies, so check
+    /// `stored` before rejecting the datagram.
+    pub fn verify_checksum_ipv4(&self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> ChecksumVerification {
+        ChecksumVerification {
+            stored: self.header.checksum,
+            expected: self.calculate_checksum_ipv4(src_ip, dst_ip),
+        }
+    }
+
+    /// Verify the stored checksum using the IPv6 pseudo-header
+    ///
+    /// A stored value of zero never verifies, since the checksum is mandatory
+    /// over IPv6 (RFC 8200 Section 8.1).
+    pub fn verify_checksum_ipv6(&self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> ChecksumVerification {
+        ChecksumVerification {
+            stored: self.header.checksum,
+            expected: self.calculate_checksum_ipv6(src_ip, dst_ip),
+        }
+    }
+
+    /// Length of the serialized datagram in bytes (header and payload)
+    pub fn serialized_len(&self) -> usize {
+        self.header.serialized_len() + self.payload.len()
+    }
+
+    /// Write header and payload with the derived length and a zero checksum
+    fn write_datagram(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total_len = self.serialized_len();
+        let length = checked_length(total_len)?;
+        ensure_capacity(buf, total_len)?;
+
+        let header_len = self.header.write_to(buf)?;
+        buf[header_len..total_len].copy_from_slice(self.payload);
+
+        // Bytes 4-5: Length, Bytes 6-7: Checksum cleared
+        buf[4..6].copy_from_slice(&length.to_be_bytes());
+        buf[6..8].fill(0);
+
+        Ok(total_len)
+    }
+
+    /// Serialize the complete datagram into `buf`, returning the number of bytes written
+    ///
+    /// The length and checksum written to `buf` are calculated from the payload
+    /// and IPv4 pseudo-header; unlike [`UdpPacket::to_bytes_ipv4`], the fields
+    /// in `self.header` are left unchanged. A datagram longer than 65535 bytes
+    /// fails with [`Error::BadTotalLength`].
+    pub fn write_to_ipv4(
+        &self,
+        buf: &mut [u8],
+        src_ip: [u8; 4],
+        dst_ip: [u8; 4],
+    ) -> Result<usize, Error> {
+        let len = self.write_datagram(buf)?;
+
+        let mut checksum = Checksum::new();
+        checksum.add_ipv4_pseudo_header(src_ip, dst_ip, IpProtocol::Udp, len as u16);
+        checksum.add_bytes(&buf[..len]);
+        buf[6..8].copy_from_slice(&substitute_zero(checksum.finish()).to_be_bytes());
+
+        Ok(len)
+    }
+
+    /// Serialize the complete datagram into `buf`, returning the number of bytes written
+    ///
+    /// Same as [`UdpPacket::write_to_ipv4`] but with the IPv6 pseudo-header.
+    pub fn write_to_ipv6(
+        &self,
+        buf: &mut [u8],
+        src_ip: [u8; 16],
+        dst_ip: [u8; 16],
+    ) -> Result<usize, Error> {
+        let len = self.write_datagram(buf)?;
+
+        let mut checksum = Checksum::new();
+        checksum.add_ipv6_pseudo_header(src_ip, dst_ip, IpProtocol::Udp, len as u32);
+        checksum.add_bytes(&buf[..len]);
+        buf[6..8].copy_from_slice(&substitute_zero(checksum.finish()).to_be_bytes());
+
+        Ok(len)
+    }
+
+    /// Update length and checksum, then serialize the complete datagram
+    ///
+    /// Fails, like [`UdpPacket::write_to_ipv4`], if the datagram is too long.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes_ipv4(&mut self, src_ip: [u8; 4], dst_ip: [u8; 4]) -> Result<Vec<u8>, Error> {
+        self.update_length()?;
+        self.header.checksum = self.calculate_checksum_ipv4(src_ip, dst_ip);
+
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to_ipv4(&mut bytes, src_ip, dst_ip)?;
+        Ok(bytes)
+    }
+
+    /// Update length and checksum, then serialize the complete datagram
+    ///
+    /// Fails, like [`UdpPacket::write_to_ipv4`], if the datagram is too long.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes_ipv6(&mut self, src_ip: [u8; 16], dst_ip: [u8; 16]) -> Result<Vec<u8>, Error> {
+        self.update_length()?;
+        self.header.checksum = self.calculate_checksum_ipv6(src_ip, dst_ip);
+
+        let mut bytes = alloc::vec![0; self.serialized_len()];
+        self.write_to_ipv6(&mut bytes, src_ip, dst_ip)?;
+        Ok(bytes)
+    }
+}
+
+/// Convert a datagram length to the 16-bit Length field
+fn checked_length(length: usize) -> Result<u16, Error> {
+    u16::try_from(length).map_err(|_| Error::BadTotalLength {
+        layer: Layer::Udp,
+        length,
+    })
+}
+
+/// Transmit a computed checksum of zero as all ones (RFC 768)
+fn substitute_zero(checksum: u16) -> u16 {
+    if checksum == 0 { 0xFFFF } else { checksum }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC_IPV6: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01,
+    ];
+    const DST_IPV6: [u8; 16] = [
+        0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02,
+    ];
+
+    #[test]
+    fn test_udp_packet_new() {
+        let payload = b"hello";
+        let actual = UdpPacket::new(53, 12345, payload);
+
+        assert_eq!(53, actual.header.source_port);
+        assert_eq!(12345, actual.header.destination_port);
+        assert_eq!(payload, actual.payload);
+    }
+
+    #[test]
+    fn test_udp_packet_update_length() {
+        let mut packet = UdpPacket::new(53, 12345, b"hello");
+        packet.update_length().unwrap();
+        assert_eq!(13, packet.header.length);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_packet_to_bytes_ipv4() {
+        let mut packet = UdpPacket::new(53, 12345, b"hello");
+        let actual = packet
+            .to_bytes_ipv4([192, 168, 1, 1], [192, 168, 1, 2])
+            .unwrap();
+
+        let expect = alloc::vec![
+            0x00, 0x35, // Source port: 53
+            0x30, 0x39, // Destination port: 12345
+            0x00, 0x0d, // Length: 13
+            0x08, 0x40, // Checksum
+            b'h', b'e', b'l', b'l', b'o',
+        ];
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_packet_to_bytes_ipv6() {
+        let mut packet = UdpPacket::new(53, 12345, b"hello");
+        let actual = packet.to_bytes_ipv6(SRC_IPV6, DST_IPV6).unwrap();
+
+        assert_eq!(13, actual.len());
+        assert_eq!([0x30, 0x1f], actual[6..8]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_checksum_zero_sent_as_all_ones_ipv4() {
+        // Payload chosen so the one's complement sum is 0xFFFF
+        let payload = [b'a', b'b', 0x7e, 0xb9];
+        let mut packet = UdpPacket::new(1000, 2000, &payload);
+        let src_ip = [10, 0, 0, 1];
+        let dst_ip = [10, 0, 0, 2];
+
+        let bytes = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+        assert_eq!(0xFFFF, packet.header.checksum);
+        assert_eq!([0xFF, 0xFF], bytes[6..8]);
+        assert!(packet.verify_checksum_ipv4(src_ip, dst_ip).is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_checksum_zero_sent_as_all_ones_ipv6() {
+        let mut src_ip = [0u8; 16];
+        let mut dst_ip = [0u8; 16];
+        src_ip[15] = 1;
+        dst_ip[15] = 2;
+        let payload = [b'a', b'b', 0x92, 0xb9];
+        let mut packet = UdpPacket::new(1000, 2000, &payload);
+
+        let bytes = packet.to_bytes_ipv6(src_ip, dst_ip).unwrap();
+        assert_eq!([0xFF, 0xFF], bytes[6..8]);
+        assert!(packet.verify_checksum_ipv6(src_ip, dst_ip).is_valid());
+    }
+
+    #[test]
+    fn test_udp_verify_checksum_ipv4_not_computed() {
+        let bytes = [
+            0x00, 0x35, 0x30, 0x39, 0x00, 0x0d, 0x00, 0x00, b'h', b'e', b'l', b'l', b'o',
+        ];
+        let packet = UdpPacket::from_bytes(&bytes).unwrap();
+
+        let actual = packet.verify_checksum_ipv4([192, 168, 1, 1], [192, 168, 1, 2]);
+        assert_eq!(0, actual.stored);
+        assert_eq!(0x0840, actual.expected);
+        assert!(!actual.is_valid());
+    }
+
+    #[test]
+    fn test_udp_verify_checksum_ipv6_zero_is_invalid() {
+        let bytes = [
+            0x00, 0x35, 0x30, 0x39, 0x00, 0x0d, 0x00, 0x00, b'h', b'e', b'l', b'l', b'o',
+        ];
+        let packet = UdpPacket::from_bytes(&bytes).unwrap();
+
+        let actual = packet.verify_checksum_ipv6(SRC_IPV6, DST_IPV6);
+        assert_eq!(0x301f, actual.expected);
+        assert!(!actual.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_verify_checksum_ipv4_corrupted() {
+        let src_ip = [192, 168, 1, 1];
+        let dst_ip = [192, 168, 1, 2];
+        let mut bytes = UdpPacket::new(53, 12345, b"hello")
+            .to_bytes_ipv4(src_ip, dst_ip)
+            .unwrap();
+        bytes[8] = b'j';
+
+        let packet = UdpPacket::from_bytes(&bytes).unwrap();
+        let actual = packet.verify_checksum_ipv4(src_ip, dst_ip);
+        assert!(!actual.is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_packet_from_bytes_round_trip() {
+        let mut expect = UdpPacket::new(5353, 5353, b"query");
+        let bytes = expect.to_bytes_ipv6(SRC_IPV6, DST_IPV6).unwrap();
+
+        let actual = UdpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_udp_packet_from_bytes_ignores_trailer() {
+        let bytes = [
+            0x00, 0x35, 0x30, 0x39, 0x00, 0x0a, 0x00, 0x00, b'h', b'i', 0, 0, 0,
+        ];
+        let actual = UdpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(b"hi", actual.payload);
+    }
+
+    #[test]
+    fn test_udp_packet_from_bytes_length_too_small() {
+        let bytes = [0x00, 0x35, 0x30, 0x39, 0x00, 0x07, 0x00, 0x00];
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Udp,
+            length: 7,
+        });
+        let actual = UdpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    fn test_udp_packet_from_bytes_length_exceeds_buffer() {
+        let bytes = [0x00, 0x35, 0x30, 0x39, 0x00, 0x10, 0x00, 0x00, b'h', b'i'];
+        let expect = Err(Error::Truncated {
+            layer: Layer::Udp,
+            needed: 16,
+            got: 10,
+        });
+        let actual = UdpPacket::from_bytes(&bytes);
+        assert_eq!(expect, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_packet_write_to_ipv4() {
+        let mut packet = UdpPacket::new(53, 12345, b"hello");
+        let src_ip = [192, 168, 1, 1];
+        let dst_ip = [192, 168, 1, 2];
+
+        let mut buf = [0u8; 32];
+        let len = packet.write_to_ipv4(&mut buf, src_ip, dst_ip).unwrap();
+        assert_eq!(packet.serialized_len(), len);
+
+        let expect = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+        assert_eq!(expect[..], buf[..len]);
+
+        let expect = Err(Error::BufferTooSmall {
+            needed: 13,
+            got: 12,
+        });
+        let actual = packet.write_to_ipv4(&mut buf[..12], src_ip, dst_ip);
+        assert_eq!(expect, actual);
+    }
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_udp_packet_too_long() {
+        let payload = alloc::vec![0; 65528];
+        let mut packet = UdpPacket::new(53, 12345, &payload);
+        let src_ip = [192, 168, 1, 1];
+        let dst_ip = [192, 168, 1, 2];
+
+        let expect = Err(Error::BadTotalLength {
+            layer: Layer::Udp,
+            length: 65536,
+        });
+        let mut buf = alloc::vec![0; packet.serialized_len()];
+        assert_eq!(expect, packet.write_to_ipv4(&mut buf, src_ip, dst_ip));
+        assert_eq!(expect, packet.write_to_ipv6(&mut buf, SRC_IPV6, DST_IPV6));
+        assert_eq!(
+            expect,
+            packet
+                .to_bytes_ipv4(src_ip, dst_ip)
+                .map(|bytes| bytes.len())
+        );
+
+        // One byte less is the longest datagram the length field can express
+        let mut packet = UdpPacket::new(53, 12345, &payload[1..]);
+        let bytes = packet.to_bytes_ipv6(SRC_IPV6, DST_IPV6).unwrap();
+        assert_eq!([0xFF, 0xFF], bytes[4..6]);
+    }
+}
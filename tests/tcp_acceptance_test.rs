@@ -1,3 +1,5 @@
+#![cfg(feature = "alloc")]
+
 use packet_builder::transport::tcp::{TcpPacket, header::TcpFlags, options::TcpOption};
 
 #[test]
@@ -40,15 +42,15 @@ fn test_rfc9293_acceptance_criteria() {
     // Test all three options: EOL, NOP, MSS
     let eol_option = TcpOption::EndOfOptionList;
     assert_eq!(0, eol_option.kind());
-    assert_eq!(vec![0], eol_option.to_bytes());
+    assert_eq!(vec![0], eol_option.to_bytes().unwrap());
 
     let nop_option = TcpOption::NoOperation;
     assert_eq!(1, nop_option.kind());
-    assert_eq!(vec![1], nop_option.to_bytes());
+    assert_eq!(vec![1], nop_option.to_bytes().unwrap());
 
     let mss_option = TcpOption::MaximumSegmentSize(1460);
     assert_eq!(2, mss_option.kind());
-    assert_eq!(vec![2, 4, 0x05, 0xb4], mss_option.to_bytes());
+    assert_eq!(vec![2, 4, 0x05, 0xb4], mss_option.to_bytes().unwrap());
 
     // Test SYN+MSS scenario matching expected byte sequence
     let payload = b"";
@@ -62,7 +64,7 @@ fn test_rfc9293_acceptance_criteria() {
     // Generate packet for IPv4
     let src_ip = [192, 168, 1, 100];
     let dst_ip = [192, 168, 1, 1];
-    let packet_bytes = packet.to_bytes_ipv4(src_ip, dst_ip);
+    let packet_bytes = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
 
     // Verify correct header size and data_offset calculation
     assert_eq!(24, packet_bytes.len()); // 20-byte header + 4-byte option
@@ -94,7 +96,9 @@ fn test_rfc9293_acceptance_criteria() {
         0x02,
     ];
 
-    let checksum_ipv6 = packet_ipv6.calculate_checksum_ipv6(src_ipv6, dst_ipv6);
+    let checksum_ipv6 = packet_ipv6
+        .calculate_checksum_ipv6(src_ipv6, dst_ipv6)
+        .unwrap();
     assert_ne!(0, checksum_ipv6);
 }
 
@@ -0,0 +1,164 @@
+#![cfg(feature = "alloc")]
+
+use packet_builder::transport::tcp::{
+    TcpPacket,
+    header::TcpFlags,
+    options::{MAX_OPTIONS_LEN, TcpOption},
+};
+
+/// Minimal xorshift PRNG so the property tests stay dependency-free and reproducible
+struct XorShift(u64);
+
+impl XorShift {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u16(&mut self) -> u16 {
+        self.next_u64() as u16
+    }
+
+    fn next_u8(&mut self) -> u8 {
+        self.next_u64() as u8
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next_u8()).collect()
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+const ITERATIONS: usize = 1000;
+
+fn random_option(rng: &mut XorShift) -> TcpOption {
+    match rng.below(11) {
+        0 => TcpOption::NoOperation,
+        1 => TcpOption::MaximumSegmentSize(rng.next_u16()),
+        2 => TcpOption::WindowScale(rng.next_u8()),
+        3 => TcpOption::SackPermitted,
+        4 => {
+            let blocks = 1 + rng.below(4);
+            TcpOption::Sack(
+                (0..blocks)
+                    .map(|_| (rng.next_u32(), rng.next_u32()))
+                    .collect(),
+            )
+        }
+        5 => TcpOption::Timestamps {
+            value: rng.next_u32(),
+            echo_reply: rng.next_u32(),
+        },
+        6 => TcpOption::UserTimeout {
+            granularity: rng.below(2) == 1,
+            timeout: rng.next_u16() & 0x7FFF,
+        },
+        7 => {
+            let len = [0, 4, 8, 16][rng.below(4)];
+            TcpOption::FastOpenCookie(rng.bytes(len))
+        }
+        8 => TcpOption::EndOfOptionList,
+        9 => {
+            let len = rng.below(8);
+            TcpOption::Experimental {
+                kind: 253 + rng.below(2) as u8,
+                exid: rng.next_u16(),
+                data: rng.bytes(len),
+            }
+        }
+        _ => {
+            // Kinds without a dedicated variant
+            let len = rng.below(8);
+            TcpOption::Unknown {
+                kind: [6, 7, 29, 30, 69, 200][rng.below(6)],
+                data: rng.bytes(len),
+            }
+        }
+    }
+}
+
+fn random_packet<'a>(rng: &mut XorShift, payload: &'a [u8]) -> TcpPacket<'a> {
+    let mut packet = TcpPacket::new(rng.next_u16(), rng.next_u16(), payload);
+    packet.header.sequence_number = rng.next_u32();
+    packet.header.acknowledgment_number = rng.next_u32();
+    packet.header.flags = TcpFlags::from_u16(rng.next_u16() & 0x0FFF);
+    packet.header.window = rng.next_u16();
+    packet.header.urgent_pointer = rng.next_u16();
+
+    for _ in 0..rng.below(12) {
+        let option = random_option(rng);
+        if packet.options.total_length() + option.length() > MAX_OPTIONS_LEN {
+            break;
+        }
+        packet.options.add(option);
+    }
+
+    packet
+}
+
+fn random_payload(rng: &mut XorShift) -> Vec<u8> {
+    let len = rng.below(128);
+    (0..len).map(|_| rng.next_u64() as u8).collect()
+}
+
+#[test]
+fn test_tcp_round_trip_ipv4() {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+
+    for _ in 0..ITERATIONS {
+        let payload = random_payload(&mut rng);
+        let mut expect = random_packet(&mut rng, &payload);
+        let src_ip = rng.next_u32().to_be_bytes();
+        let dst_ip = rng.next_u32().to_be_bytes();
+
+        let bytes = expect.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+        let actual = TcpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+}
+
+#[test]
+fn test_tcp_round_trip_ipv6() {
+    let mut rng = XorShift(0xD1B5_4A32_D192_ED03);
+
+    for _ in 0..ITERATIONS {
+        let payload = random_payload(&mut rng);
+        let mut expect = random_packet(&mut rng, &payload);
+        let mut src_ip = [0u8; 16];
+        let mut dst_ip = [0u8; 16];
+        src_ip[..8].copy_from_slice(&rng.next_u64().to_be_bytes());
+        dst_ip[8..].copy_from_slice(&rng.next_u64().to_be_bytes());
+
+        let bytes = expect.to_bytes_ipv6(src_ip, dst_ip).unwrap();
+        let actual = TcpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(expect, actual);
+    }
+}
+
+#[test]
+fn test_tcp_round_trip_reserializes_identically() {
+    let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+
+    for _ in 0..ITERATIONS {
+        let payload = random_payload(&mut rng);
+        let mut packet = random_packet(&mut rng, &payload);
+        let src_ip = [192, 0, 2, 1];
+        let dst_ip = [198, 51, 100, 2];
+
+        let expect = packet.to_bytes_ipv4(src_ip, dst_ip).unwrap();
+        let actual = TcpPacket::from_bytes(&expect)
+            .unwrap()
+            .to_bytes_ipv4(src_ip, dst_ip)
+            .unwrap();
+        assert_eq!(expect, actual);
+    }
+}